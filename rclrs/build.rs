@@ -27,11 +27,13 @@ fn main() {
         .allowlist_type("rcl_.*")
         .allowlist_type("rmw_.*")
         .allowlist_type("rcutils_.*")
+        .allowlist_type("rosidl_typesupport_introspection_c__.*")
         .allowlist_function("rcl_.*")
         .allowlist_function("rmw_.*")
         .allowlist_function("rcutils_.*")
         .allowlist_var("rcl_.*")
         .allowlist_var("rmw_.*")
+        .allowlist_var("RMW_.*")
         .allowlist_var("rcutils_.*")
         .layout_tests(false)
         .size_t_is_usize(true)
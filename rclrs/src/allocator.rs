@@ -0,0 +1,69 @@
+use crate::rcl_bindings::*;
+
+pub use crate::rcl_bindings::rcutils_allocator_t;
+
+// rcutils_allocator_t is a plain struct of function pointers and a state pointer, so copying it
+// is fine. bindgen does not derive Clone, since that is disabled for all types.
+impl Clone for rcutils_allocator_t {
+    fn clone(&self) -> Self {
+        Self {
+            allocate: self.allocate,
+            deallocate: self.deallocate,
+            reallocate: self.reallocate,
+            zero_allocate: self.zero_allocate,
+            state: self.state,
+        }
+    }
+}
+
+/// Returns the default allocator of `rcutils`, which uses the system allocator.
+pub(crate) fn default_allocator() -> rcutils_allocator_t {
+    // SAFETY: No preconditions for this function.
+    unsafe { rcutils_get_default_allocator() }
+}
+
+/// Returns the allocator that the context was initialized with.
+///
+/// Falls back to the default allocator for a context that has not been initialized.
+pub(crate) fn context_allocator(context: &rcl_context_t) -> rcutils_allocator_t {
+    // SAFETY: No preconditions for this function besides a valid pointer.
+    let init_options = unsafe { rcl_context_get_init_options(context) };
+    if init_options.is_null() {
+        return default_allocator();
+    }
+    // SAFETY: The init options are owned by the context, which is borrowed.
+    let allocator = unsafe { rcl_init_options_get_allocator(init_options) };
+    if allocator.is_null() {
+        return default_allocator();
+    }
+    // SAFETY: The allocator is owned by the context, and is copied out.
+    unsafe { (*allocator).clone() }
+}
+
+/// Returns the allocator that the node was created with.
+pub(crate) fn node_allocator(node: &rcl_node_t) -> rcutils_allocator_t {
+    // SAFETY: No preconditions for this function besides a valid pointer.
+    let options = unsafe { rcl_node_get_options(node) };
+    if options.is_null() {
+        return default_allocator();
+    }
+    // SAFETY: The options are owned by the node, which is borrowed. The allocator is copied out.
+    unsafe { (*options).allocator.clone() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_allocator_is_valid() {
+        let allocator = default_allocator();
+        assert!(allocator.allocate.is_some());
+        assert!(allocator.deallocate.is_some());
+        assert!(allocator.reallocate.is_some());
+        assert!(allocator.zero_allocate.is_some());
+        let copy = allocator.clone();
+        assert!(copy.allocate.is_some());
+        assert_eq!(copy.state, allocator.state);
+    }
+}
@@ -0,0 +1,146 @@
+use parking_lot::Mutex;
+
+/// The concurrency rule of a [`CallbackGroup`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CallbackGroupType {
+    /// The callbacks in the group never run at the same time.
+    MutuallyExclusive,
+    /// The callbacks in the group may run at the same time, including several runs of the same
+    /// callback.
+    Reentrant,
+}
+
+/// A group of callbacks whose concurrency is controlled together.
+///
+/// This is the equivalent of `rclcpp::CallbackGroup`. Callback groups only matter for an
+/// [`Executor`][1] with more than one thread, since a single thread runs all callbacks one after
+/// another anyway. Subscriptions and timers that are not explicitly put into a group belong to
+/// the mutually exclusive default group of their node.
+///
+/// Note that the callback of a [`Subscription`][2] is behind a mutex, so a reentrant group still
+/// does not run the same subscription callback twice at the same time.
+///
+/// # Example
+/// ```
+/// # use rclrs::{CallbackGroupType, Context, RclrsError};
+/// # use std::time::Duration;
+/// let context = Context::new([])?;
+/// let mut node = context.create_node("my_node")?;
+/// let group = node.create_callback_group(CallbackGroupType::MutuallyExclusive);
+/// // These two callbacks never overlap, even in a multi-threaded executor
+/// let _first = node.create_timer_in_group(Duration::from_millis(10), &group, || {})?;
+/// let _second = node.create_timer_in_group(Duration::from_millis(10), &group, || {})?;
+/// # Ok::<(), RclrsError>(())
+/// ```
+///
+/// [1]: crate::Executor
+/// [2]: crate::Subscription
+#[derive(Debug)]
+pub struct CallbackGroup {
+    group_type: CallbackGroupType,
+    // Held while a callback of a mutually exclusive group runs.
+    running: Mutex<()>,
+}
+
+// Groups are compared by identity, since two groups with the same type still constrain different
+// callbacks.
+impl PartialEq for CallbackGroup {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+impl Eq for CallbackGroup {}
+
+impl CallbackGroup {
+    /// Creates a callback group with the given concurrency rule.
+    ///
+    /// Groups are usually created with [`Node::create_callback_group()`][1].
+    ///
+    /// [1]: crate::Node::create_callback_group
+    pub fn new(group_type: CallbackGroupType) -> Self {
+        Self {
+            group_type,
+            running: Mutex::new(()),
+        }
+    }
+
+    /// Returns the concurrency rule of this group.
+    pub fn group_type(&self) -> CallbackGroupType {
+        self.group_type
+    }
+
+    // Runs a callback of this group, waiting for any other callback of a mutually exclusive group
+    // to finish first.
+    pub(crate) fn run<R>(&self, callback: impl FnOnce() -> R) -> R {
+        match self.group_type {
+            CallbackGroupType::MutuallyExclusive => {
+                let _running = self.running.lock();
+                callback()
+            }
+            CallbackGroupType::Reentrant => callback(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Context, Executor, RclrsError, RclrsErrorFilter};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    // Runs the callbacks of two timers in a group of the given type on a multi-threaded executor,
+    // and returns how many of them were running at the same time at most.
+    fn max_concurrent_callbacks(group_type: CallbackGroupType) -> Result<usize, RclrsError> {
+        let context = Context::new([])?;
+        let mut node = context.create_node("callback_group_test_node")?;
+        let group = node.create_callback_group(group_type);
+        let running = Arc::new(AtomicUsize::new(0));
+        let max_running = Arc::new(AtomicUsize::new(0));
+        let finished = Arc::new(AtomicUsize::new(0));
+        let callback = || {
+            let running = running.clone();
+            let max_running = max_running.clone();
+            let finished = finished.clone();
+            move || {
+                let now_running = running.fetch_add(1, Ordering::SeqCst) + 1;
+                max_running.fetch_max(now_running, Ordering::SeqCst);
+                std::thread::sleep(Duration::from_millis(50));
+                running.fetch_sub(1, Ordering::SeqCst);
+                finished.fetch_add(1, Ordering::SeqCst);
+            }
+        };
+        let _first = node.create_timer_in_group(Duration::from_millis(1), &group, callback())?;
+        let _second = node.create_timer_in_group(Duration::from_millis(1), &group, callback())?;
+        // Both timers are ready when the executor waits for the first time
+        std::thread::sleep(Duration::from_millis(20));
+
+        let mut executor = Executor::with_threads(2);
+        executor.add_node(&node)?;
+        let start = Instant::now();
+        while finished.load(Ordering::SeqCst) < 2 {
+            assert!(start.elapsed() < Duration::from_secs(5));
+            executor
+                .spin_once(Some(Duration::from_millis(100)))
+                .timeout_ok()?;
+        }
+        Ok(max_running.load(Ordering::SeqCst))
+    }
+
+    #[test]
+    fn mutually_exclusive_callbacks_never_overlap() -> Result<(), RclrsError> {
+        assert_eq!(
+            max_concurrent_callbacks(CallbackGroupType::MutuallyExclusive)?,
+            1
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn reentrant_callbacks_run_in_parallel() -> Result<(), RclrsError> {
+        assert_eq!(max_concurrent_callbacks(CallbackGroupType::Reentrant)?, 2);
+        Ok(())
+    }
+}
@@ -0,0 +1,330 @@
+use crate::allocator::default_allocator;
+use crate::rcl_bindings::*;
+use crate::{RclrsError, Time, ToResult};
+
+use std::os::raw::c_void;
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::{Condvar, Mutex};
+
+impl Drop for rcl_clock_t {
+    fn drop(&mut self) {
+        // SAFETY: No preconditions for this function (besides a valid handle)
+        unsafe { rcl_clock_fini(self) };
+    }
+}
+
+// SAFETY: The functions accessing this type, including drop(), shouldn't care about the thread
+// they are running in. Therefore, this type can be safely sent to another thread.
+unsafe impl Send for rcl_clock_t {}
+
+/// The source of time of a [`Clock`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ClockType {
+    /// Time that is normally the same as the system time, but can be overridden with
+    /// simulated time, e.g. when replaying recorded data.
+    RosTime,
+    /// The wall-clock time of the system, which may jump when the system time is adjusted.
+    SystemTime,
+    /// A monotonic time which never jumps backwards, and is unrelated to the wall-clock time.
+    SteadyTime,
+}
+
+impl From<ClockType> for rcl_clock_type_t {
+    fn from(clock_type: ClockType) -> Self {
+        match clock_type {
+            ClockType::RosTime => rcl_clock_type_t::RCL_ROS_TIME,
+            ClockType::SystemTime => rcl_clock_type_t::RCL_SYSTEM_TIME,
+            ClockType::SteadyTime => rcl_clock_type_t::RCL_STEADY_TIME,
+        }
+    }
+}
+
+/// A clock for reading the current [`Time`].
+///
+/// Clones of a clock share the same underlying `rcl` clock.
+#[derive(Clone)]
+pub struct Clock {
+    pub(crate) rcl_clock: Arc<Mutex<rcl_clock_t>>,
+    clock_type: ClockType,
+}
+
+impl Clock {
+    /// Creates a new clock of the given type.
+    pub fn new(clock_type: ClockType) -> Result<Self, RclrsError> {
+        let mut allocator = default_allocator();
+        // SAFETY: There is no zero-initialization function for clocks, but a zeroed clock is only
+        // used as an output argument of rcl_clock_init().
+        let mut rcl_clock: rcl_clock_t = unsafe { std::mem::zeroed() };
+        // SAFETY: The clock is only used as an output argument, and the allocator is copied.
+        unsafe {
+            rcl_clock_init(clock_type.into(), &mut rcl_clock, &mut allocator)
+                .ok_with_context("rcl_clock_init")?
+        };
+        Ok(Self {
+            rcl_clock: Arc::new(Mutex::new(rcl_clock)),
+            clock_type,
+        })
+    }
+
+    /// Returns the type of the clock.
+    pub fn clock_type(&self) -> ClockType {
+        self.clock_type
+    }
+
+    /// Returns the current time of the clock.
+    ///
+    /// # Example
+    /// ```
+    /// # use rclrs::{Clock, ClockType, RclrsError};
+    /// let clock = Clock::new(ClockType::SteadyTime)?;
+    /// let before = clock.now();
+    /// let after = clock.now();
+    /// assert!(after >= before);
+    /// # Ok::<(), RclrsError>(())
+    /// ```
+    pub fn now(&self) -> Time {
+        let mut nsec = 0;
+        let rcl_clock = &mut *self.rcl_clock.lock();
+        // SAFETY: The clock is initialized, and the time point is a valid output argument. This
+        // can only fail for invalid arguments.
+        unsafe { rcl_clock_get_now(rcl_clock, &mut nsec).ok().unwrap() };
+        Time {
+            nsec,
+            clock_type: self.clock_type,
+        }
+    }
+
+    /// Overrides the time of a ROS clock, e.g. with the simulation time received on `/clock`.
+    ///
+    /// With `Some(nsec)`, the clock reports `nsec` nanoseconds from then on, until the override
+    /// is changed again. With `None`, the override is disabled, and the clock follows the system
+    /// time again.
+    ///
+    /// This returns an error for clocks that are not of type [`ClockType::RosTime`].
+    ///
+    /// # Example
+    /// ```
+    /// # use rclrs::{Clock, ClockType, RclrsError};
+    /// let clock = Clock::new(ClockType::RosTime)?;
+    /// clock.set_ros_time_override(Some(1_000))?;
+    /// assert_eq!(clock.now().nsec, 1_000);
+    /// clock.set_ros_time_override(None)?;
+    /// assert!(clock.now().nsec > 1_000);
+    /// # Ok::<(), RclrsError>(())
+    /// ```
+    pub fn set_ros_time_override(&self, nsec: Option<i64>) -> Result<(), RclrsError> {
+        let rcl_clock = &mut *self.rcl_clock.lock();
+        // SAFETY: The clock is initialized. These functions have no other preconditions.
+        unsafe {
+            match nsec {
+                Some(nsec) => {
+                    rcl_enable_ros_time_override(rcl_clock).ok()?;
+                    rcl_set_ros_time_override(rcl_clock, nsec).ok()
+                }
+                None => rcl_disable_ros_time_override(rcl_clock).ok(),
+            }
+        }
+    }
+
+    /// Sleeps for the given duration, as measured by this clock.
+    ///
+    /// See [`sleep_until()`][1].
+    ///
+    /// [1]: Clock::sleep_until
+    pub fn sleep_for(&self, duration: Duration) -> Result<(), RclrsError> {
+        let nsec = i64::try_from(duration.as_nanos()).unwrap_or(i64::MAX);
+        let now = self.now();
+        self.sleep_until(Time {
+            nsec: now.nsec.saturating_add(nsec),
+            clock_type: now.clock_type,
+        })
+    }
+
+    /// Sleeps until the clock has reached the given time.
+    ///
+    /// For system and steady clocks, and ROS clocks without a [time override][1], this sleeps
+    /// like [`std::thread::sleep()`], but also takes jumps of the system time into account.
+    ///
+    /// For a ROS clock with a time override, such as simulation time, this waits until the
+    /// overridden time has been advanced far enough, no matter how much wall-clock time passes.
+    /// When the time jumps backwards, e.g. because a recording is replayed from the start again,
+    /// this keeps waiting until the clock reaches the given time again.
+    ///
+    /// # Panics
+    /// When `until` is from a different type of clock.
+    ///
+    /// [1]: Clock::set_ros_time_override
+    pub fn sleep_until(&self, until: Time) -> Result<(), RclrsError> {
+        assert_eq!(
+            until.clock_type, self.clock_type,
+            "Can't sleep until a time from a different type of clock"
+        );
+        // Counts the time jumps, so that no jump is missed between checking the time and waiting
+        let jumps = Arc::new((Mutex::new(0u64), Condvar::new()));
+        let user_data = Arc::as_ptr(&jumps) as *mut c_void;
+        {
+            let rcl_clock = &mut *self.rcl_clock.lock();
+            // Get notified about any jump, including the override being enabled or disabled
+            let threshold = rcl_jump_threshold_t {
+                on_clock_change: true,
+                min_forward: rcl_duration_t { nanoseconds: 1 },
+                min_backward: rcl_duration_t { nanoseconds: -1 },
+            };
+            // SAFETY: The clock is initialized. The user data stays valid until the callback is
+            // removed by the guard below, which is dropped before the jump counter.
+            unsafe {
+                rcl_clock_add_jump_callback(rcl_clock, threshold, Some(notify_time_jump), user_data)
+                    .ok()?
+            };
+        }
+        let _callback_guard = JumpCallbackGuard {
+            rcl_clock: &self.rcl_clock,
+            user_data,
+        };
+        loop {
+            // The counter must not be locked while the clock is, since the callback is called
+            // with the clock locked.
+            let previous_jump_count = *jumps.0.lock();
+            let remaining = match until.duration_since(self.now()) {
+                Some(remaining) if !remaining.is_zero() => remaining,
+                _ => break,
+            };
+            // With a time override, the time only advances through jumps. Otherwise, the system
+            // time may still jump without a notification, so wake up regularly in either case.
+            let timeout = if self.ros_time_override_is_enabled() {
+                Duration::from_millis(100)
+            } else {
+                remaining.min(Duration::from_millis(100))
+            };
+            let mut jump_count = jumps.0.lock();
+            if *jump_count == previous_jump_count {
+                jumps.1.wait_for(&mut jump_count, timeout);
+            }
+        }
+        Ok(())
+    }
+
+    // Whether the ROS time override of a ROS clock is enabled.
+    fn ros_time_override_is_enabled(&self) -> bool {
+        if self.clock_type != ClockType::RosTime {
+            return false;
+        }
+        let mut is_enabled = false;
+        let rcl_clock = &mut *self.rcl_clock.lock();
+        // SAFETY: The clock is initialized and of type RosTime. The output argument is valid.
+        unsafe { rcl_is_enabled_ros_time_override(rcl_clock, &mut is_enabled) };
+        is_enabled
+    }
+}
+
+// Removes the jump callback of Clock::sleep_until() when the sleep ends, including by a panic,
+// since the user data of the callback points to a local variable of the sleep.
+struct JumpCallbackGuard<'a> {
+    rcl_clock: &'a Mutex<rcl_clock_t>,
+    user_data: *mut c_void,
+}
+
+impl Drop for JumpCallbackGuard<'_> {
+    fn drop(&mut self) {
+        let rcl_clock = &mut *self.rcl_clock.lock();
+        // SAFETY: The callback has been added with the same user data.
+        let ret = unsafe {
+            rcl_clock_remove_jump_callback(rcl_clock, Some(notify_time_jump), self.user_data).ok()
+        };
+        if let Err(e) = ret {
+            crate::log_error!("rclrs", "Failed to remove a clock jump callback: {}", e);
+        }
+    }
+}
+
+// The jump callback of Clock::sleep_until(), which wakes up the sleeping thread.
+//
+// SAFETY: The user data must point to the jump counter and condition variable of the sleep.
+unsafe extern "C" fn notify_time_jump(
+    _time_jump: *const rcl_time_jump_t,
+    before_jump: bool,
+    user_data: *mut c_void,
+) {
+    if before_jump {
+        return;
+    }
+    let (jump_count, condvar) = &*(user_data as *const (Mutex<u64>, Condvar));
+    *jump_count.lock() += 1;
+    condvar.notify_all();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clocks_report_their_type() -> Result<(), RclrsError> {
+        for clock_type in [
+            ClockType::RosTime,
+            ClockType::SystemTime,
+            ClockType::SteadyTime,
+        ] {
+            let clock = Clock::new(clock_type)?;
+            assert_eq!(clock.clock_type(), clock_type);
+            assert_eq!(clock.now().clock_type, clock_type);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn sleep_for_waits_on_steady_clock() -> Result<(), RclrsError> {
+        let clock = Clock::new(ClockType::SteadyTime)?;
+        let start = std::time::Instant::now();
+        let before = clock.now();
+        clock.sleep_for(Duration::from_millis(50))?;
+        let elapsed = clock.now().duration_since(before).unwrap();
+        assert!(elapsed >= Duration::from_millis(50));
+        assert!(start.elapsed() < Duration::from_millis(500));
+        // Times in the past return immediately
+        clock.sleep_until(before)?;
+        Ok(())
+    }
+
+    #[test]
+    fn sleep_until_follows_overridden_ros_time() -> Result<(), RclrsError> {
+        let clock = Clock::new(ClockType::RosTime)?;
+        clock.set_ros_time_override(Some(5_000_000_000))?;
+        let (finished_sender, finished_receiver) = std::sync::mpsc::channel();
+        let sleeper = std::thread::spawn({
+            let clock = clock.clone();
+            let until = Time {
+                nsec: 11_000_000_000,
+                clock_type: ClockType::RosTime,
+            };
+            move || {
+                let result = clock.sleep_until(until);
+                finished_sender.send(()).unwrap();
+                result
+            }
+        });
+        // The time jumps backwards, then advances in steps, as with simulation time
+        clock.set_ros_time_override(Some(0))?;
+        for secs in 1..=10 {
+            clock.set_ros_time_override(Some(secs * 1_000_000_000))?;
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(finished_receiver.try_recv().is_err());
+        clock.set_ros_time_override(Some(11_000_000_000))?;
+        sleeper.join().unwrap()?;
+        Ok(())
+    }
+
+    #[test]
+    fn steady_time_is_monotonic() -> Result<(), RclrsError> {
+        let clock = Clock::new(ClockType::SteadyTime)?;
+        let mut previous = clock.now();
+        for _ in 0..100 {
+            let current = clock.now();
+            assert!(current >= previous);
+            previous = current;
+        }
+        Ok(())
+    }
+}
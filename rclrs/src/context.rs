@@ -1,7 +1,12 @@
+mod builder;
+pub use self::builder::*;
+
+use crate::allocator::{context_allocator, default_allocator, rcutils_allocator_t};
+use crate::error::{RclErrorCode, RclErrorMsg, RclReturnCode};
 use crate::rcl_bindings::*;
-use crate::{Node, NodeBuilder, RclrsError, ToResult};
+use crate::{ArgumentParseError, Gid, LogSeverity, Node, NodeBuilder, RclrsError, ToResult};
 
-use std::ffi::CString;
+use std::ffi::{CStr, CString};
 use std::os::raw::c_char;
 use std::string::String;
 use std::sync::Arc;
@@ -9,17 +14,21 @@ use std::vec::Vec;
 
 use parking_lot::Mutex;
 
+pub use crate::rcl_bindings::rcl_context_t;
+
 impl Drop for rcl_context_t {
     fn drop(&mut self) {
         unsafe {
             // The context may be invalid when rcl_init failed, e.g. because of invalid command
-            // line arguments.
+            // line arguments, or when it has already been shut down.
             // SAFETY: No preconditions for this function.
             if rcl_context_is_valid(self) {
-                // SAFETY: These functions have no preconditions besides a valid handle
+                // SAFETY: No preconditions for this function besides a valid handle
                 rcl_shutdown(self);
-                rcl_context_fini(self);
             }
+            // SAFETY: The context is shut down. Finalizing a zero-initialized context, which is
+            // what a failed rcl_init() leaves behind, does nothing.
+            rcl_context_fini(self);
         }
     }
 }
@@ -28,6 +37,41 @@ impl Drop for rcl_context_t {
 // they are running in. Therefore, this type can be safely sent to another thread.
 unsafe impl Send for rcl_context_t {}
 
+// The logging system is process-global. It is configured by the first context that asks for it,
+// and finalized when the last of those contexts is dropped. This counts these contexts.
+pub(crate) static LOGGING_USERS: Mutex<usize> = parking_lot::const_mutex(0);
+
+// Held by the tests that configure logging, so that they can check the number of users.
+#[cfg(test)]
+pub(crate) static CONFIGURE_LOGGING_TEST_LOCK: Mutex<()> = parking_lot::const_mutex(());
+
+/// Options for creating a [`Context`].
+///
+/// # Example
+/// ```
+/// # use rclrs::{Context, ContextOptions, LogSeverity, RclrsError};
+/// let options = ContextOptions {
+///     configure_logging: false,
+///     log_level: Some(LogSeverity::Warn),
+/// };
+/// let context = Context::new_with_options([], options)?;
+/// # Ok::<(), RclrsError>(())
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ContextOptions {
+    /// Whether to configure the logging system with `rcl_logging_configure()`.
+    ///
+    /// This sets up the external logging library, which e.g. writes log files, as well as the
+    /// `/rosout` topic. It is disabled by default, in which case log messages are only printed to
+    /// the console.
+    pub configure_logging: bool,
+    /// The default severity threshold for all loggers.
+    ///
+    /// When this is `None`, the threshold is left as is, i.e. it is determined by the
+    /// `--log-level` command line argument if logging is configured.
+    pub log_level: Option<LogSeverity>,
+}
+
 /// Shared state between nodes and similar entities.
 ///
 /// It is possible, but not usually necessary, to have several contexts in an application.
@@ -38,10 +82,37 @@ unsafe impl Send for rcl_context_t {}
 /// A context stores, among other things
 /// - command line arguments (used for e.g. name remapping)
 /// - middleware-specific data, e.g. the domain participant in DDS
-/// - the allocator used (left as the default by `rclrs`)
+/// - the allocator used (the default allocator, unless the context was created with
+///   [`Context::new_with_allocator`])
 ///
 pub struct Context {
-    pub(crate) handle: Arc<Mutex<rcl_context_t>>,
+    pub(crate) handle: Arc<ContextHandle>,
+}
+
+// The state of a context that is shared by the Context and all entities created from it.
+pub(crate) struct ContextHandle {
+    pub(crate) rcl_context: Mutex<rcl_context_t>,
+    // Whether this context has configured the logging system, which must then be finalized
+    // when the last owner of the context is dropped.
+    pub(crate) configured_logging: bool,
+    // The enclave set with ContextBuilder::enclave(), which Context::init() sets again.
+    pub(crate) enclave: Option<String>,
+    // The GIDs of the publishers created in this context, see
+    // SubscriptionOptions::ignore_local_publications.
+    pub(crate) local_publishers: Mutex<Vec<Gid>>,
+}
+
+impl Drop for ContextHandle {
+    fn drop(&mut self) {
+        if self.configured_logging {
+            let mut logging_users = LOGGING_USERS.lock();
+            *logging_users -= 1;
+            if *logging_users == 0 {
+                // SAFETY: The logging system was configured, and no other context uses it anymore.
+                unsafe { rcl_logging_fini() };
+            }
+        }
+    }
 }
 
 impl Context {
@@ -50,7 +121,8 @@ impl Context {
     /// Usually, this would be called with `std::env::args()`, analogously to `rclcpp::init()`.
     /// See also the official "Passing ROS arguments to nodes via the command-line" tutorial.
     ///
-    /// Creating a context can fail in case the args contain invalid ROS arguments.
+    /// Creating a context can fail in case the args contain invalid ROS arguments. The
+    /// [`source`][1] of the error is then an [`ArgumentParseError`] identifying the argument.
     ///
     /// # Example
     /// ```
@@ -60,47 +132,75 @@ impl Context {
     /// assert!(Context::new(invalid_remapping).is_err());
     /// ```
     ///
+    /// [1]: std::error::Error::source
+    ///
     /// # Panics
     /// When there is an interior null byte in any of the args.
     pub fn new(args: impl IntoIterator<Item = String>) -> Result<Self, RclrsError> {
-        // SAFETY: Getting a zero-initialized value is always safe
-        let mut rcl_context = unsafe { rcl_get_zero_initialized_context() };
-        let cstring_args: Vec<CString> = args
-            .into_iter()
-            .map(|arg| CString::new(arg).unwrap())
-            .collect();
-        // Vector of pointers into cstring_args
-        let c_args: Vec<*const c_char> = cstring_args.iter().map(|arg| arg.as_ptr()).collect();
-        unsafe {
-            // SAFETY: No preconditions for this function.
-            let allocator = rcutils_get_default_allocator();
-            // SAFETY: Getting a zero-initialized value is always safe.
-            let mut init_options = rcl_get_zero_initialized_init_options();
-            // SAFETY: Passing in a zero-initialized value is expected.
-            // In the case where this returns not ok, there's nothing to clean up.
-            rcl_init_options_init(&mut init_options, allocator).ok()?;
-            // SAFETY: This function does not store the ephemeral init_options and c_args
-            // pointers. Passing in a zero-initialized handle is expected.
-            let ret = rcl_init(
-                c_args.len() as i32,
-                if c_args.is_empty() {
-                    std::ptr::null()
-                } else {
-                    c_args.as_ptr()
-                },
-                &init_options,
-                &mut rcl_context,
-            )
-            .ok();
-            // SAFETY: It's safe to pass in an initialized object.
-            // Early return will not leak memory, because this is the last fini function.
-            rcl_init_options_fini(&mut init_options).ok()?;
-            // Move the check after the last fini()
-            ret?;
+        Self::new_with_options(args, ContextOptions::default())
+    }
+
+    /// Creates a new context with the given options.
+    ///
+    /// See [`Context::new`] for the meaning of `args`.
+    ///
+    /// # Example
+    /// ```
+    /// # use rclrs::{Context, ContextOptions};
+    /// let options = ContextOptions {
+    ///     configure_logging: true,
+    ///     ..Default::default()
+    /// };
+    /// assert!(Context::new_with_options([], options).is_ok());
+    /// ```
+    ///
+    /// # Panics
+    /// When there is an interior null byte in any of the args.
+    pub fn new_with_options(
+        args: impl IntoIterator<Item = String>,
+        options: ContextOptions,
+    ) -> Result<Self, RclrsError> {
+        let mut builder = Self::builder(args).configure_logging(options.configure_logging);
+        if let Some(log_level) = options.log_level {
+            builder = builder.log_level(log_level);
         }
-        Ok(Self {
-            handle: Arc::new(Mutex::new(rcl_context)),
-        })
+        builder.build()
+    }
+
+    /// Creates a new context which uses the given allocator.
+    ///
+    /// See [`Context::new`] for the meaning of `args`.
+    ///
+    /// The allocator is used by `rcl` for the context itself, and is inherited by the nodes,
+    /// publishers, subscriptions and other entities that are created from the context. This makes
+    /// it possible to e.g. use a pool allocator in real-time systems instead of `malloc()`.
+    ///
+    /// # Panics
+    /// When there is an interior null byte in any of the args.
+    pub fn new_with_allocator(
+        args: impl IntoIterator<Item = String>,
+        allocator: rcutils_allocator_t,
+    ) -> Result<Self, RclrsError> {
+        Self::builder(args).allocator(allocator).build()
+    }
+
+    /// Creates a [`ContextBuilder`][1] with the given args.
+    ///
+    /// Convenience function equivalent to [`ContextBuilder::new()`][2].
+    /// Please see that function's documentation.
+    ///
+    /// [1]: crate::ContextBuilder
+    /// [2]: crate::ContextBuilder::new
+    ///
+    /// # Example
+    /// ```
+    /// # use rclrs::{Context, RclrsError};
+    /// let context = Context::builder([]).configure_logging(true).build()?;
+    /// assert!(context.ok());
+    /// # Ok::<(), RclrsError>(())
+    /// ```
+    pub fn builder(args: impl IntoIterator<Item = String>) -> ContextBuilder {
+        ContextBuilder::new(args)
     }
 
     /// Creates a new node in the empty namespace.
@@ -146,13 +246,618 @@ impl Context {
 
     /// Checks if the context is still valid.
     ///
-    /// This will return `false` when a signal has caused the context to shut down (currently
-    /// unimplemented).
+    /// This will return `false` after [`shutdown()`][1], or when a signal has caused the context
+    /// to shut down (currently unimplemented).
+    ///
+    /// [1]: Context::shutdown
     pub fn ok(&self) -> bool {
-        // This will currently always return true, but once we have a signal handler, the signal
-        // handler could call `rcl_shutdown()`, hence making the context invalid.
-        let handle = &mut *self.handle.lock();
+        // Once we have a signal handler, the signal handler could also call `rcl_shutdown()`,
+        // hence making the context invalid.
+        let handle = &mut *self.handle.rcl_context.lock();
         // SAFETY: No preconditions for this function.
         unsafe { rcl_context_is_valid(handle) }
     }
+
+    /// Shuts down the context.
+    ///
+    /// Afterwards, [`ok()`][1] returns `false`, and the entities created from this context stop
+    /// working. The context can be brought back with [`init()`][2].
+    ///
+    /// Shutting down a context that is already shut down returns an error with the
+    /// [`AlreadyShutdown`][3] code.
+    ///
+    /// [1]: Context::ok
+    /// [2]: Context::init
+    /// [3]: crate::RclErrorCode::AlreadyShutdown
+    pub fn shutdown(&self) -> Result<(), RclrsError> {
+        let handle = &mut *self.handle.rcl_context.lock();
+        // SAFETY: No preconditions for this function.
+        if !unsafe { rcl_context_is_valid(handle) } {
+            return Err(RclrsError {
+                code: RclReturnCode::RclError(RclErrorCode::AlreadyShutdown),
+                msg: None,
+            });
+        }
+        // SAFETY: The context is valid.
+        unsafe { rcl_shutdown(handle).ok() }
+    }
+
+    /// Initializes a context again after it has been shut down.
+    ///
+    /// See [`Context::new`] for the meaning of `args`. The allocator, domain ID, logging options
+    /// and the enclave set with [`ContextBuilder::enclave()`][3] are kept as they are. Entities
+    /// that were created before the shutdown are not revived, and need to be created again.
+    ///
+    /// Initializing a context that has not been shut down returns an error with the
+    /// [`AlreadyInit`][1] code. Since entities keep using the context they were created from,
+    /// the context can also only be initialized again once all of them have been dropped.
+    /// Otherwise, an error with the [`Error`][2] code is returned.
+    ///
+    /// # Example
+    /// ```
+    /// # use rclrs::{Context, RclrsError};
+    /// let mut context = Context::new([])?;
+    /// assert!(context.init([]).is_err());
+    /// let node = context.create_node("my_node")?;
+    /// context.shutdown()?;
+    /// assert!(!context.ok());
+    /// // The node still uses the context
+    /// assert!(context.init([]).is_err());
+    /// drop(node);
+    /// context.init([])?;
+    /// assert!(context.ok());
+    /// # Ok::<(), RclrsError>(())
+    /// ```
+    ///
+    /// [1]: crate::RclErrorCode::AlreadyInit
+    /// [2]: crate::RclReturnCode::Error
+    /// [3]: crate::ContextBuilder::enclave
+    ///
+    /// # Panics
+    /// When there is an interior null byte in any of the args.
+    pub fn init(&mut self, args: impl IntoIterator<Item = String>) -> Result<(), RclrsError> {
+        // SAFETY: No preconditions for this function.
+        if unsafe { rcl_context_is_valid(&*self.handle.rcl_context.lock()) } {
+            return Err(RclrsError {
+                code: RclReturnCode::RclError(RclErrorCode::AlreadyInit),
+                msg: None,
+            });
+        }
+        // Replacing the rcl context while entities still refer to it would leave them with a
+        // finalized context.
+        let (handle, enclave) = match Arc::get_mut(&mut self.handle) {
+            Some(handle) => (handle.rcl_context.get_mut(), handle.enclave.as_deref()),
+            None => {
+                return Err(RclrsError {
+                    code: RclReturnCode::Error,
+                    msg: Some(RclErrorMsg::Context {
+                        context: String::from("Context::init"),
+                        message: Some(String::from(
+                            "the context is still used by entities created from it",
+                        )),
+                    }),
+                })
+            }
+        };
+        let allocator = context_allocator(handle);
+        #[cfg(ros_distro = "foxy")]
+        let domain_id = None;
+        #[cfg(not(ros_distro = "foxy"))]
+        let domain_id = Some(domain_id_of(handle));
+        // Dropping the old context finalizes it
+        *handle = init_rcl_context(args, allocator, domain_id, enclave)?;
+        Ok(())
+    }
+
+    /// Runs a function with the underlying `rcl_context_t`, for interoperating with other
+    /// libraries that use `rcl` directly.
+    ///
+    /// The context is locked while the function runs, so the function must not use this context
+    /// in any other way, e.g. by creating nodes from it, or it will deadlock.
+    ///
+    /// # Safety
+    /// The handle must not be invalidated, i.e. the function must not call `rcl_context_fini()`,
+    /// overwrite the handle, or move it out. The handle must not be used after the function has
+    /// returned. Calling `rcl_shutdown()` is allowed, but [`shutdown()`][1] should be preferred.
+    ///
+    /// # Example
+    /// ```
+    /// # use rclrs::{rcl_context_t, Context, RclrsError};
+    /// extern "C" {
+    ///     fn rcl_context_is_valid(context: *const rcl_context_t) -> bool;
+    /// }
+    ///
+    /// let context = Context::new([])?;
+    /// // SAFETY: rcl_context_is_valid() does not store the pointer.
+    /// let is_valid = unsafe { context.with_rcl_handle(|handle| rcl_context_is_valid(handle)) };
+    /// assert!(is_valid);
+    /// # Ok::<(), RclrsError>(())
+    /// ```
+    ///
+    /// [1]: Context::shutdown
+    pub unsafe fn with_rcl_handle<R>(&self, f: impl FnOnce(&mut rcl_context_t) -> R) -> R {
+        f(&mut self.handle.rcl_context.lock())
+    }
+
+    /// Returns the identifier of the RMW implementation that is in use.
+    ///
+    /// This is e.g. `"rmw_fastrtps_cpp"` or `"rmw_cyclonedds_cpp"`, depending on the ROS
+    /// installation and the `RMW_IMPLEMENTATION` environment variable.
+    ///
+    /// # Example
+    /// ```
+    /// # use rclrs::{Context, RclrsError};
+    /// let context = Context::new([])?;
+    /// assert!(context.rmw_implementation_identifier().starts_with("rmw_"));
+    /// # Ok::<(), RclrsError>(())
+    /// ```
+    pub fn rmw_implementation_identifier(&self) -> &'static str {
+        // SAFETY: No preconditions for this function.
+        let identifier = unsafe { rmw_get_implementation_identifier() };
+        // SAFETY: The identifier is a static string owned by the RMW implementation.
+        unsafe { static_str_from_ptr(identifier) }
+    }
+
+    /// Returns the serialization format used by the RMW implementation, e.g. `"cdr"`.
+    pub fn rmw_serialization_format(&self) -> &'static str {
+        // SAFETY: No preconditions for this function.
+        let format = unsafe { rmw_get_serialization_format() };
+        // SAFETY: The format is a static string owned by the RMW implementation.
+        unsafe { static_str_from_ptr(format) }
+    }
+
+    /// Returns the domain ID of the context.
+    ///
+    /// This is the domain ID set with [`ContextBuilder::domain_id()`][1], or else the one from
+    /// the `ROS_DOMAIN_ID` environment variable.
+    ///
+    /// [1]: crate::ContextBuilder::domain_id
+    #[cfg(not(ros_distro = "foxy"))]
+    pub fn domain_id(&self) -> usize {
+        domain_id_of(&self.handle.rcl_context.lock())
+    }
+
+    /// Returns the security enclave of the context.
+    ///
+    /// This is the enclave set with [`ContextBuilder::enclave()`][1] or the `--enclave` argument,
+    /// or else `"/"`.
+    ///
+    /// [1]: crate::ContextBuilder::enclave
+    pub fn enclave(&self) -> String {
+        let handle = &*self.handle.rcl_context.lock();
+        rmw_init_options_of(handle)
+            // SAFETY: The enclave is null or a null-terminated string owned by the init options.
+            .and_then(|options| unsafe { owned_string_from_ptr(options.enclave) })
+            .unwrap_or_default()
+    }
+
+    /// Returns the directory with the security artifacts of the enclave, or `None` if security
+    /// is not enabled.
+    ///
+    /// Security is enabled with the `ROS_SECURITY_ENABLE` environment variable when the context is
+    /// initialized. The artifacts are looked up in the `enclaves` directory of the keystore given
+    /// by `ROS_SECURITY_KEYSTORE`, under the path of the [`enclave()`][1]. If they are missing and
+    /// `ROS_SECURITY_STRATEGY` is `Enforce`, creating the context fails with an error whose
+    /// [`source()`][2] names the missing directory. Otherwise, the context is created without
+    /// security.
+    ///
+    /// [1]: Context::enclave
+    /// [2]: std::error::Error::source
+    pub fn security_root_path(&self) -> Option<String> {
+        let handle = &*self.handle.rcl_context.lock();
+        rmw_init_options_of(handle)
+            // SAFETY: The path is null or a null-terminated string owned by the init options.
+            .and_then(|options| unsafe {
+                owned_string_from_ptr(options.security_options.security_root_path)
+            })
+    }
+}
+
+// Helper for the getters of Context that read the RMW init options.
+fn rmw_init_options_of(handle: &rcl_context_t) -> Option<&rmw_init_options_t> {
+    // SAFETY: The init options are owned by the context, which is borrowed. They are valid
+    // until the context is finalized, even after a shutdown.
+    let init_options = unsafe { rcl_context_get_init_options(handle) };
+    if init_options.is_null() {
+        return None;
+    }
+    // SAFETY: The init options are valid, and only read through the returned pointer.
+    let rmw_init_options = unsafe { rcl_init_options_get_rmw_init_options(init_options as *mut _) };
+    // SAFETY: The pointer is null or valid as long as the context is borrowed.
+    unsafe { rmw_init_options.as_ref() }
+}
+
+// Helper for the getters of Context.
+//
+// SAFETY: The pointer must be null or point to a null-terminated string.
+unsafe fn owned_string_from_ptr(ptr: *const c_char) -> Option<String> {
+    if ptr.is_null() {
+        return None;
+    }
+    Some(CStr::from_ptr(ptr).to_string_lossy().into_owned())
+}
+
+// Helper for Context::domain_id() and Context::init()
+#[cfg(not(ros_distro = "foxy"))]
+fn domain_id_of(handle: &rcl_context_t) -> usize {
+    let mut domain_id = 0;
+    // SAFETY: The init options are owned by the context. They are valid until the context is
+    // finalized, even after a shutdown. rcl_init() has replaced the default domain ID with the
+    // actual one.
+    unsafe {
+        let init_options = rcl_context_get_init_options(handle);
+        rcl_init_options_get_domain_id(init_options, &mut domain_id);
+    }
+    domain_id
+}
+
+// Helper for ContextBuilder::build() and Context::init()
+#[cfg_attr(ros_distro = "foxy", allow(unused_variables))]
+fn init_rcl_context(
+    args: impl IntoIterator<Item = String>,
+    allocator: rcutils_allocator_t,
+    domain_id: Option<usize>,
+    enclave: Option<&str>,
+) -> Result<rcl_context_t, RclrsError> {
+    // SAFETY: Getting a zero-initialized value is always safe
+    let mut rcl_context = unsafe { rcl_get_zero_initialized_context() };
+    let mut args: Vec<String> = args.into_iter().collect();
+    // rcl only reads the enclave from the arguments. The last occurrence takes precedence.
+    if let Some(enclave) = enclave {
+        args.extend(["--ros-args", "--enclave", enclave].map(String::from));
+    }
+    let cstring_args: Vec<CString> = args
+        .into_iter()
+        .map(|arg| CString::new(arg).unwrap())
+        .collect();
+    // Vector of pointers into cstring_args
+    let c_args: Vec<*const c_char> = cstring_args.iter().map(|arg| arg.as_ptr()).collect();
+    validate_arguments(&cstring_args, &c_args)?;
+    unsafe {
+        // SAFETY: Getting a zero-initialized value is always safe.
+        let mut init_options = rcl_get_zero_initialized_init_options();
+        // SAFETY: Passing in a zero-initialized value is expected.
+        // In the case where this returns not ok, there's nothing to clean up.
+        rcl_init_options_init(&mut init_options, allocator).ok()?;
+        #[cfg(not(ros_distro = "foxy"))]
+        if let Some(domain_id) = domain_id {
+            // SAFETY: The init options are initialized.
+            if let Err(e) = rcl_init_options_set_domain_id(&mut init_options, domain_id).ok() {
+                rcl_init_options_fini(&mut init_options).ok()?;
+                return Err(e);
+            }
+        }
+        // SAFETY: This function does not store the ephemeral init_options and c_args
+        // pointers. Passing in a zero-initialized handle is expected.
+        let ret = rcl_init(
+            c_args.len() as i32,
+            if c_args.is_empty() {
+                std::ptr::null()
+            } else {
+                c_args.as_ptr()
+            },
+            &init_options,
+            &mut rcl_context,
+        )
+        .ok_with_context("rcl_init");
+        // SAFETY: It's safe to pass in an initialized object.
+        // Early return will not leak memory, because this is the last fini function.
+        rcl_init_options_fini(&mut init_options).ok()?;
+        // Move the check after the last fini()
+        ret?;
+    }
+    Ok(rcl_context)
+}
+
+// Helper for the rmw_* getters of Context.
+//
+// SAFETY: The pointer must be null or point to a static, null-terminated string.
+unsafe fn static_str_from_ptr(ptr: *const c_char) -> &'static str {
+    if ptr.is_null() {
+        return "";
+    }
+    CStr::from_ptr(ptr).to_str().unwrap_or("")
+}
+
+// Helper for validate_arguments()
+fn parse_arguments(c_args: &[*const c_char]) -> Result<(), RclrsError> {
+    // SAFETY: Getting a zero-initialized value is always safe.
+    let mut rcl_arguments = unsafe { rcl_get_zero_initialized_arguments() };
+    // SAFETY: The c_args pointers are valid and not stored. Passing in a zero-initialized value is
+    // expected. On failure, rcl cleans up the arguments itself.
+    unsafe {
+        rcl_parse_arguments(
+            c_args.len() as i32,
+            if c_args.is_empty() {
+                std::ptr::null()
+            } else {
+                c_args.as_ptr()
+            },
+            default_allocator(),
+            &mut rcl_arguments,
+        )
+        .ok()?;
+        // SAFETY: The arguments were successfully initialized.
+        rcl_arguments_fini(&mut rcl_arguments).ok()
+    }
+}
+
+// Parses the arguments before rcl_init() does, to find out which argument is invalid.
+//
+// rcl only reports that parsing failed, so the offending argument is located by parsing
+// increasingly long prefixes of the arguments.
+fn validate_arguments(args: &[CString], c_args: &[*const c_char]) -> Result<(), RclrsError> {
+    let err = match parse_arguments(c_args) {
+        Ok(()) => return Ok(()),
+        Err(err) => err,
+    };
+    for end in 1..=c_args.len() {
+        let prefix_err = match parse_arguments(&c_args[..end]) {
+            Ok(()) => continue,
+            Err(prefix_err) => prefix_err,
+        };
+        let argument = args[end - 1].to_string_lossy().into_owned();
+        // A flag like "-r" is only invalid here because its value was cut off.
+        if end < c_args.len() && argument.starts_with('-') {
+            continue;
+        }
+        let reason = match prefix_err.msg {
+            Some(msg) => msg.to_string(),
+            None => prefix_err.code.to_string(),
+        };
+        return Err(RclrsError {
+            code: err.code,
+            msg: Some(RclErrorMsg::ArgumentParse(ArgumentParseError {
+                argument,
+                position: end - 1,
+                reason,
+            })),
+        });
+    }
+    Err(err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::raw::{c_int, c_void};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn invalid_argument_is_reported() {
+        let args = ["my_program", "--ros-args", "-r", ":=:*/]"].map(String::from);
+        let err = Context::new(args).err().unwrap();
+        let parse_err = std::error::Error::source(&err)
+            .and_then(|source| source.downcast_ref::<ArgumentParseError>())
+            .unwrap();
+        assert_eq!(parse_err.argument, ":=:*/]");
+        assert_eq!(parse_err.position, 3);
+    }
+
+    #[test]
+    fn double_init_is_an_error() -> Result<(), RclrsError> {
+        let mut context = Context::new([])?;
+        let err = context.init([]).unwrap_err();
+        assert_eq!(err.code, RclReturnCode::RclError(RclErrorCode::AlreadyInit));
+        assert!(context.ok());
+        Ok(())
+    }
+
+    #[test]
+    fn context_can_be_reinitialized_after_shutdown() -> Result<(), RclrsError> {
+        let mut context = Context::new([])?;
+        let node = context.create_node("shut_down_context_node")?;
+        context.shutdown()?;
+        assert!(!context.ok());
+        let err = context.shutdown().unwrap_err();
+        assert_eq!(
+            err.code,
+            RclReturnCode::RclError(RclErrorCode::AlreadyShutdown)
+        );
+        let err = context.init([]).unwrap_err();
+        assert_eq!(err.code, RclReturnCode::Error);
+        drop(node);
+        context.init([])?;
+        assert!(context.ok());
+        let node = context.create_node("reinitialized_context_node")?;
+        assert_eq!(node.name(), "reinitialized_context_node");
+        Ok(())
+    }
+
+    static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+    unsafe extern "C" fn counting_allocate(size: usize, _state: *mut c_void) -> *mut c_void {
+        ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        libc::malloc(size)
+    }
+
+    unsafe extern "C" fn counting_deallocate(pointer: *mut c_void, _state: *mut c_void) {
+        libc::free(pointer)
+    }
+
+    unsafe extern "C" fn counting_reallocate(
+        pointer: *mut c_void,
+        size: usize,
+        _state: *mut c_void,
+    ) -> *mut c_void {
+        ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        libc::realloc(pointer, size)
+    }
+
+    unsafe extern "C" fn counting_zero_allocate(
+        number_of_elements: usize,
+        size_of_element: usize,
+        _state: *mut c_void,
+    ) -> *mut c_void {
+        ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        libc::calloc(number_of_elements, size_of_element)
+    }
+
+    #[test]
+    fn custom_allocator_is_used() -> Result<(), RclrsError> {
+        let allocator = rcutils_allocator_t {
+            allocate: Some(counting_allocate),
+            deallocate: Some(counting_deallocate),
+            reallocate: Some(counting_reallocate),
+            zero_allocate: Some(counting_zero_allocate),
+            state: std::ptr::null_mut(),
+        };
+        let context = Context::new_with_allocator([], allocator)?;
+        let after_context = ALLOCATIONS.load(Ordering::SeqCst);
+        assert!(after_context > 0);
+        let _node = context.create_node("custom_allocator_node")?;
+        assert!(ALLOCATIONS.load(Ordering::SeqCst) > after_context);
+        Ok(())
+    }
+
+    #[test]
+    fn rcl_handle_is_accessible() -> Result<(), RclrsError> {
+        let context = Context::new([])?;
+        // SAFETY: The handle is only passed to a function that doesn't store it.
+        let is_valid = unsafe { context.with_rcl_handle(|handle| rcl_context_is_valid(handle)) };
+        assert!(is_valid);
+        context.shutdown()?;
+        // SAFETY: Same as above.
+        let is_valid = unsafe { context.with_rcl_handle(|handle| rcl_context_is_valid(handle)) };
+        assert!(!is_valid);
+        Ok(())
+    }
+
+    #[test]
+    fn rmw_implementation_is_reported() -> Result<(), RclrsError> {
+        let context = Context::new([])?;
+        assert!(!context.rmw_implementation_identifier().is_empty());
+        assert!(!context.rmw_serialization_format().is_empty());
+        Ok(())
+    }
+
+    // The number of messages of the "rclrs_context_test" logger seen by the output handler below,
+    // at Info and at Error severity.
+    static INFO_MESSAGES: AtomicUsize = AtomicUsize::new(0);
+    static ERROR_MESSAGES: AtomicUsize = AtomicUsize::new(0);
+
+    unsafe extern "C" fn counting_output_handler(
+        _location: *const rcutils_log_location_t,
+        severity: c_int,
+        name: *const c_char,
+        _timestamp: rcutils_time_point_value_t,
+        _format: *const c_char,
+        _args: *mut va_list,
+    ) {
+        if CStr::from_ptr(name).to_bytes() != b"rclrs_context_test" {
+            return;
+        }
+        if severity == LogSeverity::Info.to_native() as c_int {
+            INFO_MESSAGES.fetch_add(1, Ordering::SeqCst);
+        } else if severity == LogSeverity::Error.to_native() as c_int {
+            ERROR_MESSAGES.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn context_without_logging() -> Result<(), RclrsError> {
+        // Prevents contexts created by other tests from configuring logging, which would replace
+        // the output handler and the default log level.
+        let _logging_users = LOGGING_USERS.lock();
+        // Initializes the logging system, which would replace the output handler otherwise.
+        crate::log_debug!("rclrs_context_test_init", "Initializing logging");
+        // SAFETY: The handler has the correct signature and is valid for the whole program.
+        // The other functions have no preconditions.
+        let (previous_handler, previous_level) = unsafe {
+            let previous_handler = rcutils_logging_get_output_handler();
+            rcutils_logging_set_output_handler(Some(counting_output_handler));
+            (previous_handler, rcutils_logging_get_default_logger_level())
+        };
+
+        let options = ContextOptions {
+            configure_logging: false,
+            log_level: Some(LogSeverity::Error),
+        };
+        let context = Context::new_with_options([], options)?;
+        assert!(context.ok());
+        assert!(!context.handle.configured_logging);
+        // The output handler has not been replaced by the one of rcl, so the messages only reach
+        // this handler, and the log level is applied.
+        crate::log_info!("rclrs_context_test", "Below the log level");
+        crate::log_error!("rclrs_context_test", "At the log level");
+
+        // SAFETY: Restoring the handler and log level that were there before.
+        unsafe {
+            rcutils_logging_set_output_handler(previous_handler);
+            rcutils_logging_set_default_logger_level(previous_level);
+        }
+        assert_eq!(INFO_MESSAGES.load(Ordering::SeqCst), 0);
+        assert_eq!(ERROR_MESSAGES.load(Ordering::SeqCst), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn context_builder_sets_init_options() -> Result<(), RclrsError> {
+        let builder = Context::builder(["--ros-args", "-e", "/other"].map(String::from))
+            .configure_logging(false)
+            .enclave("/my/enclave");
+        #[cfg(not(ros_distro = "foxy"))]
+        let builder = builder.domain_id(42);
+        #[cfg_attr(ros_distro = "foxy", allow(unused_mut))]
+        let mut context = builder.build()?;
+        assert_eq!(context.enclave(), "/my/enclave");
+        #[cfg(not(ros_distro = "foxy"))]
+        {
+            assert_eq!(context.domain_id(), 42);
+            context.shutdown()?;
+            context.init([])?;
+            assert_eq!(context.domain_id(), 42);
+            assert_eq!(context.enclave(), "/my/enclave");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn missing_security_artifacts_are_reported() -> Result<(), RclrsError> {
+        // Setting up a keystore would affect all other tests, since the security settings are
+        // taken from the environment. So this only checks the errors when the tests are run with
+        // enforced security.
+        let env_is = |name, value| std::env::var(name).as_deref() == Ok(value);
+        if !env_is("ROS_SECURITY_ENABLE", "true") {
+            assert_eq!(Context::new([])?.security_root_path(), None);
+            return Ok(());
+        }
+        if !env_is("ROS_SECURITY_STRATEGY", "Enforce") {
+            return Ok(());
+        }
+        let err = Context::builder([])
+            .configure_logging(false)
+            .enclave("/rclrs_missing_enclave")
+            .build()
+            .err()
+            .unwrap();
+        let msg = std::error::Error::source(&err).unwrap().to_string();
+        assert!(msg.contains("rclrs_missing_enclave"), "{}", msg);
+        Ok(())
+    }
+
+    #[test]
+    fn default_enclave_is_root() -> Result<(), RclrsError> {
+        let context = Context::new([])?;
+        assert_eq!(context.enclave(), "/");
+        Ok(())
+    }
+
+    #[test]
+    fn logging_is_finalized_by_the_last_owner() -> Result<(), RclrsError> {
+        // The other tests that configure logging are excluded, so the number of users is exact.
+        let _lock = CONFIGURE_LOGGING_TEST_LOCK.lock();
+        let build = || Context::builder([]).configure_logging(true).build();
+        let context_1 = build()?;
+        let context_2 = build()?;
+        assert_eq!(*LOGGING_USERS.lock(), 2);
+        let node = context_1.create_node("logging_owner_test_node")?;
+        drop(context_1);
+        drop(context_2);
+        // The node still owns its context, which keeps the logging system alive
+        assert_eq!(*LOGGING_USERS.lock(), 1);
+        drop(node);
+        assert_eq!(*LOGGING_USERS.lock(), 0);
+        Ok(())
+    }
 }
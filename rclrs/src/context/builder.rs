@@ -0,0 +1,155 @@
+use super::{init_rcl_context, ContextHandle, LOGGING_USERS};
+use crate::allocator::{default_allocator, rcutils_allocator_t};
+use crate::rcl_bindings::*;
+use crate::{Context, LogSeverity, RclrsError, ToResult};
+
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+/// A builder for creating a [`Context`][1].
+///
+/// The builder pattern allows selectively setting some fields, and leaving all others at their default values.
+/// This struct instance can be created via [`Context::builder()`][2].
+///
+/// The default values for optional fields are:
+/// - `configure_logging: false`
+/// - `log_level: None`
+/// - `allocator`: the default allocator
+/// - `domain_id: None`, i.e. taken from the `ROS_DOMAIN_ID` environment variable
+/// - `enclave: None`, i.e. taken from the args, or `"/"`
+///
+/// Unlike `rclcpp::InitOptions`, this does not give access to the `impl` field of the RMW init
+/// options. Its contents are specific to the RMW implementation, and rcl does not let it be set
+/// before initialization.
+///
+/// # Example
+/// ```
+/// # use rclrs::{Context, RclrsError};
+/// let context = Context::builder([])
+///     .configure_logging(false)
+///     .enclave("/my/enclave")
+///     .build()?;
+/// assert_eq!(context.enclave(), "/my/enclave");
+/// # Ok::<(), RclrsError>(())
+/// ```
+///
+/// [1]: crate::Context
+/// [2]: crate::Context::builder
+///
+pub struct ContextBuilder {
+    arguments: Vec<String>,
+    configure_logging: bool,
+    log_level: Option<LogSeverity>,
+    allocator: rcutils_allocator_t,
+    domain_id: Option<usize>,
+    enclave: Option<String>,
+}
+
+impl ContextBuilder {
+    /// Creates a builder for a context with the given command line arguments.
+    ///
+    /// See [`Context::new`][1] for the meaning of `args`.
+    ///
+    /// [1]: crate::Context::new
+    pub fn new(args: impl IntoIterator<Item = String>) -> ContextBuilder {
+        ContextBuilder {
+            arguments: args.into_iter().collect(),
+            configure_logging: false,
+            log_level: None,
+            allocator: default_allocator(),
+            domain_id: None,
+            enclave: None,
+        }
+    }
+
+    /// Sets whether to configure the logging system.
+    ///
+    /// See [`ContextOptions::configure_logging`][1].
+    ///
+    /// [1]: crate::ContextOptions::configure_logging
+    pub fn configure_logging(mut self, configure_logging: bool) -> Self {
+        self.configure_logging = configure_logging;
+        self
+    }
+
+    /// Sets the default severity threshold for all loggers.
+    ///
+    /// See [`ContextOptions::log_level`][1].
+    ///
+    /// [1]: crate::ContextOptions::log_level
+    pub fn log_level(mut self, log_level: LogSeverity) -> Self {
+        self.log_level = Some(log_level);
+        self
+    }
+
+    /// Sets the allocator of the context.
+    ///
+    /// See [`Context::new_with_allocator`][1].
+    ///
+    /// [1]: crate::Context::new_with_allocator
+    pub fn allocator(mut self, allocator: rcutils_allocator_t) -> Self {
+        self.allocator = allocator;
+        self
+    }
+
+    /// Sets the domain ID, overriding the `ROS_DOMAIN_ID` environment variable.
+    ///
+    /// Only nodes in the same domain can discover each other.
+    #[cfg(not(ros_distro = "foxy"))]
+    pub fn domain_id(mut self, domain_id: usize) -> Self {
+        self.domain_id = Some(domain_id);
+        self
+    }
+
+    /// Sets the security enclave, overriding an `--enclave` (`-e`) argument in the args.
+    ///
+    /// The enclave determines which security artifacts are used when security is enabled with
+    /// the `ROS_SECURITY_*` environment variables. See [`Context::security_root_path()`][1].
+    ///
+    /// [1]: crate::Context::security_root_path
+    pub fn enclave(mut self, enclave: &str) -> Self {
+        self.enclave = Some(enclave.to_string());
+        self
+    }
+
+    /// Builds the context instance.
+    ///
+    /// Creating a context can fail in case the args contain invalid ROS arguments, see
+    /// [`Context::new`][1].
+    ///
+    /// [1]: crate::Context::new
+    ///
+    /// # Panics
+    /// When there is an interior null byte in any of the args or the enclave.
+    pub fn build(&self) -> Result<Context, RclrsError> {
+        let rcl_context = init_rcl_context(
+            self.arguments.clone(),
+            self.allocator.clone(),
+            self.domain_id,
+            self.enclave.as_deref(),
+        )?;
+        if self.configure_logging {
+            let mut logging_users = LOGGING_USERS.lock();
+            if *logging_users == 0 {
+                let allocator = default_allocator();
+                // SAFETY: The global arguments of the context are initialized, and not stored by
+                // this function. The logging system is protected by the LOGGING_USERS mutex.
+                unsafe { rcl_logging_configure(&rcl_context.global_arguments, &allocator) }.ok()?;
+            }
+            *logging_users += 1;
+        }
+        if let Some(log_level) = self.log_level {
+            // SAFETY: No preconditions for this function.
+            unsafe { rcutils_logging_set_default_logger_level(log_level.to_native() as i32) };
+        }
+        Ok(Context {
+            handle: Arc::new(ContextHandle {
+                rcl_context: Mutex::new(rcl_context),
+                configured_logging: self.configure_logging,
+                enclave: self.enclave.clone(),
+                local_publishers: Mutex::new(Vec::new()),
+            }),
+        })
+    }
+}
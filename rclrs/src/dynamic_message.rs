@@ -0,0 +1,270 @@
+mod printer;
+
+use crate::rcl_bindings::*;
+
+use std::error::Error;
+use std::ffi::{CStr, CString};
+use std::fmt::{self, Display};
+use std::os::raw::c_void;
+
+use rosidl_runtime_rs::{Message, RmwMessage};
+
+/// An error related to resolving a message type at runtime.
+#[derive(Debug, PartialEq, Eq)]
+pub enum DynamicMessageError {
+    /// The message type name is not of the form `package_name/msg/TypeName`.
+    InvalidMessageTypeSyntax {
+        /// The message type name that was passed in.
+        input: String,
+    },
+    /// The type support library of the package could not be loaded.
+    ///
+    /// This usually means that the package is not installed, or that the workspace containing it
+    /// has not been sourced.
+    LibraryLoadingError {
+        /// The name of the library that was attempted to be loaded.
+        library: String,
+        /// The reason reported by the dynamic linker.
+        reason: String,
+    },
+    /// The package does not contain a message type with this name.
+    InvalidMessageType {
+        /// The message type name that was passed in.
+        input: String,
+    },
+}
+
+impl Display for DynamicMessageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidMessageTypeSyntax { input } => write!(
+                f,
+                "The message type '{}' is not of the form 'package_name/msg/TypeName'.",
+                input
+            ),
+            Self::LibraryLoadingError { library, reason } => {
+                write!(f, "Could not load the library '{}': {}", library, reason)
+            }
+            Self::InvalidMessageType { input } => {
+                write!(f, "The message type '{}' does not exist.", input)
+            }
+        }
+    }
+}
+
+impl Error for DynamicMessageError {}
+
+/// The introspection type support of a message type that is only known at runtime.
+///
+/// This is obtained from the `rosidl_typesupport_introspection_c` library of the package that
+/// defines the message, which is loaded dynamically. The library stays loaded for as long as this
+/// struct exists.
+///
+/// # Example
+/// ```
+/// # use rclrs::{DynamicMessageError, MessageTypeSupport};
+/// assert!(MessageTypeSupport::new("test_msgs/msg/BasicTypes").is_ok());
+/// assert!(matches!(
+///     MessageTypeSupport::new("test_msgs/BasicTypes"),
+///     Err(DynamicMessageError::InvalidMessageTypeSyntax { .. })
+/// ));
+/// ```
+pub struct MessageTypeSupport {
+    handle: *const rosidl_message_type_support_t,
+    // The handle points into this library, so it must not be unloaded before the handle is dropped.
+    library: *mut c_void,
+}
+
+impl Drop for MessageTypeSupport {
+    fn drop(&mut self) {
+        // SAFETY: The library handle is valid, and nothing that points into the library outlives
+        // this struct.
+        unsafe { libc::dlclose(self.library) };
+    }
+}
+
+// SAFETY: The type support is immutable static data, and loading/unloading libraries is
+// thread-safe.
+unsafe impl Send for MessageTypeSupport {}
+// SAFETY: See above.
+unsafe impl Sync for MessageTypeSupport {}
+
+impl MessageTypeSupport {
+    /// Loads the type support for the given message type.
+    ///
+    /// The message type must be given in the form `package_name/msg/TypeName`,
+    /// e.g. `"std_msgs/msg/String"`.
+    pub fn new(full_message_type: &str) -> Result<Self, DynamicMessageError> {
+        let invalid_syntax = || DynamicMessageError::InvalidMessageTypeSyntax {
+            input: full_message_type.to_owned(),
+        };
+        let parts: Vec<&str> = full_message_type.split('/').collect();
+        let (package_name, subfolder, type_name) = match parts[..] {
+            [package_name, subfolder, type_name] => (package_name, subfolder, type_name),
+            _ => return Err(invalid_syntax()),
+        };
+        let is_identifier =
+            |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !(is_identifier(package_name) && is_identifier(subfolder) && is_identifier(type_name)) {
+            return Err(invalid_syntax());
+        }
+
+        let library_name = format!(
+            "{}{}__rosidl_typesupport_introspection_c{}",
+            std::env::consts::DLL_PREFIX,
+            package_name,
+            std::env::consts::DLL_SUFFIX
+        );
+        // The validation above guarantees that there are no null bytes.
+        let library_name_c = CString::new(library_name.as_str()).unwrap();
+        // SAFETY: No preconditions for this function.
+        let library = unsafe { libc::dlopen(library_name_c.as_ptr(), libc::RTLD_NOW) };
+        if library.is_null() {
+            return Err(DynamicMessageError::LibraryLoadingError {
+                library: library_name,
+                reason: last_dl_error(),
+            });
+        }
+
+        let symbol_name = CString::new(format!(
+            "rosidl_typesupport_introspection_c__get_message_type_support_handle__{}__{}__{}",
+            package_name, subfolder, type_name
+        ))
+        .unwrap();
+        // SAFETY: The library handle is valid.
+        let symbol = unsafe { libc::dlsym(library, symbol_name.as_ptr()) };
+        if symbol.is_null() {
+            // SAFETY: The library handle is valid and not used anymore.
+            unsafe { libc::dlclose(library) };
+            return Err(DynamicMessageError::InvalidMessageType {
+                input: full_message_type.to_owned(),
+            });
+        }
+        // SAFETY: The symbol is a function generated by rosidl_typesupport_introspection_c with
+        // this signature.
+        let get_type_support: unsafe extern "C" fn() -> *const rosidl_message_type_support_t =
+            unsafe { std::mem::transmute(symbol) };
+        // SAFETY: No preconditions for this function.
+        let handle = unsafe { get_type_support() };
+        Ok(Self { handle, library })
+    }
+
+    /// Returns a pointer to the type support struct.
+    ///
+    /// The pointer is valid for as long as this struct exists.
+    pub fn handle(&self) -> *const rosidl_message_type_support_t {
+        self.handle
+    }
+}
+
+/// Returns the full name of the message type `T`, e.g. `"std_msgs/msg/String"`.
+///
+/// The name is read from the introspection type support of the message, which is loaded on
+/// demand. It is empty if the introspection type support can not be loaded.
+pub(crate) fn message_type_name<T: Message>() -> String {
+    let type_support =
+        <T as Message>::RmwMsg::get_type_support() as *const rosidl_message_type_support_t;
+    // SAFETY: The type support of a generated message is valid.
+    unsafe { type_name_from_type_support(type_support) }.unwrap_or_default()
+}
+
+// Helper for message_type_name()
+//
+// SAFETY: The type support must be valid.
+unsafe fn type_name_from_type_support(
+    type_support: *const rosidl_message_type_support_t,
+) -> Option<String> {
+    // The value of rosidl_typesupport_introspection_c__identifier. Type supports are matched by
+    // comparing the identifier strings, so this does not need to be the same pointer.
+    let identifier = CString::new("rosidl_typesupport_introspection_c").unwrap();
+    // This is what get_message_typesupport_handle() from rosidl_runtime_c does: Either the type
+    // support already is the introspection type support, or it can look it up, e.g. the
+    // rosidl_typesupport_c type support of a generated message.
+    let introspection = if CStr::from_ptr((*type_support).typesupport_identifier) == &*identifier {
+        type_support
+    } else {
+        (*type_support).func?(type_support, identifier.as_ptr())
+    };
+    if introspection.is_null() {
+        return None;
+    }
+    let members =
+        &*((*introspection).data as *const rosidl_typesupport_introspection_c__MessageMembers);
+    // The namespace is e.g. "std_msgs__msg"
+    let namespace = CStr::from_ptr(members.message_namespace_).to_str().ok()?;
+    let name = CStr::from_ptr(members.message_name_).to_str().ok()?;
+    Some(format!("{}/{}", namespace.replace("__", "/"), name))
+}
+
+// Helper for MessageTypeSupport::new()
+fn last_dl_error() -> String {
+    // SAFETY: No preconditions for this function.
+    let error_ptr = unsafe { libc::dlerror() };
+    if error_ptr.is_null() {
+        return String::from("unknown error");
+    }
+    // SAFETY: The returned string is valid until the next call to dlerror(), and it is
+    // immediately converted to an owned string.
+    unsafe { CStr::from_ptr(error_ptr) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_known_message_types() {
+        for full_message_type in ["test_msgs/msg/BasicTypes", "test_msgs/msg/Strings"] {
+            let type_support = MessageTypeSupport::new(full_message_type).unwrap();
+            assert!(!type_support.handle().is_null());
+        }
+    }
+
+    #[test]
+    fn message_type_names() {
+        assert_eq!(
+            message_type_name::<test_msgs::msg::Strings>(),
+            "test_msgs/msg/Strings"
+        );
+        assert_eq!(
+            message_type_name::<test_msgs::msg::rmw::Empty>(),
+            "test_msgs/msg/Empty"
+        );
+        let type_support = MessageTypeSupport::new("test_msgs/msg/BasicTypes").unwrap();
+        // SAFETY: The type support is valid.
+        let type_name = unsafe { type_name_from_type_support(type_support.handle()) };
+        assert_eq!(type_name.as_deref(), Some("test_msgs/msg/BasicTypes"));
+    }
+
+    #[test]
+    fn rejects_unknown_message_types() {
+        assert!(matches!(
+            MessageTypeSupport::new("test_msgs/msg/NotAMessage"),
+            Err(DynamicMessageError::InvalidMessageType { .. })
+        ));
+        assert!(matches!(
+            MessageTypeSupport::new("not_a_package/msg/String"),
+            Err(DynamicMessageError::LibraryLoadingError { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_syntax() {
+        for input in [
+            "",
+            "String",
+            "std_msgs/String",
+            "std_msgs//String",
+            "a/msg/b/c",
+        ] {
+            assert_eq!(
+                MessageTypeSupport::new(input).err(),
+                Some(DynamicMessageError::InvalidMessageTypeSyntax {
+                    input: input.to_owned()
+                })
+            );
+        }
+    }
+}
@@ -0,0 +1,278 @@
+use super::MessageTypeSupport;
+use crate::rcl_bindings::*;
+
+use std::ffi::CStr;
+use std::fmt::Write;
+use std::os::raw::c_void;
+use std::slice;
+
+// The values of rosidl_typesupport_introspection_c_field_types, as found in the type_id_ field of
+// a message member.
+const ROS_TYPE_FLOAT: u8 = 1;
+const ROS_TYPE_DOUBLE: u8 = 2;
+const ROS_TYPE_LONG_DOUBLE: u8 = 3;
+const ROS_TYPE_CHAR: u8 = 4;
+const ROS_TYPE_WCHAR: u8 = 5;
+const ROS_TYPE_BOOLEAN: u8 = 6;
+const ROS_TYPE_OCTET: u8 = 7;
+const ROS_TYPE_UINT8: u8 = 8;
+const ROS_TYPE_INT8: u8 = 9;
+const ROS_TYPE_UINT16: u8 = 10;
+const ROS_TYPE_INT16: u8 = 11;
+const ROS_TYPE_UINT32: u8 = 12;
+const ROS_TYPE_INT32: u8 = 13;
+const ROS_TYPE_UINT64: u8 = 14;
+const ROS_TYPE_INT64: u8 = 15;
+const ROS_TYPE_STRING: u8 = 16;
+const ROS_TYPE_WSTRING: u8 = 17;
+const ROS_TYPE_MESSAGE: u8 = 18;
+
+impl MessageTypeSupport {
+    /// Renders a message of this type as a YAML-like string, similar to `ros2 topic echo`.
+    ///
+    /// The message is given as the in-memory bytes of its C struct, e.g. the `RmwMsg` of a
+    /// generated message type, or a message taken by a generic subscription. This is not the
+    /// serialized (CDR) form of the message, as returned by [`serialize_cdr()`][1]. A serialized
+    /// message must be deserialized first, e.g. with [`deserialize_cdr()`][2]. Nested messages,
+    /// arrays and sequences are rendered recursively.
+    ///
+    /// # Safety
+    /// `c_message` must be a properly aligned, initialized C message of the type described by
+    /// this type support. All pointers in it, e.g. of strings and sequences, must be valid.
+    ///
+    /// # Panics
+    /// When `c_message` is smaller than the C struct of the message type.
+    ///
+    /// # Example
+    /// ```
+    /// # use rclrs::MessageTypeSupport;
+    /// use rosidl_runtime_rs::Message;
+    /// let type_support = MessageTypeSupport::new("test_msgs/msg/Strings").unwrap();
+    /// let msg = test_msgs::msg::Strings {
+    ///     string_value: String::from("Hello"),
+    ///     ..Default::default()
+    /// };
+    /// let rmw_msg = Message::into_rmw_message(std::borrow::Cow::Owned(msg)).into_owned();
+    /// // SAFETY: The bytes are a C message of the right type.
+    /// let printed = unsafe {
+    ///     type_support.print_message(std::slice::from_raw_parts(
+    ///         &rmw_msg as *const _ as *const u8,
+    ///         std::mem::size_of_val(&rmw_msg),
+    ///     ))
+    /// };
+    /// assert!(printed.contains("string_value: 'Hello'\n"));
+    /// ```
+    ///
+    /// [1]: crate::MessageSerialization::serialize_cdr
+    /// [2]: crate::MessageSerialization::deserialize_cdr
+    pub unsafe fn print_message(&self, c_message: &[u8]) -> String {
+        let members =
+            &*((*self.handle).data as *const rosidl_typesupport_introspection_c__MessageMembers);
+        assert!(
+            c_message.len() >= members.size_of_,
+            "The message has a size of {} bytes, but only {} bytes were given",
+            members.size_of_,
+            c_message.len()
+        );
+        render_message(members, c_message.as_ptr())
+    }
+}
+
+// Renders each field on its own line, without indentation.
+//
+// SAFETY: The message must be a valid C message described by the members.
+unsafe fn render_message(
+    members: &rosidl_typesupport_introspection_c__MessageMembers,
+    message: *const u8,
+) -> String {
+    let mut out = String::new();
+    let member_count = members.member_count_ as usize;
+    if member_count == 0 {
+        return out;
+    }
+    for member in slice::from_raw_parts(members.members_, member_count) {
+        let name = CStr::from_ptr(member.name_).to_string_lossy();
+        let field = message.add(member.offset_ as usize) as *const c_void;
+        if !member.is_array_ {
+            match render_value(member, field) {
+                Value::Scalar(value) => writeln!(out, "{}: {}", name, value).unwrap(),
+                Value::Message(block) => {
+                    writeln!(out, "{}:", name).unwrap();
+                    write_block(&mut out, &block, "  ", "  ");
+                }
+            }
+            continue;
+        }
+        // The size_function and get_const_function of the member are not available on all
+        // distros, so the elements are located through the C layout instead.
+        let (elements, len) = if member.array_size_ > 0 && !member.is_upper_bound_ {
+            // A fixed-size array is stored inline
+            (field as *const u8, member.array_size_)
+        } else {
+            // Bounded and unbounded sequences are both stored as a C sequence struct
+            let sequence = &*(field as *const CSequence);
+            (sequence.data as *const u8, sequence.size)
+        };
+        if len == 0 {
+            writeln!(out, "{}: []", name).unwrap();
+            continue;
+        }
+        writeln!(out, "{}:", name).unwrap();
+        let element_size = element_size(member);
+        for i in 0..len {
+            let element = elements.add(i * element_size) as *const c_void;
+            match render_value(member, element) {
+                Value::Scalar(value) => writeln!(out, "- {}", value).unwrap(),
+                Value::Message(block) => write_block(&mut out, &block, "- ", "  "),
+            }
+        }
+    }
+    out
+}
+
+// The layout of the `__Sequence` structs generated by rosidl_generator_c.
+#[repr(C)]
+struct CSequence {
+    data: *const c_void,
+    size: usize,
+    capacity: usize,
+}
+
+// The size of a single (non-array) value of the member's type, as stored in arrays.
+//
+// SAFETY: The member must be a valid member description.
+unsafe fn element_size(member: &rosidl_typesupport_introspection_c__MessageMember) -> usize {
+    match member.type_id_ {
+        ROS_TYPE_FLOAT => 4,
+        ROS_TYPE_DOUBLE => 8,
+        // Its size is platform-dependent, but it is not read anyway, see render_value()
+        ROS_TYPE_LONG_DOUBLE => 0,
+        ROS_TYPE_CHAR | ROS_TYPE_OCTET | ROS_TYPE_UINT8 | ROS_TYPE_INT8 | ROS_TYPE_BOOLEAN => 1,
+        ROS_TYPE_WCHAR | ROS_TYPE_UINT16 | ROS_TYPE_INT16 => 2,
+        ROS_TYPE_UINT32 | ROS_TYPE_INT32 => 4,
+        ROS_TYPE_UINT64 | ROS_TYPE_INT64 => 8,
+        ROS_TYPE_STRING => std::mem::size_of::<rosidl_runtime_rs::String>(),
+        ROS_TYPE_WSTRING => std::mem::size_of::<rosidl_runtime_rs::WString>(),
+        ROS_TYPE_MESSAGE => {
+            let members = &*((*member.members_).data
+                as *const rosidl_typesupport_introspection_c__MessageMembers);
+            members.size_of_
+        }
+        // Unknown values are not read either
+        _ => 0,
+    }
+}
+
+enum Value {
+    Scalar(String),
+    Message(String),
+}
+
+// Renders a single (non-array) value of the member's type.
+//
+// SAFETY: The value must point to a valid value of the member's type.
+unsafe fn render_value(
+    member: &rosidl_typesupport_introspection_c__MessageMember,
+    value: *const c_void,
+) -> Value {
+    let scalar = match member.type_id_ {
+        ROS_TYPE_FLOAT => (*(value as *const f32)).to_string(),
+        ROS_TYPE_DOUBLE => (*(value as *const f64)).to_string(),
+        // Rust has no equivalent type
+        ROS_TYPE_LONG_DOUBLE => String::from("<long double>"),
+        ROS_TYPE_CHAR | ROS_TYPE_OCTET | ROS_TYPE_UINT8 => (*(value as *const u8)).to_string(),
+        ROS_TYPE_WCHAR | ROS_TYPE_UINT16 => (*(value as *const u16)).to_string(),
+        ROS_TYPE_BOOLEAN => (*(value as *const bool)).to_string(),
+        ROS_TYPE_INT8 => (*(value as *const i8)).to_string(),
+        ROS_TYPE_INT16 => (*(value as *const i16)).to_string(),
+        ROS_TYPE_UINT32 => (*(value as *const u32)).to_string(),
+        ROS_TYPE_INT32 => (*(value as *const i32)).to_string(),
+        ROS_TYPE_UINT64 => (*(value as *const u64)).to_string(),
+        ROS_TYPE_INT64 => (*(value as *const i64)).to_string(),
+        // The string types of rosidl_runtime_rs have the same layout as the C strings
+        ROS_TYPE_STRING => quote(&(*(value as *const rosidl_runtime_rs::String)).to_string()),
+        ROS_TYPE_WSTRING => quote(&(*(value as *const rosidl_runtime_rs::WString)).to_string()),
+        ROS_TYPE_MESSAGE => {
+            let members = &*((*member.members_).data
+                as *const rosidl_typesupport_introspection_c__MessageMembers);
+            return Value::Message(render_message(members, value as *const u8));
+        }
+        other => format!("<unknown type {}>", other),
+    };
+    Value::Scalar(scalar)
+}
+
+// Quotes a string in YAML's single-quoted style.
+fn quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+// Appends the lines of a nested message, with the first line prefixed differently than the rest.
+fn write_block(out: &mut String, block: &str, first_prefix: &str, prefix: &str) {
+    if block.is_empty() {
+        writeln!(out, "{}{{}}", first_prefix).unwrap();
+        return;
+    }
+    for (i, line) in block.lines().enumerate() {
+        let line_prefix = if i == 0 { first_prefix } else { prefix };
+        writeln!(out, "{}{}", line_prefix, line).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MessageSerialization;
+    use rosidl_runtime_rs::Message;
+    use std::borrow::Cow;
+
+    // SAFETY: The type support must match the message type.
+    unsafe fn print<T: Message>(full_message_type: &str, msg: T) -> String {
+        let type_support = MessageTypeSupport::new(full_message_type).unwrap();
+        let rmw_msg = T::into_rmw_message(Cow::Owned(msg)).into_owned();
+        let raw = slice::from_raw_parts(
+            &rmw_msg as *const T::RmwMsg as *const u8,
+            std::mem::size_of_val(&rmw_msg),
+        );
+        type_support.print_message(raw)
+    }
+
+    #[test]
+    fn prints_strings() {
+        let msg = test_msgs::msg::Strings {
+            string_value: String::from("It's a string"),
+            ..Default::default()
+        };
+        // A serialized message must be deserialized before it can be printed
+        let serialized = msg.serialize_cdr().unwrap();
+        let deserialized = test_msgs::msg::Strings::deserialize_cdr(&serialized).unwrap();
+        // SAFETY: The type support matches the message type.
+        let printed = unsafe { print("test_msgs/msg/Strings", deserialized) };
+        assert!(printed.contains("string_value: 'It''s a string'\n"));
+    }
+
+    #[test]
+    fn prints_fixed_size_arrays() {
+        let msg = test_msgs::msg::Arrays {
+            int32_values: [1, -2, 3],
+            ..Default::default()
+        };
+        // SAFETY: The type support matches the message type.
+        let printed = unsafe { print("test_msgs/msg/Arrays", msg) };
+        assert!(printed.contains("\nint32_values:\n- 1\n- -2\n- 3\n"));
+        assert!(printed.contains("\nstring_values:\n- ''\n- ''\n- ''\n"));
+    }
+
+    #[test]
+    fn prints_sequences() {
+        let msg = test_msgs::msg::UnboundedSequences {
+            int32_values: vec![1, -2, 3],
+            bool_values: vec![],
+            ..Default::default()
+        };
+        // SAFETY: The type support matches the message type.
+        let printed = unsafe { print("test_msgs/msg/UnboundedSequences", msg) };
+        assert!(printed.contains("\nint32_values:\n- 1\n- -2\n- 3\n"));
+        assert!(printed.starts_with("bool_values: []\n"));
+    }
+}
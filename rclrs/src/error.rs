@@ -18,9 +18,54 @@ impl Display for RclrsError {
     }
 }
 
-/// Struct encapsulating an error message from the rcl layer or below.
+impl From<RclReturnCode> for RclrsError {
+    /// Creates an error without a message.
+    fn from(code: RclReturnCode) -> Self {
+        Self { code, msg: None }
+    }
+}
+
+impl RclrsError {
+    /// Returns `true` if this error is a timeout, e.g. of [`WaitSet::wait`][1].
+    ///
+    /// A timeout is often not a real error, but only means that nothing happened in time.
+    ///
+    /// [1]: crate::WaitSet::wait
+    pub fn is_timeout(&self) -> bool {
+        self.code.is_timeout()
+    }
+}
+
+/// Extension methods for filtering [`RclrsError`]s out of results.
+pub trait RclrsErrorFilter {
+    /// Turns a timeout error into `Ok(())`, and leaves all other results unchanged.
+    ///
+    /// # Example
+    /// ```
+    /// # use rclrs::{Context, RclrsError, RclrsErrorFilter};
+    /// # use std::time::Duration;
+    /// let context = Context::new([])?;
+    /// let mut node = context.create_node("my_node")?;
+    /// let _guard_condition = node.create_guard_condition()?;
+    /// // Nothing happens within the timeout, which is fine
+    /// rclrs::spin_once(&node, Some(Duration::from_millis(10))).timeout_ok()?;
+    /// # Ok::<(), RclrsError>(())
+    /// ```
+    fn timeout_ok(self) -> Self;
+}
+
+impl RclrsErrorFilter for Result<(), RclrsError> {
+    fn timeout_ok(self) -> Self {
+        match self {
+            Err(e) if e.is_timeout() => Ok(()),
+            other => other,
+        }
+    }
+}
+
+/// Error message from the rcl layer or below.
 ///
-/// This struct is intended to be returned by the `source` method in the implementation of the
+/// This type is intended to be returned by the `source` method in the implementation of the
 /// standard [`Error`][1] trait for [`RclrsError`][2].
 /// By doing this, the error message is printed as a separate item in the error chain.
 /// This avoids an unreadable, inconsistent formatting of error codes and messages that would
@@ -29,11 +74,37 @@ impl Display for RclrsError {
 /// [1]: std::error::Error
 /// [2]: crate::RclrsError
 #[derive(Debug, PartialEq)]
-pub(crate) struct RclErrorMsg(String);
+pub(crate) enum RclErrorMsg {
+    /// The message from the rcl error state.
+    Rcl(String),
+    /// A structured error for invalid command line arguments.
+    ArgumentParse(ArgumentParseError),
+    /// A structured error for invalid topic names.
+    TopicName(TopicNameError),
+    /// The full rcl error string, labeled with the operation that failed.
+    Context {
+        /// The name of the failed operation, e.g. the rcl function.
+        context: String,
+        /// The rcl error string, which includes the location where the error was set.
+        message: Option<String>,
+    },
+}
 
 impl Display for RclErrorMsg {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.0)
+        match self {
+            Self::Rcl(msg) => write!(f, "{}", msg),
+            Self::ArgumentParse(e) => write!(f, "{}", e),
+            Self::TopicName(e) => write!(f, "{}", e),
+            Self::Context {
+                context,
+                message: Some(message),
+            } => write!(f, "{} failed: {}", context, message),
+            Self::Context {
+                context,
+                message: None,
+            } => write!(f, "{} failed", context),
+        }
     }
 }
 
@@ -41,10 +112,129 @@ impl Error for RclErrorMsg {}
 
 impl Error for RclrsError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
-        self.msg.as_ref().map(|e| e as &dyn Error)
+        self.msg.as_ref().map(|e| match e {
+            // Returned directly so that it can be downcast by the user
+            RclErrorMsg::ArgumentParse(e) => e as &dyn Error,
+            RclErrorMsg::TopicName(e) => e as &dyn Error,
+            _ => e as &dyn Error,
+        })
+    }
+}
+
+/// An invalid command line argument.
+///
+/// This is the [`source`][1] of the [`RclrsError`] returned by [`Context::new()`][2] when
+/// parsing the ROS arguments failed.
+///
+/// [1]: std::error::Error::source
+/// [2]: crate::Context::new
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ArgumentParseError {
+    /// The offending argument.
+    pub argument: String,
+    /// The index of the offending argument in the list of arguments.
+    pub position: usize,
+    /// The reason why the argument is invalid, as reported by rcl.
+    pub reason: String,
+}
+
+impl Display for ArgumentParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Invalid ROS argument '{}' at position {}: {}",
+            self.argument, self.position, self.reason
+        )
+    }
+}
+
+impl Error for ArgumentParseError {}
+
+/// The reason why a topic name is invalid.
+///
+/// See the [topic name design article][1] for the rules.
+///
+/// [1]: http://design.ros2.org/articles/topic_and_service_names.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TopicNameErrorKind {
+    /// The name is empty.
+    Empty,
+    /// The name ends with a forward slash.
+    EndsWithForwardSlash,
+    /// The name contains a character other than alphanumerics, `_`, `/`, `~`, `{` and `}`.
+    UnallowedCharacter,
+    /// A token of the name, i.e. a part between forward slashes, starts with a number.
+    TokenStartsWithNumber,
+    /// A curly brace of a substitution is not matched.
+    UnmatchedCurlyBrace,
+    /// A tilde is not the first character.
+    MisplacedTilde,
+    /// A tilde is not followed by a forward slash.
+    TildeNotFollowedByForwardSlash,
+    /// A substitution contains a character other than alphanumerics and `_`.
+    SubstitutionContainsUnallowedCharacters,
+    /// A substitution starts with a number.
+    SubstitutionStartsWithNumber,
+    /// The name contains two forward slashes in a row.
+    RepeatedForwardSlash,
+    /// The name is too long for the RMW implementation.
+    TooLong,
+}
+
+impl Display for TopicNameErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let reason = match self {
+            Self::Empty => "must not be empty",
+            Self::EndsWithForwardSlash => "must not end with '/'",
+            Self::UnallowedCharacter => {
+                "must only contain alphanumerics, '_', '/', '~', '{' and '}'"
+            }
+            Self::TokenStartsWithNumber => "must not have a token that starts with a number",
+            Self::UnmatchedCurlyBrace => "must not have an unmatched curly brace",
+            Self::MisplacedTilde => "must only have '~' as the first character",
+            Self::TildeNotFollowedByForwardSlash => "must have a '/' after '~'",
+            Self::SubstitutionContainsUnallowedCharacters => {
+                "must only contain alphanumerics and '_' in a substitution"
+            }
+            Self::SubstitutionStartsWithNumber => {
+                "must not have a substitution that starts with a number"
+            }
+            Self::RepeatedForwardSlash => "must not contain '//'",
+            Self::TooLong => "is too long",
+        };
+        write!(f, "{}", reason)
+    }
+}
+
+/// An invalid topic name.
+///
+/// This is the [`source`][1] of the [`RclrsError`] returned by [`validate_topic_name()`][2], and
+/// by the creation of publishers and subscriptions with an invalid topic name.
+///
+/// [1]: std::error::Error::source
+/// [2]: crate::validate_topic_name
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TopicNameError {
+    /// The offending topic name.
+    pub name: String,
+    /// The reason why the topic name is invalid.
+    pub kind: TopicNameErrorKind,
+    /// The index of the offending character in the topic name.
+    pub index: usize,
+}
+
+impl Display for TopicNameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Invalid topic name '{}' at index {}: The topic name {}",
+            self.name, self.index, self.kind
+        )
     }
 }
 
+impl Error for TopicNameError {}
+
 /// RCL specific error codes.
 ///
 /// These are the error codes that start at 100.
@@ -480,6 +670,18 @@ pub enum RclReturnCode {
     UnknownError(i32),
 }
 
+impl RclReturnCode {
+    /// Returns `true` for [`RclReturnCode::Ok`].
+    pub fn is_ok(&self) -> bool {
+        matches!(self, Self::Ok)
+    }
+
+    /// Returns `true` for [`RclReturnCode::Timeout`].
+    pub fn is_timeout(&self) -> bool {
+        matches!(self, Self::Timeout)
+    }
+}
+
 impl From<i32> for RclReturnCode {
     fn from(value: i32) -> Self {
         match value {
@@ -648,7 +850,7 @@ pub(crate) fn to_rcl_result(code: i32) -> Result<(), RclrsError> {
                 let s = unsafe { CStr::from_ptr(msg_ptr) }
                     .to_string_lossy()
                     .into_owned();
-                msg = Some(RclErrorMsg(s));
+                msg = Some(RclErrorMsg::Rcl(s));
             }
             // SAFETY: No preconditions for this function.
             unsafe { rcutils_reset_error() };
@@ -660,23 +862,65 @@ pub(crate) fn to_rcl_result(code: i32) -> Result<(), RclrsError> {
     }
 }
 
+/// Like [`to_rcl_result`], but the error message is the full rcl error string, labeled with the
+/// given context.
+pub(crate) fn to_rcl_result_with_context(code: i32, context: &str) -> Result<(), RclrsError> {
+    match RclReturnCode::from(code) {
+        RclReturnCode::Ok => Ok(()),
+        anything_else => {
+            let mut message = None;
+            // SAFETY: No preconditions for this function.
+            if unsafe { rcutils_error_is_set() } {
+                // SAFETY: No preconditions for this function. The string is returned by value,
+                // so there are no lifetime issues.
+                let error_string = unsafe { rcutils_get_error_string() };
+                // SAFETY: The string is null-terminated, even if the message was truncated.
+                let s = unsafe { CStr::from_ptr(error_string.str_.as_ptr()) }
+                    .to_string_lossy()
+                    .into_owned();
+                message = Some(s);
+            }
+            // SAFETY: No preconditions for this function.
+            unsafe { rcutils_reset_error() };
+            Err(RclrsError {
+                code: anything_else,
+                msg: Some(RclErrorMsg::Context {
+                    context: context.to_owned(),
+                    message,
+                }),
+            })
+        }
+    }
+}
+
 pub(crate) trait ToResult {
     fn ok(&self) -> Result<(), RclrsError>;
+
+    /// Like `ok()`, but on failure the error message names the failed operation.
+    ///
+    /// This is meant for calls whose failures are hard to attribute otherwise, like the
+    /// initialization of entities.
+    fn ok_with_context(&self, context: &str) -> Result<(), RclrsError>;
 }
 
 impl ToResult for rcl_ret_t {
     fn ok(&self) -> Result<(), RclrsError> {
         to_rcl_result(*self as i32)
     }
+
+    fn ok_with_context(&self, context: &str) -> Result<(), RclrsError> {
+        to_rcl_result_with_context(*self as i32, context)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::error::{
         ClientErrorCode, EventErrorCode, LifecycleErrorCode, NodeErrorCode, ParsingErrorCode,
-        RclErrorCode, RclReturnCode, ServiceErrorCode, SubscriberErrorCode, TimerErrorCode,
-        WaitSetErrorCode,
+        RclErrorCode, RclReturnCode, RclrsError, RclrsErrorFilter, ServiceErrorCode,
+        SubscriberErrorCode, TimerErrorCode, ToResult, WaitSetErrorCode,
     };
+    use crate::rcl_bindings::rcl_ret_t;
 
     #[test]
     fn test_ok() {
@@ -691,6 +935,34 @@ mod tests {
     #[test]
     fn test_timeout() {
         assert_eq!(RclReturnCode::from(2), RclReturnCode::Timeout);
+        assert!(RclReturnCode::from(2).is_timeout());
+        assert!(!RclReturnCode::from(2).is_ok());
+        assert!(RclReturnCode::from(0).is_ok());
+        assert!(!RclReturnCode::from(1).is_timeout());
+    }
+
+    #[test]
+    fn test_timeout_is_distinguishable_from_other_errors() {
+        let timeout = (2 as rcl_ret_t).ok().unwrap_err();
+        assert!(timeout.is_timeout());
+        assert_eq!(Err(timeout).timeout_ok(), Ok(()));
+        let error = (1 as rcl_ret_t).ok().unwrap_err();
+        assert!(!error.is_timeout());
+        assert_eq!(
+            Err(error).timeout_ok(),
+            Err(RclrsError::from(RclReturnCode::Error))
+        );
+        assert_eq!(Ok(()).timeout_ok(), Ok(()));
+    }
+
+    #[test]
+    fn test_wait_timeout_is_filtered() -> Result<(), RclrsError> {
+        let context = crate::Context::new([])?;
+        let mut node = context.create_node("error_timeout_test_node")?;
+        let _guard_condition = node.create_guard_condition()?;
+        let result = crate::spin_once(&node, Some(std::time::Duration::from_millis(10)));
+        assert!(result.as_ref().unwrap_err().is_timeout());
+        result.timeout_ok()
     }
 
     #[test]
@@ -1122,4 +1394,19 @@ mod tests {
     fn test_unknown_error() {
         assert_eq!(RclReturnCode::from(-42), RclReturnCode::UnknownError(-42));
     }
+
+    #[test]
+    fn test_error_string_is_captured_with_context() {
+        // SAFETY: Passing a null pointer is checked by rcl and sets the error state.
+        let err = unsafe { crate::rcl_bindings::rcl_wait_set_clear(std::ptr::null_mut()) }
+            .ok_with_context("rcl_wait_set_clear")
+            .unwrap_err();
+        assert_eq!(err.code, RclReturnCode::InvalidArgument);
+        let msg = std::error::Error::source(&err).unwrap().to_string();
+        assert!(msg.starts_with("rcl_wait_set_clear failed: "));
+        assert!(msg.len() > "rcl_wait_set_clear failed: ".len());
+        // The error state has been reset
+        // SAFETY: No preconditions for this function.
+        assert!(!unsafe { crate::rcl_bindings::rcutils_error_is_set() });
+    }
 }
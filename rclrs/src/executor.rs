@@ -0,0 +1,218 @@
+use crate::{context_is_valid, Node, RclReturnCode, RclrsError, RclrsErrorFilter, WaitSet};
+
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Runs the callbacks of several nodes, on the current thread or on several threads.
+///
+/// The [`spin_once`][1] and [`spin`][2] functions only handle a single node. An executor instead
+/// waits on the entities of all of its nodes at once, so that a process with several nodes does
+/// not need a thread per node.
+///
+/// An executor created with [`Executor::with_threads()`][4] runs the callbacks of ready
+/// subscriptions and timers in parallel, as allowed by their [`CallbackGroup`][5]s.
+///
+/// The nodes are borrowed by the executor, so all entities should be created before the nodes
+/// are added. The entities are collected anew on each [`spin_once()`][3], so entities that are
+/// dropped in the meantime are not waited on anymore.
+///
+/// # Example
+/// ```
+/// # use rclrs::{Context, Executor, RclrsError, RclrsErrorFilter};
+/// # use std::time::Duration;
+/// let context = Context::new([])?;
+/// let mut talker = context.create_node("talker")?;
+/// let mut listener = context.create_node("listener")?;
+/// let _timer = talker.create_timer(Duration::from_millis(10), || println!("Tick"))?;
+/// let _guard_condition = listener.create_guard_condition()?;
+///
+/// let mut executor = Executor::new();
+/// executor.add_node(&talker)?;
+/// executor.add_node(&listener)?;
+/// executor.spin_once(Some(Duration::from_millis(100))).timeout_ok()?;
+/// # Ok::<(), RclrsError>(())
+/// ```
+///
+/// [1]: crate::spin_once
+/// [2]: crate::spin
+/// [3]: Executor::spin_once
+/// [4]: Executor::with_threads
+/// [5]: crate::CallbackGroup
+pub struct Executor<'a> {
+    nodes: Vec<&'a Node>,
+    number_of_threads: usize,
+}
+
+impl<'a> Default for Executor<'a> {
+    fn default() -> Self {
+        Self {
+            nodes: Vec::new(),
+            number_of_threads: 1,
+        }
+    }
+}
+
+impl<'a> Executor<'a> {
+    /// Creates an executor without any nodes, which runs all callbacks on the current thread.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an executor without any nodes, which runs callbacks on up to `number_of_threads`
+    /// threads.
+    ///
+    /// On each [`spin_once()`][1], the callbacks of the ready subscriptions and timers are
+    /// distributed over the threads. Callbacks in the same mutually exclusive
+    /// [`CallbackGroup`][2] still run one after another.
+    ///
+    /// # Panics
+    /// When `number_of_threads` is zero.
+    ///
+    /// [1]: Executor::spin_once
+    /// [2]: crate::CallbackGroup
+    pub fn with_threads(number_of_threads: usize) -> Self {
+        assert!(
+            number_of_threads > 0,
+            "An executor needs at least one thread"
+        );
+        Self {
+            nodes: Vec::new(),
+            number_of_threads,
+        }
+    }
+
+    /// Adds a node to the executor.
+    ///
+    /// Adding a node that has already been added has no effect.
+    ///
+    /// All nodes of an executor must have been created from the same context, since they are
+    /// waited on with a single wait set. Adding a node from another context returns an error with
+    /// the [`InvalidArgument`][1] code.
+    ///
+    /// [1]: crate::RclReturnCode::InvalidArgument
+    pub fn add_node(&mut self, node: &'a Node) -> Result<(), RclrsError> {
+        if let Some(first) = self.nodes.first() {
+            if !Arc::ptr_eq(&first.handle.context_handle, &node.handle.context_handle) {
+                return Err(RclrsError {
+                    code: RclReturnCode::InvalidArgument,
+                    msg: None,
+                });
+            }
+        }
+        if !self.nodes.contains(&node) {
+            self.nodes.push(node);
+        }
+        Ok(())
+    }
+
+    /// Removes a node from the executor.
+    ///
+    /// Returns `false` if the node had not been added.
+    pub fn remove_node(&mut self, node: &Node) -> bool {
+        let len = self.nodes.len();
+        self.nodes.retain(|added| *added != node);
+        self.nodes.len() < len
+    }
+
+    /// Waits on the entities of all nodes and executes the callbacks of the ready ones.
+    ///
+    /// This is the equivalent of [`spin_once`][1] for several nodes, see there for the meaning of
+    /// the `timeout` and possible errors. An executor without nodes returns immediately.
+    ///
+    /// [1]: crate::spin_once
+    pub fn spin_once(&self, timeout: Option<Duration>) -> Result<(), RclrsError> {
+        if self.nodes.is_empty() {
+            return Ok(());
+        }
+        let mut wait_set = WaitSet::new_for_nodes(&self.nodes)?;
+        let ready_entities = wait_set.wait(timeout)?;
+        if self.number_of_threads > 1 {
+            ready_entities.execute_in_parallel(self.number_of_threads)
+        } else {
+            ready_entities.execute()
+        }
+    }
+
+    /// Calls [`spin_once()`][1] in a loop, until the context of the nodes is shut down.
+    ///
+    /// An executor without nodes returns immediately.
+    ///
+    /// [1]: Executor::spin_once
+    pub fn spin(&self) -> Result<(), RclrsError> {
+        while let Some(node) = self.nodes.first() {
+            if !context_is_valid(&node.handle.context_handle) {
+                break;
+            }
+            self.spin_once(None).timeout_ok()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Context, QOS_PROFILE_DEFAULT};
+    use parking_lot::Mutex;
+    use std::time::Instant;
+
+    #[test]
+    fn messages_flow_between_nodes_of_one_executor() -> Result<(), RclrsError> {
+        let context = Context::new([])?;
+        let mut talker = context.create_node("executor_test_talker")?;
+        let mut listener = context.create_node("executor_test_listener")?;
+        let publisher = talker
+            .create_publisher::<test_msgs::msg::Strings>("executor_test", QOS_PROFILE_DEFAULT)?;
+        // The talker publishes from a timer, so that both nodes have entities to wait on.
+        let _timer = talker.create_timer(Duration::from_millis(10), move || {
+            let msg = test_msgs::msg::Strings {
+                string_value: String::from("hello"),
+                ..Default::default()
+            };
+            publisher.publish(msg).unwrap();
+        })?;
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let _subscription = listener.create_subscription::<test_msgs::msg::Strings, _>(
+            "executor_test",
+            QOS_PROFILE_DEFAULT,
+            {
+                let received = received.clone();
+                move |msg| received.lock().push(msg.string_value)
+            },
+        )?;
+
+        let mut executor = Executor::new();
+        executor.add_node(&talker)?;
+        executor.add_node(&listener)?;
+        let start = Instant::now();
+        while received.lock().is_empty() {
+            assert!(start.elapsed() < Duration::from_secs(5));
+            executor
+                .spin_once(Some(Duration::from_millis(100)))
+                .timeout_ok()?;
+        }
+        assert_eq!(received.lock()[0], "hello");
+        Ok(())
+    }
+
+    #[test]
+    fn nodes_must_share_a_context() -> Result<(), RclrsError> {
+        let context = Context::new([])?;
+        let node = context.create_node("executor_test_node")?;
+        let other_context = Context::new([])?;
+        let other_node = other_context.create_node("executor_test_other_node")?;
+
+        let mut executor = Executor::new();
+        // An empty executor has nothing to do
+        executor.spin_once(None)?;
+        executor.add_node(&node)?;
+        executor.add_node(&node)?;
+        let err = executor.add_node(&other_node).unwrap_err();
+        assert_eq!(err.code, RclReturnCode::InvalidArgument);
+        assert!(executor.remove_node(&node));
+        assert!(!executor.remove_node(&node));
+        // After removing the only node, nodes from other contexts can be added
+        executor.add_node(&other_node)?;
+        Ok(())
+    }
+}
@@ -5,18 +5,40 @@
 //!
 //! [1]: https://github.com/ros2-rust/ros2_rust/blob/master/README.md
 
+mod allocator;
+mod callback_group;
+mod clock;
 mod context;
+mod dynamic_message;
 mod error;
+mod executor;
+mod logging;
 mod node;
+mod parameter;
 mod qos;
+mod rate;
+mod serialization;
+mod time;
+mod timer;
 mod wait;
 
 mod rcl_bindings;
 
+pub use allocator::rcutils_allocator_t;
+pub use callback_group::*;
+pub use clock::*;
 pub use context::*;
+pub use dynamic_message::*;
 pub use error::*;
+pub use executor::*;
+pub use logging::*;
 pub use node::*;
+pub use parameter::*;
 pub use qos::*;
+pub use rate::*;
+pub use serialization::*;
+pub use time::*;
+pub use timer::*;
 pub use wait::*;
 
 use rcl_bindings::rcl_context_is_valid;
@@ -32,44 +54,29 @@ use std::time::Duration;
 ///
 /// [1]: crate::SubscriberErrorCode
 pub fn spin_once(node: &Node, timeout: Option<Duration>) -> Result<(), RclrsError> {
-    let live_subscriptions = node.live_subscriptions();
-    let ctx = Context {
-        handle: node.context.clone(),
-    };
-    let mut wait_set = WaitSet::new(live_subscriptions.len(), &ctx)?;
-
-    for live_subscription in &live_subscriptions {
-        wait_set.add_subscription(live_subscription.clone())?;
-    }
-
-    let ready_entities = wait_set.wait(timeout)?;
-    for ready_subscription in ready_entities.subscriptions {
-        ready_subscription.execute()?;
-    }
-
-    Ok(())
+    let mut wait_set = WaitSet::new_for_node(node)?;
+    wait_set.wait(timeout)?.execute()
 }
 
 /// Convenience function for calling [`spin_once`] in a loop.
 ///
 /// This function additionally checks that the context is still valid.
 pub fn spin(node: &Node) -> Result<(), RclrsError> {
-    // The context_is_valid functions exists only to abstract away ROS distro differences
+    while context_is_valid(&node.handle.context_handle) {
+        spin_once(node, None).timeout_ok()?;
+    }
+
+    Ok(())
+}
+
+// Checks whether the context has not been shut down, for the spin functions.
+pub(crate) fn context_is_valid(context_handle: &ContextHandle) -> bool {
+    // The two variants exist only to abstract away ROS distro differences
     #[cfg(ros_distro = "foxy")]
     // SAFETY: No preconditions for this function.
-    let context_is_valid = || unsafe { rcl_context_is_valid(&mut *node.context.lock()) };
+    let is_valid = unsafe { rcl_context_is_valid(&mut *context_handle.rcl_context.lock()) };
     #[cfg(not(ros_distro = "foxy"))]
     // SAFETY: No preconditions for this function.
-    let context_is_valid = || unsafe { rcl_context_is_valid(&*node.context.lock()) };
-
-    while context_is_valid() {
-        if let Some(error) = spin_once(node, None).err() {
-            match error.code {
-                RclReturnCode::Timeout => continue,
-                _ => return Err(error),
-            };
-        }
-    }
-
-    Ok(())
+    let is_valid = unsafe { rcl_context_is_valid(&*context_handle.rcl_context.lock()) };
+    is_valid
 }
@@ -0,0 +1,697 @@
+use crate::rcl_bindings::*;
+use crate::{Node, RclrsError, ToResult};
+
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::Once;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+
+/// The severity of a log message.
+///
+/// Only messages at or above the severity threshold of a logger are emitted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LogSeverity {
+    /// Detailed information that is usually only of interest when debugging.
+    Debug,
+    /// Information about the normal operation of the program.
+    Info,
+    /// Something unexpected happened, but the program can continue normally.
+    Warn,
+    /// Something went wrong, but the program may be able to recover.
+    Error,
+    /// Something went wrong, and the program cannot continue.
+    Fatal,
+}
+
+impl LogSeverity {
+    pub(crate) fn to_native(self) -> RCUTILS_LOG_SEVERITY {
+        match self {
+            Self::Debug => RCUTILS_LOG_SEVERITY::RCUTILS_LOG_SEVERITY_DEBUG,
+            Self::Info => RCUTILS_LOG_SEVERITY::RCUTILS_LOG_SEVERITY_INFO,
+            Self::Warn => RCUTILS_LOG_SEVERITY::RCUTILS_LOG_SEVERITY_WARN,
+            Self::Error => RCUTILS_LOG_SEVERITY::RCUTILS_LOG_SEVERITY_ERROR,
+            Self::Fatal => RCUTILS_LOG_SEVERITY::RCUTILS_LOG_SEVERITY_FATAL,
+        }
+    }
+}
+
+/// Sets the severity threshold of a logger.
+///
+/// Messages below this severity are not emitted by the logger. This can be called at any time,
+/// e.g. to increase the verbosity of a running node for debugging.
+///
+/// # Example
+/// ```
+/// # use rclrs::{log_debug, set_logger_level, LogSeverity, RclrsError};
+/// set_logger_level("my_logger", LogSeverity::Debug)?;
+/// log_debug!("my_logger", "This is now emitted");
+/// # Ok::<(), RclrsError>(())
+/// ```
+pub fn set_logger_level(logger_name: &str, level: LogSeverity) -> Result<(), RclrsError> {
+    let logger_name = to_cstring_lossy(logger_name);
+    // SAFETY: The logger name is a valid C string, and is copied by this function.
+    unsafe { rcutils_logging_set_logger_level(logger_name.as_ptr(), level.to_native() as i32).ok() }
+}
+
+/// Implemented by types that determine the logger in the logging macros.
+///
+/// This makes it possible to pass either a logger name or a [`Node`] to the macros.
+pub trait LoggerName {
+    /// Returns the name of the logger.
+    fn logger_name(&self) -> Cow<'_, str>;
+}
+
+impl LoggerName for str {
+    fn logger_name(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl LoggerName for String {
+    fn logger_name(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl LoggerName for Node {
+    fn logger_name(&self) -> Cow<'_, str> {
+        Cow::Owned(Node::logger_name(self))
+    }
+}
+
+/// The logger of the current [`LoggingScope`], for use as the logger in the logging macros.
+///
+/// Outside of a logging scope, this is the default logger, whose name is empty.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CurrentLogger;
+
+impl LoggerName for CurrentLogger {
+    fn logger_name(&self) -> Cow<'_, str> {
+        Cow::Owned(current_logger_name().unwrap_or_default())
+    }
+}
+
+impl<T: LoggerName + ?Sized> LoggerName for &T {
+    fn logger_name(&self) -> Cow<'_, str> {
+        (**self).logger_name()
+    }
+}
+
+thread_local! {
+    // The logger used by the logging macros when no logger is given.
+    static CURRENT_LOGGER: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// A guard that sets the logger used by the logging macros on the current thread.
+///
+/// While the guard is alive, the logging macros log to the logger of the scope when they are
+/// given [`CurrentLogger`] as the logger, e.g. `log_info!(CurrentLogger, "msg")`. When the guard
+/// is dropped, the previous logger is restored, so scopes can be nested. Guards should be dropped
+/// in the reverse order of their creation, which is the case when they are bound to local
+/// variables.
+///
+/// Without a scope, these macro invocations log to the default logger, whose name is empty.
+///
+/// The scope only applies to the thread it was entered on, so the guard is not [`Send`].
+///
+/// # Example
+/// ```
+/// # use rclrs::{current_logger_name, log_info, Context, CurrentLogger, RclrsError};
+/// let context = Context::new([])?;
+/// let node = context.create_node("my_node")?;
+/// {
+///     let _scope = node.enter_logging_scope();
+///     // Logs to the logger of the node
+///     log_info!(CurrentLogger, "Hello from {}", node.name());
+///     assert_eq!(current_logger_name().as_deref(), Some("my_node"));
+/// }
+/// assert_eq!(current_logger_name(), None);
+/// # Ok::<(), RclrsError>(())
+/// ```
+#[must_use = "the logging scope is exited when the guard is dropped"]
+pub struct LoggingScope {
+    previous: Option<String>,
+    // Makes the guard !Send, since it restores thread-local state.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl LoggingScope {
+    /// Enters a logging scope with the given logger name or [`Node`].
+    ///
+    /// See also [`Node::enter_logging_scope()`].
+    pub fn enter<L: LoggerName + ?Sized>(logger: &L) -> Self {
+        let logger_name = logger.logger_name().into_owned();
+        let previous = CURRENT_LOGGER.with(|current| current.replace(Some(logger_name)));
+        Self {
+            previous,
+            _not_send: PhantomData,
+        }
+    }
+}
+
+impl Drop for LoggingScope {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        CURRENT_LOGGER.with(|current| *current.borrow_mut() = previous);
+    }
+}
+
+/// Returns the name of the logger of the innermost [`LoggingScope`] on the current thread.
+///
+/// Returns `None` outside of a logging scope.
+pub fn current_logger_name() -> Option<String> {
+    CURRENT_LOGGER.with(|current| current.borrow().clone())
+}
+
+// Replaces null bytes instead of failing, since logging should never panic.
+fn to_cstring_lossy(s: &str) -> CString {
+    CString::new(s.replace('\0', "\\0")).unwrap()
+}
+
+/// Logs a message with the given severity to the given logger.
+///
+/// This is used by the logging macros and not meant to be called directly.
+#[doc(hidden)]
+pub fn log_internal(
+    severity: LogSeverity,
+    logger_name: &str,
+    message: fmt::Arguments<'_>,
+    file: &str,
+    line: u32,
+    module_path: &str,
+) {
+    static INIT_LOGGING: Once = Once::new();
+    INIT_LOGGING.call_once(|| {
+        // If initialization fails, rcutils_log() will report it to stderr.
+        // SAFETY: No preconditions for this function.
+        unsafe { rcutils_logging_initialize() };
+    });
+    let logger_name = to_cstring_lossy(logger_name);
+    let severity = severity.to_native() as i32;
+    // SAFETY: The logger name is a valid C string.
+    if !unsafe { rcutils_logging_logger_is_enabled_for(logger_name.as_ptr(), severity) } {
+        return;
+    }
+    let message = to_cstring_lossy(&message.to_string());
+    let file = to_cstring_lossy(file);
+    let module_path = to_cstring_lossy(module_path);
+    let location = rcutils_log_location_t {
+        function_name: module_path.as_ptr(),
+        file_name: file.as_ptr(),
+        line_number: line as usize,
+    };
+    // SAFETY: All strings are valid C strings that outlive this call, and the format string
+    // matches the single string argument.
+    unsafe {
+        rcutils_log(
+            &location,
+            severity,
+            logger_name.as_ptr(),
+            CStr::from_bytes_with_nul(b"%s\0").unwrap().as_ptr(),
+            message.as_ptr(),
+        );
+    }
+}
+
+/// The time at which a throttled logging macro invocation last logged.
+///
+/// This is used by the throttled logging macros and not meant to be used directly.
+#[doc(hidden)]
+#[derive(Default)]
+pub struct LastLogged(Mutex<Option<Instant>>);
+
+impl LastLogged {
+    /// Creates the state of an invocation that has not logged yet.
+    ///
+    /// Unlike the `Mutex` of std, this can be used to initialize a `static`.
+    pub const fn new() -> Self {
+        Self(parking_lot::const_mutex(None))
+    }
+}
+
+/// Checks whether at least `period` has passed since this last returned `true`.
+///
+/// This is used by the throttled logging macros and not meant to be called directly.
+#[doc(hidden)]
+pub fn throttle_elapsed(last_logged: &LastLogged, period: Duration) -> bool {
+    let now = Instant::now();
+    let mut last_logged = last_logged.0.lock();
+    match *last_logged {
+        Some(last) if now.duration_since(last) < period => false,
+        _ => {
+            *last_logged = Some(now);
+            true
+        }
+    }
+}
+
+/// Logs a message with the given [`LogSeverity`].
+///
+/// The first argument is the logger name or a [`Node`][1], the remaining arguments are the same as
+/// for [`format!`]. The message goes through the standard ROS logging pipeline, so it respects e.g.
+/// the `RCUTILS_CONSOLE_OUTPUT_FORMAT` environment variable and the logger level.
+///
+/// To log to the logger of the current [`LoggingScope`][2], pass [`CurrentLogger`][3] as the
+/// logger.
+///
+/// Usually, one of the severity-specific macros such as [`log_info!`] is used instead.
+///
+/// # Example
+/// ```
+/// # use rclrs::{log, CurrentLogger, LogSeverity, LoggingScope};
+/// log!(LogSeverity::Info, "my_logger", "The answer is {}", 42);
+/// let _scope = LoggingScope::enter("my_logger");
+/// log!(LogSeverity::Info, CurrentLogger, "The answer is still {}", 42);
+/// ```
+///
+/// [1]: crate::Node
+/// [2]: crate::LoggingScope
+/// [3]: crate::CurrentLogger
+#[macro_export]
+macro_rules! log {
+    ($severity:expr, $logger:expr, $($arg:tt)+) => {
+        $crate::log_internal(
+            $severity,
+            &$crate::LoggerName::logger_name(&$logger),
+            ::std::format_args!($($arg)+),
+            ::std::file!(),
+            ::std::line!(),
+            ::std::module_path!(),
+        )
+    };
+}
+
+/// Like [`log!`], but only logs the first time this macro invocation is reached.
+#[macro_export]
+macro_rules! log_once {
+    ($severity:expr, $($arg:tt)+) => {{
+        static ONCE: ::std::sync::Once = ::std::sync::Once::new();
+        ONCE.call_once(|| $crate::log!($severity, $($arg)+));
+    }};
+}
+
+/// Like [`log!`], but only logs if at least the given [`Duration`][1] has passed since this
+/// macro invocation last logged.
+///
+/// # Example
+/// ```
+/// # use rclrs::{log_throttle, LogSeverity};
+/// # use std::time::Duration;
+/// for i in 0..1000 {
+///     // Logs only once, since the loop takes less than a second
+///     log_throttle!(LogSeverity::Info, "my_logger", Duration::from_secs(1), "Iteration {}", i);
+/// }
+/// ```
+///
+/// [1]: std::time::Duration
+#[macro_export]
+macro_rules! log_throttle {
+    (@throttled $period:expr, $log:expr) => {{
+        static LAST_LOGGED: $crate::LastLogged = $crate::LastLogged::new();
+        if $crate::throttle_elapsed(&LAST_LOGGED, $period) {
+            $log;
+        }
+    }};
+    ($severity:expr, $logger:expr, $period:expr, $($arg:tt)+) => {
+        $crate::log_throttle!(@throttled $period, $crate::log!($severity, $logger, $($arg)+))
+    };
+}
+
+/// Logs a message with [`Debug`](LogSeverity::Debug) severity. See [`log!`].
+#[macro_export]
+macro_rules! log_debug {
+    ($($arg:tt)+) => {
+        $crate::log!($crate::LogSeverity::Debug, $($arg)+)
+    };
+}
+
+/// Logs a message with [`Info`](LogSeverity::Info) severity. See [`log!`].
+///
+/// # Example
+/// ```
+/// # use rclrs::log_info;
+/// let distance = 2.5;
+/// log_info!("my_logger", "Distance to goal: {:.1} m", distance);
+/// ```
+#[macro_export]
+macro_rules! log_info {
+    ($($arg:tt)+) => {
+        $crate::log!($crate::LogSeverity::Info, $($arg)+)
+    };
+}
+
+/// Logs a message with [`Warn`](LogSeverity::Warn) severity. See [`log!`].
+#[macro_export]
+macro_rules! log_warn {
+    ($($arg:tt)+) => {
+        $crate::log!($crate::LogSeverity::Warn, $($arg)+)
+    };
+}
+
+/// Logs a message with [`Error`](LogSeverity::Error) severity. See [`log!`].
+#[macro_export]
+macro_rules! log_error {
+    ($($arg:tt)+) => {
+        $crate::log!($crate::LogSeverity::Error, $($arg)+)
+    };
+}
+
+/// Logs a message with [`Fatal`](LogSeverity::Fatal) severity. See [`log!`].
+#[macro_export]
+macro_rules! log_fatal {
+    ($($arg:tt)+) => {
+        $crate::log!($crate::LogSeverity::Fatal, $($arg)+)
+    };
+}
+
+/// Logs a message with [`Debug`](LogSeverity::Debug) severity only once. See [`log_once!`].
+#[macro_export]
+macro_rules! log_debug_once {
+    ($($arg:tt)+) => {
+        $crate::log_once!($crate::LogSeverity::Debug, $($arg)+)
+    };
+}
+
+/// Logs a message with [`Info`](LogSeverity::Info) severity only once. See [`log_once!`].
+#[macro_export]
+macro_rules! log_info_once {
+    ($($arg:tt)+) => {
+        $crate::log_once!($crate::LogSeverity::Info, $($arg)+)
+    };
+}
+
+/// Logs a message with [`Warn`](LogSeverity::Warn) severity only once. See [`log_once!`].
+#[macro_export]
+macro_rules! log_warn_once {
+    ($($arg:tt)+) => {
+        $crate::log_once!($crate::LogSeverity::Warn, $($arg)+)
+    };
+}
+
+/// Logs a message with [`Error`](LogSeverity::Error) severity only once. See [`log_once!`].
+#[macro_export]
+macro_rules! log_error_once {
+    ($($arg:tt)+) => {
+        $crate::log_once!($crate::LogSeverity::Error, $($arg)+)
+    };
+}
+
+/// Logs a message with [`Fatal`](LogSeverity::Fatal) severity only once. See [`log_once!`].
+#[macro_export]
+macro_rules! log_fatal_once {
+    ($($arg:tt)+) => {
+        $crate::log_once!($crate::LogSeverity::Fatal, $($arg)+)
+    };
+}
+
+/// Logs a message with [`Debug`](LogSeverity::Debug) severity at most once per period.
+/// See [`log_throttle!`].
+#[macro_export]
+macro_rules! log_debug_throttle {
+    ($($arg:tt)+) => {
+        $crate::log_throttle!($crate::LogSeverity::Debug, $($arg)+)
+    };
+}
+
+/// Logs a message with [`Info`](LogSeverity::Info) severity at most once per period.
+/// See [`log_throttle!`].
+#[macro_export]
+macro_rules! log_info_throttle {
+    ($($arg:tt)+) => {
+        $crate::log_throttle!($crate::LogSeverity::Info, $($arg)+)
+    };
+}
+
+/// Logs a message with [`Warn`](LogSeverity::Warn) severity at most once per period.
+/// See [`log_throttle!`].
+#[macro_export]
+macro_rules! log_warn_throttle {
+    ($($arg:tt)+) => {
+        $crate::log_throttle!($crate::LogSeverity::Warn, $($arg)+)
+    };
+}
+
+/// Logs a message with [`Error`](LogSeverity::Error) severity at most once per period.
+/// See [`log_throttle!`].
+#[macro_export]
+macro_rules! log_error_throttle {
+    ($($arg:tt)+) => {
+        $crate::log_throttle!($crate::LogSeverity::Error, $($arg)+)
+    };
+}
+
+/// Logs a message with [`Fatal`](LogSeverity::Fatal) severity at most once per period.
+/// See [`log_throttle!`].
+#[macro_export]
+macro_rules! log_fatal_throttle {
+    ($($arg:tt)+) => {
+        $crate::log_throttle!($crate::LogSeverity::Fatal, $($arg)+)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::LOGGING_USERS;
+    use std::os::raw::{c_char, c_int};
+
+    // The logger names and severities seen by the test output handler.
+    static RECEIVED: Mutex<Vec<(String, c_int)>> = parking_lot::const_mutex(Vec::new());
+
+    unsafe extern "C" fn test_output_handler(
+        _location: *const rcutils_log_location_t,
+        severity: c_int,
+        name: *const c_char,
+        _timestamp: rcutils_time_point_value_t,
+        _format: *const c_char,
+        _args: *mut va_list,
+    ) {
+        let name = CStr::from_ptr(name).to_string_lossy().into_owned();
+        RECEIVED.lock().push((name, severity));
+    }
+
+    fn received_by(logger_name: &str) -> Vec<c_int> {
+        RECEIVED
+            .lock()
+            .iter()
+            .filter(|(name, _)| name == logger_name)
+            .map(|(_, severity)| *severity)
+            .collect()
+    }
+
+    #[test]
+    fn log_macros_reach_output_handler() {
+        // Prevents contexts created by other tests from replacing the output handler.
+        let _logging_users = LOGGING_USERS.lock();
+        // Initializes the logging system, which would replace the output handler otherwise.
+        crate::log_debug!("rclrs_logging_test_init", "Initializing logging");
+        // SAFETY: The handler has the correct signature and is valid for the whole program.
+        let previous_handler = unsafe {
+            let previous_handler = rcutils_logging_get_output_handler();
+            rcutils_logging_set_output_handler(Some(test_output_handler));
+            previous_handler
+        };
+
+        // Other tests may change the default logger level.
+        for logger_name in [
+            "rclrs_logging_test",
+            "rclrs_logging_test_once",
+            "rclrs_logging_test_throttle",
+            "rclrs_logging_test_scoped",
+        ] {
+            set_logger_level(logger_name, LogSeverity::Debug).unwrap();
+        }
+
+        crate::log_info!("rclrs_logging_test", "Hello {}", "world");
+        crate::log_error!(String::from("rclrs_logging_test"), "Goodbye");
+        for _ in 0..3 {
+            crate::log_warn_once!("rclrs_logging_test_once", "Only once");
+            crate::log_warn_throttle!(
+                "rclrs_logging_test_throttle",
+                Duration::from_secs(60),
+                "Only once per minute"
+            );
+        }
+        {
+            let _scope = LoggingScope::enter("rclrs_logging_test_scoped");
+            let count = 1;
+            crate::log_info!(CurrentLogger, "Scoped {}", count);
+            crate::log_warn_throttle!(
+                CurrentLogger,
+                Duration::from_secs(60),
+                "Scoped and throttled"
+            );
+        }
+        crate::log_info!(CurrentLogger, "Outside the scope");
+
+        // SAFETY: Restoring the handler that was there before.
+        unsafe { rcutils_logging_set_output_handler(previous_handler) };
+        assert_eq!(
+            received_by("rclrs_logging_test"),
+            [
+                LogSeverity::Info.to_native() as c_int,
+                LogSeverity::Error.to_native() as c_int
+            ]
+        );
+        assert_eq!(received_by("rclrs_logging_test_once").len(), 1);
+        assert_eq!(received_by("rclrs_logging_test_throttle").len(), 1);
+        assert_eq!(
+            received_by("rclrs_logging_test_scoped"),
+            [
+                LogSeverity::Info.to_native() as c_int,
+                LogSeverity::Warn.to_native() as c_int
+            ]
+        );
+    }
+
+    #[test]
+    fn logging_scopes_nest_and_restore() -> Result<(), RclrsError> {
+        let context = crate::Context::new([])?;
+        let node = context.create_node("logging_scope_test_node")?;
+        assert_eq!(current_logger_name(), None);
+        {
+            let _node_scope = node.enter_logging_scope();
+            assert_eq!(
+                current_logger_name().as_deref(),
+                Some("logging_scope_test_node")
+            );
+            {
+                let _inner_scope = LoggingScope::enter("rclrs_logging_test_inner");
+                assert_eq!(
+                    current_logger_name().as_deref(),
+                    Some("rclrs_logging_test_inner")
+                );
+            }
+            assert_eq!(
+                current_logger_name().as_deref(),
+                Some("logging_scope_test_node")
+            );
+            // Other threads are not affected
+            std::thread::spawn(|| assert_eq!(current_logger_name(), None))
+                .join()
+                .unwrap();
+        }
+        assert_eq!(current_logger_name(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn logger_level_can_be_set() -> Result<(), RclrsError> {
+        let logger_name = "rclrs_logging_test_level";
+        let logger_name_c = to_cstring_lossy(logger_name);
+        for level in [LogSeverity::Debug, LogSeverity::Fatal] {
+            set_logger_level(logger_name, level)?;
+            // SAFETY: The logger name is a valid C string.
+            let actual_level = unsafe { rcutils_logging_get_logger_level(logger_name_c.as_ptr()) };
+            assert_eq!(actual_level, level.to_native() as c_int);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn throttle_waits_for_period() {
+        let last_logged = LastLogged::new();
+        assert!(throttle_elapsed(&last_logged, Duration::from_secs(60)));
+        assert!(!throttle_elapsed(&last_logged, Duration::from_secs(60)));
+        assert!(throttle_elapsed(&last_logged, Duration::ZERO));
+    }
+
+    #[test]
+    #[cfg(not(ros_distro = "foxy"))]
+    fn lost_messages_are_warned_about() {
+        // See log_macros_reach_output_handler()
+        let _logging_users = LOGGING_USERS.lock();
+        crate::log_debug!("rclrs_logging_test_init", "Initializing logging");
+        // SAFETY: The handler has the correct signature and is valid for the whole program.
+        let previous_handler = unsafe {
+            let previous_handler = rcutils_logging_get_output_handler();
+            rcutils_logging_set_output_handler(Some(test_output_handler));
+            previous_handler
+        };
+        set_logger_level("rclrs_logging_test_drops", LogSeverity::Debug).unwrap();
+
+        // The reports are simulated here to check the throttling, which can't be controlled with
+        // real lost messages.
+        let mut callback = crate::drop_warning_callback(
+            String::from("rclrs_logging_test_drops"),
+            String::from("/topic"),
+        );
+        let lost = |total_count_change| crate::MessageLostStatus {
+            total_count: 0,
+            total_count_change,
+        };
+        callback(lost(0));
+        callback(lost(3));
+        // Throttled
+        callback(lost(2));
+
+        // SAFETY: Restoring the handler that was there before.
+        unsafe { rcutils_logging_set_output_handler(previous_handler) };
+        assert_eq!(
+            received_by("rclrs_logging_test_drops"),
+            [LogSeverity::Warn.to_native() as c_int]
+        );
+    }
+
+    #[test]
+    #[cfg(not(ros_distro = "foxy"))]
+    fn subscriptions_warn_about_messages_lost_by_the_middleware() -> Result<(), RclrsError> {
+        let context = crate::Context::new([])?;
+        let mut node = context.create_node("rclrs_logging_test_drop_node")?;
+        // Not every RMW implementation supports the event for lost messages
+        if !crate::message_lost_event_supported(&mut node)? {
+            return Ok(());
+        }
+        // Bursts of messages overflow a history of depth 1
+        let qos = crate::QoSProfile {
+            history: crate::QoSHistoryPolicy::KeepLast { depth: 1 },
+            ..crate::QOS_PROFILE_SENSOR_DATA
+        };
+        let publisher = node.create_publisher::<test_msgs::msg::UnboundedSequences>(
+            "rclrs_logging_test_drops",
+            qos,
+        )?;
+        let options = crate::SubscriptionOptions {
+            warn_on_drop: true,
+            ..Default::default()
+        };
+        let subscription = node
+            .create_subscription_with_options::<test_msgs::msg::UnboundedSequences, _>(
+                "rclrs_logging_test_drops",
+                qos,
+                options,
+                |_| {},
+            )?;
+        assert!(subscription.drop_warning.is_some());
+        publisher.wait_for_matched(1, Duration::from_secs(5))?;
+
+        // See log_macros_reach_output_handler()
+        let _logging_users = LOGGING_USERS.lock();
+        crate::log_debug!("rclrs_logging_test_init", "Initializing logging");
+        // SAFETY: The handler has the correct signature and is valid for the whole program.
+        let previous_handler = unsafe {
+            let previous_handler = rcutils_logging_get_output_handler();
+            rcutils_logging_set_output_handler(Some(test_output_handler));
+            previous_handler
+        };
+        let logger_name = node.logger_name();
+        set_logger_level(&logger_name, LogSeverity::Debug)?;
+        let warned =
+            crate::publish_until_lost(&node, &publisher, || !received_by(&logger_name).is_empty());
+
+        // SAFETY: Restoring the handler that was there before.
+        unsafe { rcutils_logging_set_output_handler(previous_handler) };
+        assert!(warned?, "No warning about lost messages was logged");
+        // The warning is throttled, so later drops are only reported after a while
+        assert_eq!(
+            received_by(&logger_name),
+            [LogSeverity::Warn.to_native() as c_int]
+        );
+        Ok(())
+    }
+}
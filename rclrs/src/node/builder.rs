@@ -1,7 +1,12 @@
+use crate::allocator::context_allocator;
 use crate::rcl_bindings::*;
-use crate::{Context, Node, RclrsError, ToResult};
+use crate::{
+    CallbackGroup, CallbackGroupType, Clock, ClockType, Context, ContextHandle, Node, NodeHandle,
+    RclrsError, ToResult,
+};
 
 use std::ffi::CString;
+use std::os::raw::c_char;
 
 use parking_lot::Mutex;
 use std::sync::Arc;
@@ -13,6 +18,9 @@ use std::sync::Arc;
 ///
 /// The default values for optional fields are:
 /// - `namespace: "/"`
+/// - `use_global_arguments: true`
+/// - `arguments: []`
+/// - `enable_rosout: true`
 ///
 /// # Example
 /// ```
@@ -37,9 +45,12 @@ use std::sync::Arc;
 /// [2]: crate::Node::builder
 ///
 pub struct NodeBuilder {
-    context: Arc<Mutex<rcl_context_t>>,
+    context: Arc<ContextHandle>,
     name: String,
     namespace: String,
+    use_global_arguments: bool,
+    arguments: Vec<String>,
+    enable_rosout: bool,
 }
 
 impl NodeBuilder {
@@ -84,6 +95,9 @@ impl NodeBuilder {
             context: context.handle.clone(),
             name: name.to_string(),
             namespace: "/".to_string(),
+            use_global_arguments: true,
+            arguments: vec![],
+            enable_rosout: true,
         }
     }
 
@@ -142,6 +156,81 @@ impl NodeBuilder {
         self
     }
 
+    /// Enables or disables using global arguments.
+    ///
+    /// The "global" arguments are those used in [creating the context][1].
+    ///
+    /// # Example
+    /// ```
+    /// # use rclrs::{Context, Node, RclrsError};
+    /// let context_args = ["--ros-args", "--remap", "__node:=your_node"]
+    ///   .map(String::from);
+    /// let context = Context::new(context_args)?;
+    /// // Ignore the global arguments:
+    /// let node_without_global_args = context
+    ///   .create_node_builder("my_node")
+    ///   .use_global_arguments(false)
+    ///   .build()?;
+    /// assert_eq!(node_without_global_args.name(), "my_node");
+    /// // Do not ignore the global arguments:
+    /// let node_with_global_args = context
+    ///   .create_node_builder("my_other_node")
+    ///   .use_global_arguments(true)
+    ///   .build()?;
+    /// assert_eq!(node_with_global_args.name(), "your_node");
+    /// # Ok::<(), RclrsError>(())
+    /// ```
+    ///
+    /// [1]: crate::Context::new
+    pub fn use_global_arguments(mut self, enable: bool) -> Self {
+        self.use_global_arguments = enable;
+        self
+    }
+
+    /// Sets node-specific command line arguments.
+    ///
+    /// These arguments are parsed the same way as those for [`Context::new()`][1].
+    /// However, the node-specific command line arguments have higher precedence than the arguments
+    /// used in creating the context.
+    ///
+    /// For more details about command line arguments, see [here][2].
+    ///
+    /// # Example
+    /// ```
+    /// # use rclrs::{Context, Node, RclrsError};
+    /// // Usually, this would change the name of "my_node" to "context_args_node":
+    /// let context_args = ["--ros-args", "--remap", "my_node:__node:=context_args_node"]
+    ///   .map(String::from);
+    /// let context = Context::new(context_args)?;
+    /// // But the node-specific arguments will change it to "node_args_node":
+    /// let node_args = ["--ros-args", "--remap", "my_node:__node:=node_args_node"]
+    ///   .map(String::from);
+    /// let node = context
+    ///   .create_node_builder("my_node")
+    ///   .arguments(node_args)
+    ///   .build()?;
+    /// assert_eq!(node.name(), "node_args_node");
+    /// # Ok::<(), RclrsError>(())
+    /// ```
+    ///
+    /// [1]: crate::Context::new
+    /// [2]: https://design.ros2.org/articles/ros_command_line_arguments.html
+    pub fn arguments(mut self, arguments: impl IntoIterator<Item = String>) -> Self {
+        self.arguments = arguments.into_iter().collect();
+        self
+    }
+
+    /// Enables or disables logging to rosout.
+    ///
+    /// When enabled, log messages are published to the `/rosout` topic in addition to
+    /// standard output.
+    ///
+    /// Disabling this saves the overhead of the rosout publisher, e.g. for high-rate nodes.
+    pub fn enable_rosout(mut self, enable: bool) -> Self {
+        self.enable_rosout = enable;
+        self
+    }
+
     /// Builds the node instance.
     ///
     /// Node name and namespace validation is performed in this method.
@@ -149,7 +238,7 @@ impl NodeBuilder {
     /// For example usage, see the [`NodeBuilder`][1] docs.
     ///
     /// # Panics
-    /// When the node name or namespace contain null bytes.
+    /// When the node name, namespace or arguments contain null bytes.
     ///
     /// [1]: crate::NodeBuilder
     pub fn build(&self) -> Result<Node, RclrsError> {
@@ -159,16 +248,14 @@ impl NodeBuilder {
         // SAFETY: No preconditions for this function.
         let mut node_handle = unsafe { rcl_get_zero_initialized_node() };
 
-        unsafe {
-            // SAFETY: No preconditions for this function.
-            let context_handle = &mut *self.context.lock();
-            // SAFETY: No preconditions for this function.
-            let node_options = rcl_node_get_default_options();
-
-            // SAFETY: The node handle is zero-initialized as expected by this function.
-            // The strings and node options are copied by this function, so we don't need
-            // to keep them alive.
-            // The context handle has to be kept alive because it is co-owned by the node.
+        let mut node_options = self.create_node_options()?;
+        let context_handle = &mut *self.context.rcl_context.lock();
+
+        // SAFETY: The node handle is zero-initialized as expected by this function.
+        // The strings and node options are copied by this function, so we don't need
+        // to keep them alive.
+        // The context handle has to be kept alive because it is co-owned by the node.
+        let ret = unsafe {
             rcl_node_init(
                 &mut node_handle,
                 node_name.as_ptr(),
@@ -176,15 +263,118 @@ impl NodeBuilder {
                 context_handle,
                 &node_options,
             )
-            .ok()?;
+            .ok_with_context("rcl_node_init")
         };
+        // SAFETY: The node options are initialized and not used anymore.
+        unsafe { rcl_node_options_fini(&mut node_options).ok()? };
+        // Move the check after the fini()
+        ret?;
 
-        let handle = Arc::new(Mutex::new(node_handle));
+        let handle = Arc::new(NodeHandle {
+            rcl_node_mtx: Mutex::new(node_handle),
+            context_handle: self.context.clone(),
+        });
 
         Ok(Node {
             handle,
-            context: self.context.clone(),
             subscriptions: std::vec![],
+            guard_conditions: std::vec![],
+            timers: std::vec![],
+            events: std::vec![],
+            default_callback_group: Arc::new(CallbackGroup::new(
+                CallbackGroupType::MutuallyExclusive,
+            )),
+            clock: Clock::new(ClockType::RosTime)?,
         })
     }
+
+    /// Creates node options.
+    ///
+    /// Any fields not present in the builder will have their default value.
+    /// For detailed descriptions of each field, see the respective methods.
+    fn create_node_options(&self) -> Result<rcl_node_options_t, RclrsError> {
+        // SAFETY: No preconditions for this function.
+        let mut node_options = unsafe { rcl_node_get_default_options() };
+        node_options.allocator = context_allocator(&self.context.rcl_context.lock());
+
+        let cstring_args = self
+            .arguments
+            .iter()
+            .map(|s| CString::new(s.as_str()).unwrap())
+            .collect::<Vec<_>>();
+        let cstring_arg_ptrs = cstring_args
+            .iter()
+            .map(|s| s.as_ptr())
+            .collect::<Vec<*const c_char>>();
+        // SAFETY: The arguments in node_options are zero-initialized, as expected by this
+        // function. The cstring_arg_ptrs are not stored.
+        unsafe {
+            rcl_parse_arguments(
+                cstring_arg_ptrs.len() as i32,
+                if cstring_arg_ptrs.is_empty() {
+                    std::ptr::null()
+                } else {
+                    cstring_arg_ptrs.as_ptr()
+                },
+                node_options.allocator.clone(),
+                &mut node_options.arguments,
+            )
+        }
+        .ok()?;
+
+        node_options.use_global_arguments = self.use_global_arguments;
+        node_options.enable_rosout = self.enable_rosout;
+        Ok(node_options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    // Helper for reading back the options a node was created with
+    fn node_options(node: &Node) -> (bool, bool) {
+        let handle = &*node.handle.lock();
+        // SAFETY: The node handle is valid, and the options are owned by the node.
+        let options = unsafe { &*rcl_node_get_options(handle) };
+        (options.use_global_arguments, options.enable_rosout)
+    }
+
+    #[test]
+    fn default_options() -> Result<(), RclrsError> {
+        let context = Context::new([])?;
+        let node = context.create_node("default_options_node")?;
+        assert_eq!(node_options(&node), (true, true));
+        Ok(())
+    }
+
+    // Waits until the node has been discovered, and returns its publishers.
+    fn published_topics(node: &Node, node_name: &str) -> Vec<String> {
+        let start = Instant::now();
+        loop {
+            match node.get_publisher_names_and_types_by_node(node_name, "/") {
+                Ok(topics) => return topics.into_keys().collect(),
+                Err(e) if start.elapsed() > Duration::from_secs(5) => panic!("{}", e),
+                Err(_) => std::thread::sleep(Duration::from_millis(10)),
+            }
+        }
+    }
+
+    #[test]
+    fn rosout_can_be_disabled() -> Result<(), RclrsError> {
+        // The rosout publisher is only created when the context configures logging.
+        let _lock = crate::context::CONFIGURE_LOGGING_TEST_LOCK.lock();
+        let context = Context::builder([]).configure_logging(true).build()?;
+        let node = context
+            .create_node_builder("no_rosout_node")
+            .use_global_arguments(false)
+            .enable_rosout(false)
+            .build()?;
+        assert_eq!(node_options(&node), (false, false));
+        let rosout_node = context.create_node("rosout_node")?;
+        assert!(published_topics(&rosout_node, "rosout_node").contains(&String::from("/rosout")));
+        assert!(!published_topics(&node, "no_rosout_node").contains(&String::from("/rosout")));
+        Ok(())
+    }
 }
@@ -0,0 +1,443 @@
+use crate::error::{EventErrorCode, RclReturnCode, ToResult};
+use crate::rcl_bindings::*;
+use crate::{PublisherHandle, RclrsError, SubscriptionHandle};
+
+use std::boxed::Box;
+use std::sync::Arc;
+
+use parking_lot::{Mutex, MutexGuard};
+
+// SAFETY: The functions accessing this type, including drop(), shouldn't care about the thread
+// they are running in. Therefore, this type can be safely sent to another thread.
+unsafe impl Send for rcl_event_t {}
+
+/// Internal struct used by events.
+pub struct EventHandle {
+    handle: Mutex<rcl_event_t>,
+    // The event refers to the subscription or publisher it was created from, which therefore
+    // must be kept alive.
+    _parent_handle: Arc<dyn Send + Sync>,
+}
+
+impl EventHandle {
+    pub(crate) fn lock(&self) -> MutexGuard<rcl_event_t> {
+        self.handle.lock()
+    }
+}
+
+impl Drop for EventHandle {
+    fn drop(&mut self) {
+        // SAFETY: No preconditions for this function (besides passing in a valid event). The
+        // parent handle is only dropped after this.
+        unsafe { rcl_event_fini(self.handle.get_mut()) };
+    }
+}
+
+/// Trait to be implemented by concrete [`Event`]s.
+pub trait EventBase: Send + Sync {
+    /// Internal function to get a reference to the `rcl` handle.
+    fn handle(&self) -> &EventHandle;
+    /// Tries to take the event status and run the callback with it.
+    fn execute(&self) -> Result<(), RclrsError>;
+}
+
+/// A status that is reported by an [`Event`].
+///
+/// This trait is implemented by the statuses of all supported event types, and is not meant to be
+/// implemented by users.
+pub trait EventStatus: Sized + Send + 'static {
+    /// The corresponding `rmw` status struct.
+    #[doc(hidden)]
+    type RmwStatus;
+    /// Converts the `rmw` status struct.
+    #[doc(hidden)]
+    fn from_rmw(status: &Self::RmwStatus) -> Self;
+}
+
+/// An [`EventStatus`] that is reported for subscriptions.
+pub trait SubscriptionEventStatus: EventStatus {
+    /// The type of the event.
+    #[doc(hidden)]
+    const EVENT_TYPE: rcl_subscription_event_type_t;
+}
+
+/// An [`EventStatus`] that is reported for publishers.
+pub trait PublisherEventStatus: EventStatus {
+    /// The type of the event.
+    #[doc(hidden)]
+    const EVENT_TYPE: rcl_publisher_event_type_t;
+}
+
+/// Reports that a subscription did not receive a message within the deadline of its QoS profile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RequestedDeadlineMissedStatus {
+    /// The total number of missed deadlines so far.
+    pub total_count: i32,
+    /// The number of missed deadlines since the last time the status was taken.
+    pub total_count_change: i32,
+}
+
+impl EventStatus for RequestedDeadlineMissedStatus {
+    type RmwStatus = rmw_requested_deadline_missed_status_t;
+    fn from_rmw(status: &Self::RmwStatus) -> Self {
+        Self {
+            total_count: status.total_count,
+            total_count_change: status.total_count_change,
+        }
+    }
+}
+
+impl SubscriptionEventStatus for RequestedDeadlineMissedStatus {
+    const EVENT_TYPE: rcl_subscription_event_type_t =
+        rcl_subscription_event_type_t::RCL_SUBSCRIPTION_REQUESTED_DEADLINE_MISSED;
+}
+
+/// Reports that the liveliness of a publisher matched with a subscription has changed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LivelinessChangedStatus {
+    /// The number of matched publishers that are currently alive.
+    pub alive_count: i32,
+    /// The number of matched publishers that are currently not alive.
+    pub not_alive_count: i32,
+    /// The change of `alive_count` since the last time the status was taken.
+    pub alive_count_change: i32,
+    /// The change of `not_alive_count` since the last time the status was taken.
+    pub not_alive_count_change: i32,
+}
+
+impl EventStatus for LivelinessChangedStatus {
+    type RmwStatus = rmw_liveliness_changed_status_t;
+    fn from_rmw(status: &Self::RmwStatus) -> Self {
+        Self {
+            alive_count: status.alive_count,
+            not_alive_count: status.not_alive_count,
+            alive_count_change: status.alive_count_change,
+            not_alive_count_change: status.not_alive_count_change,
+        }
+    }
+}
+
+impl SubscriptionEventStatus for LivelinessChangedStatus {
+    const EVENT_TYPE: rcl_subscription_event_type_t =
+        rcl_subscription_event_type_t::RCL_SUBSCRIPTION_LIVELINESS_CHANGED;
+}
+
+/// Reports that a publisher did not publish a message within the deadline of its QoS profile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OfferedDeadlineMissedStatus {
+    /// The total number of missed deadlines so far.
+    pub total_count: i32,
+    /// The number of missed deadlines since the last time the status was taken.
+    pub total_count_change: i32,
+}
+
+impl EventStatus for OfferedDeadlineMissedStatus {
+    type RmwStatus = rmw_offered_deadline_missed_status_t;
+    fn from_rmw(status: &Self::RmwStatus) -> Self {
+        Self {
+            total_count: status.total_count,
+            total_count_change: status.total_count_change,
+        }
+    }
+}
+
+impl PublisherEventStatus for OfferedDeadlineMissedStatus {
+    const EVENT_TYPE: rcl_publisher_event_type_t =
+        rcl_publisher_event_type_t::RCL_PUBLISHER_OFFERED_DEADLINE_MISSED;
+}
+
+/// Reports that a publisher failed to assert its liveliness within the lease duration of its QoS
+/// profile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LivelinessLostStatus {
+    /// The total number of times the liveliness was lost so far.
+    pub total_count: i32,
+    /// The number of times the liveliness was lost since the last time the status was taken.
+    pub total_count_change: i32,
+}
+
+impl EventStatus for LivelinessLostStatus {
+    type RmwStatus = rmw_liveliness_lost_status_t;
+    fn from_rmw(status: &Self::RmwStatus) -> Self {
+        Self {
+            total_count: status.total_count,
+            total_count_change: status.total_count_change,
+        }
+    }
+}
+
+impl PublisherEventStatus for LivelinessLostStatus {
+    const EVENT_TYPE: rcl_publisher_event_type_t =
+        rcl_publisher_event_type_t::RCL_PUBLISHER_LIVELINESS_LOST;
+}
+
+/// Reports that messages were lost, i.e. they were sent by a publisher but never received.
+#[cfg(not(ros_distro = "foxy"))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MessageLostStatus {
+    /// The total number of messages lost so far.
+    pub total_count: usize,
+    /// The number of messages lost since the last time the status was taken.
+    pub total_count_change: usize,
+}
+
+#[cfg(not(ros_distro = "foxy"))]
+impl EventStatus for MessageLostStatus {
+    type RmwStatus = rmw_message_lost_status_t;
+    fn from_rmw(status: &Self::RmwStatus) -> Self {
+        Self {
+            total_count: status.total_count,
+            total_count_change: status.total_count_change,
+        }
+    }
+}
+
+#[cfg(not(ros_distro = "foxy"))]
+impl SubscriptionEventStatus for MessageLostStatus {
+    const EVENT_TYPE: rcl_subscription_event_type_t =
+        rcl_subscription_event_type_t::RCL_SUBSCRIPTION_MESSAGE_LOST;
+}
+
+/// An event that reports changes in the status of a subscription or publisher.
+///
+/// Events are created with [`Node::create_subscription_event`][1] and
+/// [`Node::create_publisher_event`][2]. The callback of the event is run by [`spin_once`][3] when
+/// the status has changed, and receives the new status.
+///
+/// [1]: crate::Node::create_subscription_event
+/// [2]: crate::Node::create_publisher_event
+/// [3]: crate::spin_once
+pub struct Event<S: EventStatus> {
+    pub(crate) handle: EventHandle,
+    /// The callback function that runs when the status has changed.
+    pub callback: Mutex<Box<dyn FnMut(S) + 'static + Send>>,
+}
+
+impl<S: EventStatus> Event<S> {
+    /// Creates a new event for a subscription.
+    pub(crate) fn new_for_subscription<F>(
+        subscription_handle: Arc<SubscriptionHandle>,
+        callback: F,
+    ) -> Result<Self, RclrsError>
+    where
+        S: SubscriptionEventStatus,
+        F: FnMut(S) + 'static + Send,
+    {
+        // SAFETY: Getting a zero-initialized value is always safe.
+        let mut event = unsafe { rcl_get_zero_initialized_event() };
+        unsafe {
+            // SAFETY: The event is zero-initialized as expected by this function. The
+            // subscription is kept alive because it is co-owned by the event.
+            rcl_subscription_event_init(&mut event, &*subscription_handle.lock(), S::EVENT_TYPE)
+                .ok()?;
+        }
+        Ok(Self {
+            handle: EventHandle {
+                handle: Mutex::new(event),
+                _parent_handle: subscription_handle,
+            },
+            callback: Mutex::new(Box::new(callback)),
+        })
+    }
+
+    /// Creates a new event for a publisher.
+    pub(crate) fn new_for_publisher<F>(
+        publisher_handle: Arc<PublisherHandle>,
+        callback: F,
+    ) -> Result<Self, RclrsError>
+    where
+        S: PublisherEventStatus,
+        F: FnMut(S) + 'static + Send,
+    {
+        // SAFETY: Getting a zero-initialized value is always safe.
+        let mut event = unsafe { rcl_get_zero_initialized_event() };
+        unsafe {
+            // SAFETY: The event is zero-initialized as expected by this function. The
+            // publisher is kept alive because it is co-owned by the event.
+            rcl_publisher_event_init(&mut event, &*publisher_handle.lock(), S::EVENT_TYPE).ok()?;
+        }
+        Ok(Self {
+            handle: EventHandle {
+                handle: Mutex::new(event),
+                _parent_handle: publisher_handle,
+            },
+            callback: Mutex::new(Box::new(callback)),
+        })
+    }
+
+    /// Takes the current status of the event.
+    ///
+    /// When the status has not changed since it was last taken, this will return an
+    /// [`EventTakeFailed`][1] wrapped in an [`RclrsError`].
+    ///
+    /// [1]: crate::EventErrorCode
+    pub fn take(&self) -> Result<S, RclrsError> {
+        // SAFETY: The rmw status structs are plain C structs, for which all zeroes is valid.
+        let mut status: S::RmwStatus = unsafe { std::mem::zeroed() };
+        unsafe {
+            // SAFETY: The event is valid, and the status struct matches the event type, which is
+            // guaranteed by the EventStatus impls.
+            rcl_take_event(
+                &*self.handle.lock(),
+                &mut status as *mut S::RmwStatus as *mut _,
+            )
+            .ok()?;
+        }
+        Ok(S::from_rmw(&status))
+    }
+}
+
+impl<S: EventStatus> EventBase for Event<S> {
+    fn handle(&self) -> &EventHandle {
+        &self.handle
+    }
+
+    fn execute(&self) -> Result<(), RclrsError> {
+        let status = match self.take() {
+            Ok(status) => status,
+            Err(RclrsError {
+                code: RclReturnCode::EventError(EventErrorCode::EventTakeFailed),
+                ..
+            }) => {
+                // Spurious wakeup, the status has not changed.
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        };
+        (*self.callback.lock())(status);
+        Ok(())
+    }
+}
+
+// Publishes bursts of large messages on a best-effort topic until `lost` returns true, or gives
+// up after a deadline. Large messages are fragmented by the middleware, and a burst of them
+// overflows its buffers, so some of them are lost by any subscription with the same QoS.
+#[cfg(all(test, not(ros_distro = "foxy")))]
+pub(crate) fn publish_until_lost(
+    node: &crate::Node,
+    publisher: &crate::Publisher<test_msgs::msg::UnboundedSequences>,
+    mut lost: impl FnMut() -> bool,
+) -> Result<bool, RclrsError> {
+    use crate::RclrsErrorFilter;
+    use std::time::{Duration, Instant};
+
+    let msg = test_msgs::msg::UnboundedSequences {
+        int32_values: vec![0; 256 * 1024],
+        ..Default::default()
+    };
+    let start = Instant::now();
+    while start.elapsed() < Duration::from_secs(10) {
+        for _ in 0..20 {
+            publisher.publish(&msg)?;
+        }
+        crate::spin_once(node, Some(Duration::from_millis(100))).timeout_ok()?;
+        if lost() {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+// Checks whether the RMW implementation supports the event for lost messages, by creating it for
+// a throwaway subscription.
+#[cfg(all(test, not(ros_distro = "foxy")))]
+pub(crate) fn message_lost_event_supported(node: &mut crate::Node) -> Result<bool, RclrsError> {
+    let subscription = node.create_subscription::<test_msgs::msg::Empty, _>(
+        "message_lost_event_support_probe",
+        crate::QOS_PROFILE_DEFAULT,
+        |_| {},
+    )?;
+    match node.create_subscription_event(&subscription, |_: MessageLostStatus| {}) {
+        Ok(_) => Ok(true),
+        Err(RclrsError {
+            code: RclReturnCode::Unsupported,
+            ..
+        }) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Context, QoSDuration, QoSProfile, RclrsErrorFilter, QOS_PROFILE_DEFAULT};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn slow_publisher_misses_deadline() -> Result<(), RclrsError> {
+        let context = Context::new([])?;
+        let mut node = context.create_node("deadline_event_test_node")?;
+        let qos = QoSProfile {
+            deadline: QoSDuration::Custom(Duration::from_millis(10)),
+            ..QOS_PROFILE_DEFAULT
+        };
+        let publisher =
+            node.create_publisher::<test_msgs::msg::Empty>("deadline_event_test", qos)?;
+        let missed = Arc::new(Mutex::new(None));
+        let event = node.create_publisher_event(&publisher, {
+            let missed = missed.clone();
+            move |status: OfferedDeadlineMissedStatus| *missed.lock() = Some(status)
+        });
+        let _event = match event {
+            Ok(event) => event,
+            // Not every RMW implementation supports this event
+            Err(RclrsError {
+                code: RclReturnCode::Unsupported,
+                ..
+            }) => return Ok(()),
+            Err(e) => return Err(e),
+        };
+
+        // Publish once, and then not again for much longer than the deadline
+        publisher.publish(test_msgs::msg::Empty::default())?;
+        let start = Instant::now();
+        while missed.lock().is_none() && start.elapsed() < Duration::from_secs(5) {
+            crate::spin_once(&node, Some(Duration::from_millis(100))).timeout_ok()?;
+        }
+        let status = missed
+            .lock()
+            .expect("The deadline missed event was not reported");
+        assert!(status.total_count >= 1);
+        assert!(status.total_count_change >= 1);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(not(ros_distro = "foxy"))]
+    fn message_lost_event_reports_consistent_counts() -> Result<(), RclrsError> {
+        let context = Context::new([])?;
+        let mut node = context.create_node("message_lost_event_test_node")?;
+        let publisher = node.create_publisher::<test_msgs::msg::UnboundedSequences>(
+            "message_lost_event_test",
+            crate::QOS_PROFILE_SENSOR_DATA,
+        )?;
+        let subscription = node.create_subscription::<test_msgs::msg::UnboundedSequences, _>(
+            "message_lost_event_test",
+            crate::QOS_PROFILE_SENSOR_DATA,
+            |_| {},
+        )?;
+        let statuses = Arc::new(Mutex::new(Vec::new()));
+        let event = node.create_subscription_event(&subscription, {
+            let statuses = statuses.clone();
+            move |status: MessageLostStatus| statuses.lock().push(status)
+        });
+        let _event = match event {
+            Ok(event) => event,
+            // Not every RMW implementation supports this event
+            Err(RclrsError {
+                code: RclReturnCode::Unsupported,
+                ..
+            }) => return Ok(()),
+            Err(e) => return Err(e),
+        };
+
+        publisher.wait_for_matched(1, Duration::from_secs(5))?;
+        let lost = publish_until_lost(&node, &publisher, || !statuses.lock().is_empty())?;
+        assert!(lost, "No lost messages were reported");
+        for status in statuses.lock().iter() {
+            assert!(status.total_count_change >= 1);
+            assert!(status.total_count >= status.total_count_change);
+        }
+        Ok(())
+    }
+}
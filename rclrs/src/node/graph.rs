@@ -0,0 +1,268 @@
+use crate::allocator::node_allocator;
+use crate::rcl_bindings::*;
+use crate::{Node, RclrsError, ToResult};
+
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use std::slice;
+
+/// A map from names of topics or services to the names of their types.
+///
+/// A name can have several types when different nodes disagree about the type.
+pub type NamesAndTypes = HashMap<String, Vec<String>>;
+
+impl Drop for rcl_names_and_types_t {
+    fn drop(&mut self) {
+        // SAFETY: No preconditions for this function besides a valid pointer. Zero-initialized
+        // names and types are valid, too.
+        unsafe { rcl_names_and_types_fini(self) };
+    }
+}
+
+impl Node {
+    /// Returns the names and types of all services in the ROS graph.
+    ///
+    /// Services of other nodes only show up after they have been discovered, which may take a
+    /// moment after they were created.
+    ///
+    /// # Example
+    /// ```
+    /// # use rclrs::{Context, RclrsError};
+    /// let context = Context::new([])?;
+    /// let node = context.create_node("my_node")?;
+    /// for (name, types) in node.get_service_names_and_types()? {
+    ///     println!("{}: {}", name, types.join(", "));
+    /// }
+    /// # Ok::<(), RclrsError>(())
+    /// ```
+    pub fn get_service_names_and_types(&self) -> Result<NamesAndTypes, RclrsError> {
+        let rcl_node = &*self.handle.lock();
+        let mut allocator = node_allocator(rcl_node);
+        // SAFETY: No preconditions for this function.
+        let mut names_and_types = unsafe { rmw_get_zero_initialized_names_and_types() };
+        // SAFETY: The node is valid, and the names and types are zero-initialized.
+        unsafe {
+            rcl_get_service_names_and_types(rcl_node, &mut allocator, &mut names_and_types).ok()?
+        };
+        Ok(convert_names_and_types(&names_and_types))
+    }
+
+    /// Returns the names and types of the services offered by the given node.
+    ///
+    /// The node is identified by its name and namespace, e.g. `"my_node"` and `"/"`. This
+    /// returns an error with code [`NodeNameNonexistent`][1] when no such node has been
+    /// discovered.
+    ///
+    /// [1]: crate::NodeErrorCode::NodeNameNonexistent
+    pub fn get_service_names_and_types_by_node(
+        &self,
+        node_name: &str,
+        node_namespace: &str,
+    ) -> Result<NamesAndTypes, RclrsError> {
+        let node_name = CString::new(node_name).unwrap();
+        let node_namespace = CString::new(node_namespace).unwrap();
+        let rcl_node = &*self.handle.lock();
+        let mut allocator = node_allocator(rcl_node);
+        // SAFETY: No preconditions for this function.
+        let mut names_and_types = unsafe { rmw_get_zero_initialized_names_and_types() };
+        // SAFETY: The node is valid, the strings are valid for the duration of the call, and the
+        // names and types are zero-initialized.
+        unsafe {
+            rcl_get_service_names_and_types_by_node(
+                rcl_node,
+                &mut allocator,
+                node_name.as_ptr(),
+                node_namespace.as_ptr(),
+                &mut names_and_types,
+            )
+            .ok()?
+        };
+        Ok(convert_names_and_types(&names_and_types))
+    }
+
+    /// Returns the names and types of the topics that the given node publishes on.
+    ///
+    /// The node is identified like for [`get_service_names_and_types_by_node()`][1], and the same
+    /// errors apply.
+    ///
+    /// [1]: Node::get_service_names_and_types_by_node
+    pub fn get_publisher_names_and_types_by_node(
+        &self,
+        node_name: &str,
+        node_namespace: &str,
+    ) -> Result<NamesAndTypes, RclrsError> {
+        let node_name = CString::new(node_name).unwrap();
+        let node_namespace = CString::new(node_namespace).unwrap();
+        let rcl_node = &*self.handle.lock();
+        let mut allocator = node_allocator(rcl_node);
+        // SAFETY: No preconditions for this function.
+        let mut names_and_types = unsafe { rmw_get_zero_initialized_names_and_types() };
+        // SAFETY: The node is valid, the strings are valid for the duration of the call, and the
+        // names and types are zero-initialized.
+        unsafe {
+            rcl_get_publisher_names_and_types_by_node(
+                rcl_node,
+                &mut allocator,
+                false,
+                node_name.as_ptr(),
+                node_namespace.as_ptr(),
+                &mut names_and_types,
+            )
+            .ok()?
+        };
+        Ok(convert_names_and_types(&names_and_types))
+    }
+
+    /// Returns the number of clients of the given service.
+    ///
+    /// The service name must be fully qualified, e.g. `"/my_service"`. Like for
+    /// [`get_service_names_and_types()`][1], clients of other nodes are only counted after they
+    /// have been discovered.
+    ///
+    /// [1]: Node::get_service_names_and_types
+    // rcl_count_clients() was added in Iron
+    #[cfg(not(any(ros_distro = "foxy", ros_distro = "galactic", ros_distro = "humble")))]
+    pub fn count_clients(&self, service_name: &str) -> Result<usize, RclrsError> {
+        let service_name = CString::new(service_name).unwrap();
+        let mut count = 0;
+        // SAFETY: The node is valid, and the string is valid for the duration of the call.
+        unsafe { rcl_count_clients(&*self.handle.lock(), service_name.as_ptr(), &mut count).ok()? };
+        Ok(count)
+    }
+
+    /// Returns the number of servers of the given service.
+    ///
+    /// The service name must be fully qualified, like for [`count_clients()`][1].
+    ///
+    /// [1]: Node::count_clients
+    // rcl_count_services() was added in Iron
+    #[cfg(not(any(ros_distro = "foxy", ros_distro = "galactic", ros_distro = "humble")))]
+    pub fn count_services(&self, service_name: &str) -> Result<usize, RclrsError> {
+        let service_name = CString::new(service_name).unwrap();
+        let mut count = 0;
+        // SAFETY: The node is valid, and the string is valid for the duration of the call.
+        unsafe {
+            rcl_count_services(&*self.handle.lock(), service_name.as_ptr(), &mut count).ok()?
+        };
+        Ok(count)
+    }
+}
+
+// Copies the names and types into owned strings. They are finalized when dropped.
+fn convert_names_and_types(names_and_types: &rcl_names_and_types_t) -> NamesAndTypes {
+    let names = string_array_to_vec(&names_and_types.names);
+    if names.is_empty() {
+        return NamesAndTypes::new();
+    }
+    // SAFETY: rcl fills in one array of types for each name.
+    let types = unsafe { slice::from_raw_parts(names_and_types.types, names.len()) };
+    names
+        .into_iter()
+        .zip(types.iter().map(string_array_to_vec))
+        .collect()
+}
+
+fn string_array_to_vec(array: &rcutils_string_array_t) -> Vec<String> {
+    if array.size == 0 || array.data.is_null() {
+        return Vec::new();
+    }
+    // SAFETY: The array holds `size` valid pointers to nul-terminated strings.
+    unsafe { slice::from_raw_parts(array.data, array.size) }
+        .iter()
+        .map(|&s| {
+            // SAFETY: See above. The strings are copied right away.
+            unsafe { CStr::from_ptr(s) }.to_string_lossy().into_owned()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rcl_bindings::*;
+    use crate::{Context, NodeErrorCode, RclReturnCode, RclrsError, ToResult};
+    use std::ffi::CString;
+    use std::time::{Duration, Instant};
+
+    // rclrs has no service support yet, so the service in the test below is created directly
+    // with rcl, using the C type support of a service type from test_msgs.
+    #[link(name = "test_msgs__rosidl_typesupport_c")]
+    extern "C" {
+        fn rosidl_typesupport_c__get_service_type_support_handle__test_msgs__srv__Empty(
+        ) -> *const rosidl_service_type_support_t;
+    }
+
+    #[test]
+    fn service_names_and_types_of_node_without_services() -> Result<(), crate::RclrsError> {
+        let context = Context::new([])?;
+        let node = context
+            .create_node_builder("graph_test_node")
+            .namespace("/graph_test")
+            .build()?;
+        let services =
+            node.get_service_names_and_types_by_node("graph_test_node", "/graph_test")?;
+        assert!(services.is_empty());
+        assert!(node
+            .get_service_names_and_types()?
+            .keys()
+            .all(|name| !name.starts_with("/graph_test/")));
+        let err = node
+            .get_service_names_and_types_by_node("nonexistent_node", "/graph_test")
+            .unwrap_err();
+        assert_eq!(
+            err.code,
+            RclReturnCode::NodeError(NodeErrorCode::NodeNameNonexistent)
+        );
+        Ok(())
+    }
+    #[test]
+    fn service_shows_up_after_discovery() -> Result<(), RclrsError> {
+        let context = Context::new([])?;
+        let node = context
+            .create_node_builder("graph_service_test_node")
+            .namespace("/graph_test")
+            .build()?;
+        // SAFETY: Getting a zero-initialized value and the default options is always safe.
+        let mut service = unsafe { rcl_get_zero_initialized_service() };
+        let options = unsafe { rcl_service_get_default_options() };
+        let service_name = CString::new("empty_service").unwrap();
+        // SAFETY: The service is zero-initialized, the node is valid, and the type support and
+        // strings are valid for the duration of the call.
+        unsafe {
+            rcl_service_init(
+                &mut service,
+                &*node.handle.lock(),
+                rosidl_typesupport_c__get_service_type_support_handle__test_msgs__srv__Empty(),
+                service_name.as_ptr(),
+                &options,
+            )
+            .ok()?
+        };
+
+        let expected_types = vec!["test_msgs/srv/Empty".to_string()];
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            let services_of_node =
+                node.get_service_names_and_types_by_node("graph_service_test_node", "/graph_test")?;
+            let services = node.get_service_names_and_types()?;
+            if services_of_node.get("/graph_test/empty_service") == Some(&expected_types)
+                && services.get("/graph_test/empty_service") == Some(&expected_types)
+            {
+                break;
+            }
+            assert!(
+                Instant::now() < deadline,
+                "The service was not discovered in time"
+            );
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        #[cfg(not(any(ros_distro = "foxy", ros_distro = "galactic", ros_distro = "humble")))]
+        {
+            assert_eq!(node.count_services("/graph_test/empty_service")?, 1);
+            assert_eq!(node.count_clients("/graph_test/empty_service")?, 0);
+        }
+
+        // SAFETY: The service was initialized with this node.
+        unsafe { rcl_service_fini(&mut service, &mut *node.handle.lock()).ok()? };
+        Ok(())
+    }
+}
@@ -0,0 +1,61 @@
+use crate::rcl_bindings::*;
+
+use std::time::{Duration, SystemTime};
+
+/// A globally unique identifier of a publisher.
+///
+/// This can be used to tell apart messages coming from different publishers on the same topic.
+/// GIDs are only meaningful in comparison to other GIDs from the same RMW implementation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Gid {
+    data: [u8; RMW_GID_STORAGE_SIZE as usize],
+}
+
+impl Gid {
+    /// Returns the raw bytes of the GID.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+impl From<&rmw_gid_t> for Gid {
+    fn from(gid: &rmw_gid_t) -> Self {
+        Self { data: gid.data }
+    }
+}
+
+/// Additional information about a received message.
+///
+/// This is obtained together with the message itself from [`Subscription::take_with_info`][1].
+///
+/// [1]: crate::Subscription::take_with_info
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MessageInfo {
+    /// The time when the message was published, if the RMW implementation reports it.
+    pub source_timestamp: Option<SystemTime>,
+    /// The time when the message was received, if the RMW implementation reports it.
+    pub received_timestamp: Option<SystemTime>,
+    /// The GID of the publisher that sent the message.
+    pub publisher_gid: Gid,
+    /// Whether the message was delivered via intra-process communication.
+    pub from_intra_process: bool,
+}
+
+impl From<&rmw_message_info_t> for MessageInfo {
+    fn from(info: &rmw_message_info_t) -> Self {
+        Self {
+            source_timestamp: timestamp_to_system_time(info.source_timestamp),
+            received_timestamp: timestamp_to_system_time(info.received_timestamp),
+            publisher_gid: Gid::from(&info.publisher_gid),
+            from_intra_process: info.from_intra_process,
+        }
+    }
+}
+
+// A timestamp of zero means that the RMW implementation does not support it.
+fn timestamp_to_system_time(nanoseconds: rmw_time_point_value_t) -> Option<SystemTime> {
+    if nanoseconds <= 0 {
+        return None;
+    }
+    Some(SystemTime::UNIX_EPOCH + Duration::from_nanos(nanoseconds as u64))
+}
@@ -1,21 +1,33 @@
 mod builder;
+mod event;
+mod graph;
+mod message_info;
 mod publisher;
 mod subscription;
+mod topic_name;
 pub use self::builder::*;
+pub use self::event::*;
+pub use self::graph::*;
+pub use self::message_info::*;
 pub use self::publisher::*;
 pub use self::subscription::*;
+pub use self::topic_name::*;
 
 use crate::rcl_bindings::*;
-use crate::{Context, QoSProfile, RclrsError, ToResult};
+use crate::{
+    CallbackGroup, CallbackGroupType, Clock, Context, ContextHandle, GuardCondition, LoggingScope,
+    QoSProfile, RclrsError, Time, Timer, ToResult,
+};
 use std::ffi::CStr;
 
 use std::cmp::PartialEq;
 use std::fmt;
 use std::sync::{Arc, Weak};
+use std::time::Duration;
 use std::vec::Vec;
 
 use libc::c_char;
-use parking_lot::Mutex;
+use parking_lot::{Mutex, MutexGuard};
 
 use rosidl_runtime_rs::Message;
 
@@ -30,6 +42,25 @@ impl Drop for rcl_node_t {
 // they are running in. Therefore, this type can be safely sent to another thread.
 unsafe impl Send for rcl_node_t {}
 
+/// Internal struct that owns an `rcl_node_t` together with the context it was created from.
+///
+/// A node must be finalized before its context, since `rcl_node_fini()` accesses the context.
+/// Holding the context here guarantees this ordering, no matter in which order the [`Context`],
+/// the [`Node`] and the entities created from the node are dropped: The node handle is shared by
+/// all of them, and when the last one goes away, the node is finalized before the context handle
+/// is released.
+pub(crate) struct NodeHandle {
+    // Fields are dropped in declaration order, so the node is finalized first.
+    rcl_node_mtx: Mutex<rcl_node_t>,
+    pub(crate) context_handle: Arc<ContextHandle>,
+}
+
+impl NodeHandle {
+    pub(crate) fn lock(&self) -> MutexGuard<rcl_node_t> {
+        self.rcl_node_mtx.lock()
+    }
+}
+
 /// A processing unit that can communicate with other nodes.
 ///
 /// Nodes are a core concept in ROS 2. Refer to the official ["Understanding ROS 2 nodes"][1]
@@ -38,6 +69,8 @@ unsafe impl Send for rcl_node_t {}
 /// Ownership of the node is shared with all [`Publisher`]s and [`Subscription`]s created from it.
 /// That means that even after the node itself is dropped, it will continue to exist and be
 /// displayed by e.g. `ros2 topic` as long as its publishers and subscriptions are not dropped.
+/// In turn, the node keeps the [`Context`] it was created from alive, so the context may be
+/// dropped before its nodes.
 ///
 /// # Naming
 /// A node has a *name* and a *namespace*.
@@ -66,9 +99,14 @@ unsafe impl Send for rcl_node_t {}
 /// [3]: crate::NodeBuilder::new
 /// [4]: crate::NodeBuilder::namespace
 pub struct Node {
-    handle: Arc<Mutex<rcl_node_t>>,
-    pub(crate) context: Arc<Mutex<rcl_context_t>>,
+    pub(crate) handle: Arc<NodeHandle>,
     pub(crate) subscriptions: Vec<Weak<dyn SubscriptionBase>>,
+    pub(crate) guard_conditions: Vec<Weak<GuardCondition>>,
+    pub(crate) timers: Vec<Weak<Timer>>,
+    pub(crate) events: Vec<Weak<dyn EventBase>>,
+    // The group of the subscriptions and timers that are not put into a group explicitly.
+    pub(crate) default_callback_group: Arc<CallbackGroup>,
+    clock: Clock,
 }
 
 impl Eq for Node {}
@@ -165,7 +203,73 @@ impl Node {
         self.get_string(rcl_node_get_fully_qualified_name)
     }
 
-    // Helper for name(), namespace(), fully_qualified_name()
+    /// Returns the name of the logger of the node.
+    ///
+    /// The logger name is derived from the fully qualified name, with the leading slash removed
+    /// and the remaining slashes replaced by dots.
+    ///
+    /// The node can also be passed directly to the logging macros such as [`log_info!`][1],
+    /// which then use this logger.
+    ///
+    /// # Example
+    /// ```
+    /// # use rclrs::{log_info, Context, RclrsError};
+    /// let context = Context::new([])?;
+    /// let node = context.create_node("my_node")?;
+    /// assert_eq!(node.logger_name(), "my_node");
+    /// let namespaced_node = context
+    ///   .create_node_builder("my_node")
+    ///   .namespace("/my/namespace")
+    ///   .build()?;
+    /// assert_eq!(namespaced_node.logger_name(), "my.namespace.my_node");
+    /// log_info!(namespaced_node, "Hello from {}", namespaced_node.name());
+    /// # Ok::<(), RclrsError>(())
+    /// ```
+    ///
+    /// [1]: crate::log_info
+    pub fn logger_name(&self) -> String {
+        self.get_string(rcl_node_get_logger_name)
+    }
+
+    /// Makes the logger of the node the default logger of the logging macros on this thread.
+    ///
+    /// Until the returned guard is dropped, the logging macros log to the logger of the node when
+    /// they are given [`CurrentLogger`][1], e.g. `log_info!(CurrentLogger, "msg")`. See
+    /// [`LoggingScope`] for details.
+    ///
+    /// # Example
+    /// ```
+    /// # use rclrs::{log_info, Context, CurrentLogger, RclrsError};
+    /// let context = Context::new([])?;
+    /// let node = context.create_node("my_node")?;
+    /// let _scope = node.enter_logging_scope();
+    /// log_info!(CurrentLogger, "Logged to the my_node logger");
+    /// # Ok::<(), RclrsError>(())
+    /// ```
+    ///
+    /// [1]: crate::CurrentLogger
+    pub fn enter_logging_scope(&self) -> LoggingScope {
+        LoggingScope::enter(self)
+    }
+
+    /// Returns the current time of the node's ROS clock.
+    ///
+    /// There is no support for simulated time yet, so this is the same as the system time.
+    ///
+    /// # Example
+    /// ```
+    /// # use rclrs::{ClockType, Context, RclrsError};
+    /// let context = Context::new([])?;
+    /// let node = context.create_node("my_node")?;
+    /// let now = node.now();
+    /// assert_eq!(now.clock_type, ClockType::RosTime);
+    /// # Ok::<(), RclrsError>(())
+    /// ```
+    pub fn now(&self) -> Time {
+        self.clock.now()
+    }
+
+    // Helper for name(), namespace(), fully_qualified_name(), logger_name()
     fn get_string(
         &self,
         getter: unsafe extern "C" fn(*const rcl_node_t) -> *const c_char,
@@ -199,6 +303,22 @@ impl Node {
         Publisher::<T>::new(self, topic, qos)
     }
 
+    /// Creates a [`Publisher`][1] with the given [`PublisherOptions`][2].
+    ///
+    /// [1]: crate::Publisher
+    /// [2]: crate::PublisherOptions
+    pub fn create_publisher_with_options<T>(
+        &self,
+        topic: &str,
+        qos: QoSProfile,
+        options: PublisherOptions,
+    ) -> Result<Publisher<T>, RclrsError>
+    where
+        T: Message,
+    {
+        Publisher::<T>::new_with_options(self, topic, qos, options)
+    }
+
     /// Creates a [`Subscription`][1].
     ///
     /// [1]: crate::Subscription
@@ -213,12 +333,243 @@ impl Node {
         T: Message,
         F: FnMut(T) + 'static + Send,
     {
-        let subscription = Arc::new(Subscription::<T>::new(self, topic, qos, callback)?);
+        self.create_subscription_with_options(topic, qos, SubscriptionOptions::default(), callback)
+    }
+
+    /// Creates a [`Subscription`][1] with the given [`SubscriptionOptions`][2].
+    ///
+    /// [1]: crate::Subscription
+    /// [2]: crate::SubscriptionOptions
+    pub fn create_subscription_with_options<T, F>(
+        &mut self,
+        topic: &str,
+        qos: QoSProfile,
+        options: SubscriptionOptions,
+        callback: F,
+    ) -> Result<Arc<Subscription<T>>, RclrsError>
+    where
+        T: Message,
+        F: FnMut(T) + 'static + Send,
+    {
+        let warn_on_drop = options.warn_on_drop;
+        let mut subscription =
+            Subscription::<T>::new_with_options(self, topic, qos, options, callback)?;
+        if warn_on_drop {
+            subscription.drop_warning = self.create_drop_warning(&subscription)?;
+        }
+        let subscription = Arc::new(subscription);
         self.subscriptions
             .push(Arc::downgrade(&subscription) as Weak<dyn SubscriptionBase>);
         Ok(subscription)
     }
 
+    // Creates the event that logs lost messages of a subscription, see
+    // SubscriptionOptions::warn_on_drop.
+    fn create_drop_warning<T: Message>(
+        &mut self,
+        subscription: &Subscription<T>,
+    ) -> Result<Option<Arc<dyn EventBase>>, RclrsError> {
+        let logger_name = self.logger_name();
+        #[cfg(not(ros_distro = "foxy"))]
+        {
+            let callback = drop_warning_callback(logger_name.clone(), subscription.topic_name());
+            match self.create_subscription_event(subscription, callback) {
+                Ok(event) => return Ok(Some(event)),
+                // Not every RMW implementation supports this event
+                Err(RclrsError {
+                    code: crate::RclReturnCode::Unsupported,
+                    ..
+                }) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        crate::log_warn!(
+            logger_name,
+            "Lost messages on topic '{}' cannot be detected",
+            subscription.topic_name()
+        );
+        Ok(None)
+    }
+
+    /// Creates a [`GuardCondition`][1] that is waited on by [`spin_once`][2] for this node.
+    ///
+    /// See the [`GuardCondition`][1] docs for an example.
+    ///
+    /// [1]: crate::GuardCondition
+    /// [2]: crate::spin_once
+    pub fn create_guard_condition(&mut self) -> Result<Arc<GuardCondition>, RclrsError> {
+        let context = Context {
+            handle: self.handle.context_handle.clone(),
+        };
+        let guard_condition = Arc::new(GuardCondition::new(&context)?);
+        self.guard_conditions.push(Arc::downgrade(&guard_condition));
+        Ok(guard_condition)
+    }
+
+    /// Creates a [`GuardCondition`][1] with a callback that is run by [`spin_once`][2] when the
+    /// guard condition was triggered.
+    ///
+    /// [1]: crate::GuardCondition
+    /// [2]: crate::spin_once
+    pub fn create_guard_condition_with_callback<F>(
+        &mut self,
+        callback: F,
+    ) -> Result<Arc<GuardCondition>, RclrsError>
+    where
+        F: FnMut() + 'static + Send,
+    {
+        let context = Context {
+            handle: self.handle.context_handle.clone(),
+        };
+        let guard_condition = Arc::new(GuardCondition::new_with_callback(&context, callback)?);
+        self.guard_conditions.push(Arc::downgrade(&guard_condition));
+        Ok(guard_condition)
+    }
+
+    /// Creates a [`Timer`][1] that runs the callback every `period`, measured with the node's
+    /// ROS clock.
+    ///
+    /// The callback is run by [`spin_once`][2]. See the [`Timer`][1] docs for an example.
+    ///
+    /// [1]: crate::Timer
+    /// [2]: crate::spin_once
+    pub fn create_timer<F>(
+        &mut self,
+        period: Duration,
+        callback: F,
+    ) -> Result<Arc<Timer>, RclrsError>
+    where
+        F: FnMut() + 'static + Send,
+    {
+        let callback_group = self.default_callback_group.clone();
+        self.create_timer_in_group(period, &callback_group, callback)
+    }
+
+    /// Creates a [`Timer`][1] like [`create_timer()`][2], whose callback belongs to the given
+    /// [`CallbackGroup`][3].
+    ///
+    /// See the [`CallbackGroup`][3] docs for an example.
+    ///
+    /// [1]: crate::Timer
+    /// [2]: Node::create_timer
+    /// [3]: crate::CallbackGroup
+    pub fn create_timer_in_group<F>(
+        &mut self,
+        period: Duration,
+        callback_group: &Arc<CallbackGroup>,
+        callback: F,
+    ) -> Result<Arc<Timer>, RclrsError>
+    where
+        F: FnMut() + 'static + Send,
+    {
+        let context = Context {
+            handle: self.handle.context_handle.clone(),
+        };
+        let timer = Timer::new(&context, &self.clock, period, callback)?;
+        Ok(self.add_timer(timer, callback_group))
+    }
+
+    /// Creates a [`Timer`][1] that runs the callback once after `delay`, measured with the
+    /// node's ROS clock.
+    ///
+    /// The callback is run by [`spin_once`][2]. After that, the timer is canceled, and can be
+    /// started again with [`Timer::reset()`][3].
+    ///
+    /// [1]: crate::Timer
+    /// [2]: crate::spin_once
+    /// [3]: crate::Timer::reset
+    pub fn create_one_shot_timer<F>(
+        &mut self,
+        delay: Duration,
+        callback: F,
+    ) -> Result<Arc<Timer>, RclrsError>
+    where
+        F: FnMut() + 'static + Send,
+    {
+        let context = Context {
+            handle: self.handle.context_handle.clone(),
+        };
+        let timer = Timer::new_one_shot(&context, &self.clock, delay, callback)?;
+        let callback_group = self.default_callback_group.clone();
+        Ok(self.add_timer(timer, &callback_group))
+    }
+
+    // Registers a timer, so that it is waited on by spin_once().
+    fn add_timer(&mut self, mut timer: Timer, callback_group: &Arc<CallbackGroup>) -> Arc<Timer> {
+        timer.callback_group = Some(callback_group.clone());
+        let timer = Arc::new(timer);
+        self.timers.push(Arc::downgrade(&timer));
+        timer
+    }
+
+    /// Creates a [`CallbackGroup`][1] with the given concurrency rule.
+    ///
+    /// Subscriptions are put into the group through [`SubscriptionOptions::callback_group`][2],
+    /// and timers with [`create_timer_in_group()`][3].
+    ///
+    /// [1]: crate::CallbackGroup
+    /// [2]: crate::SubscriptionOptions::callback_group
+    /// [3]: Node::create_timer_in_group
+    pub fn create_callback_group(&self, group_type: CallbackGroupType) -> Arc<CallbackGroup> {
+        Arc::new(CallbackGroup::new(group_type))
+    }
+
+    /// Creates an [`Event`][1] that reports status changes of a subscription.
+    ///
+    /// The type of event is selected by the status type, e.g.
+    /// [`RequestedDeadlineMissedStatus`][2]. The callback is run by [`spin_once`][3] when the
+    /// status has changed.
+    ///
+    /// [1]: crate::Event
+    /// [2]: crate::RequestedDeadlineMissedStatus
+    /// [3]: crate::spin_once
+    pub fn create_subscription_event<T, S, F>(
+        &mut self,
+        subscription: &Subscription<T>,
+        callback: F,
+    ) -> Result<Arc<Event<S>>, RclrsError>
+    where
+        T: Message,
+        S: SubscriptionEventStatus,
+        F: FnMut(S) + 'static + Send,
+    {
+        let event = Arc::new(Event::new_for_subscription(
+            subscription.handle.clone(),
+            callback,
+        )?);
+        self.events
+            .push(Arc::downgrade(&event) as Weak<dyn EventBase>);
+        Ok(event)
+    }
+
+    /// Creates an [`Event`][1] that reports status changes of a publisher.
+    ///
+    /// The type of event is selected by the status type, e.g.
+    /// [`OfferedDeadlineMissedStatus`][2]. The callback is run by [`spin_once`][3] when the status
+    /// has changed.
+    ///
+    /// [1]: crate::Event
+    /// [2]: crate::OfferedDeadlineMissedStatus
+    /// [3]: crate::spin_once
+    pub fn create_publisher_event<T, S, F>(
+        &mut self,
+        publisher: &Publisher<T>,
+        callback: F,
+    ) -> Result<Arc<Event<S>>, RclrsError>
+    where
+        T: Message,
+        S: PublisherEventStatus,
+        F: FnMut(S) + 'static + Send,
+    {
+        let event = Arc::new(Event::new_for_publisher(
+            publisher.handle.clone(),
+            callback,
+        )?);
+        self.events
+            .push(Arc::downgrade(&event) as Weak<dyn EventBase>);
+        Ok(event)
+    }
+
     /// Returns the subscriptions that have not been dropped yet.
     pub(crate) fn live_subscriptions(&self) -> Vec<Arc<dyn SubscriptionBase>> {
         self.subscriptions
@@ -227,6 +578,24 @@ impl Node {
             .collect()
     }
 
+    /// Returns the guard conditions that have not been dropped yet.
+    pub(crate) fn live_guard_conditions(&self) -> Vec<Arc<GuardCondition>> {
+        self.guard_conditions
+            .iter()
+            .filter_map(Weak::upgrade)
+            .collect()
+    }
+
+    /// Returns the timers that have not been dropped yet.
+    pub(crate) fn live_timers(&self) -> Vec<Arc<Timer>> {
+        self.timers.iter().filter_map(Weak::upgrade).collect()
+    }
+
+    /// Returns the events that have not been dropped yet.
+    pub(crate) fn live_events(&self) -> Vec<Arc<dyn EventBase>> {
+        self.events.iter().filter_map(Weak::upgrade).collect()
+    }
+
     /// Returns the ROS domain ID that the node is using.
     ///    
     /// The domain ID controls which nodes can send messages to each other, see the [ROS 2 concept article][1].
@@ -279,3 +648,41 @@ impl Node {
         NodeBuilder::new(context, node_name)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn now_is_close_to_system_time() -> Result<(), RclrsError> {
+        let context = Context::new([])?;
+        let node = context.create_node("node_now_test")?;
+        let system_nsec = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos() as i64;
+        let node_nsec = node.now().nsec;
+        assert!((node_nsec - system_nsec).abs() < Duration::from_secs(1).as_nanos() as i64);
+        Ok(())
+    }
+
+    #[test]
+    fn node_outlives_dropped_context() -> Result<(), RclrsError> {
+        let context = Context::new([])?;
+        let node = context.create_node("node_outlives_context")?;
+        let publisher = node.create_publisher::<test_msgs::msg::Empty>(
+            "node_outlives_context_topic",
+            crate::QOS_PROFILE_DEFAULT,
+        )?;
+        // The node and publisher keep the context alive
+        drop(context);
+        assert_eq!(node.name(), "node_outlives_context");
+        // The publisher keeps the node, and therefore the context, alive
+        drop(node);
+        publisher.publish(test_msgs::msg::Empty::default())?;
+        // Finalizes the publisher, then the node, then the context
+        drop(publisher);
+        Ok(())
+    }
+}
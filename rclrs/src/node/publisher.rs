@@ -1,12 +1,14 @@
+use crate::allocator::node_allocator;
 use crate::error::{RclrsError, ToResult};
 use crate::qos::QoSProfile;
 use crate::rcl_bindings::*;
-use crate::Node;
+use crate::{message_type_name, validate_topic_name, Gid, Node, NodeHandle};
 
 use std::borrow::Cow;
-use std::ffi::CString;
+use std::ffi::{CStr, CString};
 use std::marker::PhantomData;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use parking_lot::{Mutex, MutexGuard};
 
@@ -18,17 +20,37 @@ unsafe impl Send for rcl_publisher_t {}
 
 pub(crate) struct PublisherHandle {
     handle: Mutex<rcl_publisher_t>,
-    node_handle: Arc<Mutex<rcl_node_t>>,
+    node_handle: Arc<NodeHandle>,
 }
 
 impl PublisherHandle {
-    fn lock(&self) -> MutexGuard<rcl_publisher_t> {
+    pub(crate) fn lock(&self) -> MutexGuard<rcl_publisher_t> {
         self.handle.lock()
     }
+
+    fn gid(&self) -> Result<Gid, RclrsError> {
+        let handle = &*self.lock();
+        // SAFETY: Zero-initializing a plain C struct is safe, it is only used as an output argument.
+        let mut gid: rmw_gid_t = unsafe { std::mem::zeroed() };
+        unsafe {
+            // SAFETY: The publisher handle is valid, so it has a valid rmw handle.
+            let rmw_handle = rcl_publisher_get_rmw_handle(handle);
+            // SAFETY: The rmw handle is valid, and the GID is a valid output argument.
+            rmw_get_gid_for_publisher(rmw_handle, &mut gid).ok()?;
+        }
+        Ok(Gid::from(&gid))
+    }
 }
 
 impl Drop for PublisherHandle {
     fn drop(&mut self) {
+        if let Ok(gid) = self.gid() {
+            let context_handle = &self.node_handle.context_handle;
+            context_handle
+                .local_publishers
+                .lock()
+                .retain(|&local| local != gid);
+        }
         let handle = self.handle.get_mut();
         let node_handle = &mut *self.node_handle.lock();
         // SAFETY: No preconditions for this function (besides the arguments being valid).
@@ -38,6 +60,34 @@ impl Drop for PublisherHandle {
     }
 }
 
+/// Options for creating a [`Publisher`].
+///
+/// # Example
+/// ```
+/// # use rclrs::{Context, PublisherOptions, RclrsError, QOS_PROFILE_DEFAULT};
+/// let context = Context::new([])?;
+/// let node = context.create_node("my_node")?;
+/// let options = PublisherOptions {
+///     use_intra_process: true,
+/// };
+/// let publisher = node.create_publisher_with_options::<test_msgs::msg::Empty>(
+///     "topic",
+///     QOS_PROFILE_DEFAULT,
+///     options,
+/// )?;
+/// assert!(publisher.is_intra_process_capable());
+/// # Ok::<(), RclrsError>(())
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PublisherOptions {
+    /// Whether to opt in to intra-process communication with subscriptions in the same process.
+    ///
+    /// See [`SubscriptionOptions::use_intra_process`][1]. It is disabled by default.
+    ///
+    /// [1]: crate::SubscriptionOptions::use_intra_process
+    pub use_intra_process: bool,
+}
+
 /// Struct for sending messages of type `T`.
 ///
 /// Multiple publishers can be created for the same topic, in different nodes or the same node.
@@ -47,12 +97,18 @@ impl Drop for PublisherHandle {
 ///
 /// Sending messages does not require calling [`spin`][1] on the publisher's node.
 ///
+/// Publishers are `Send` and `Sync`, so a publisher can be shared between threads, e.g. in an
+/// `Arc`. Since `rcl` publishers are not thread-safe, the underlying handle is protected by a
+/// mutex, which means that concurrent calls to [`Publisher::publish()`] are serialized. When
+/// many threads publish at a high rate, it can be better to create one publisher per thread.
+///
 /// [1]: crate::spin
 pub struct Publisher<T>
 where
     T: Message,
 {
     pub(crate) handle: Arc<PublisherHandle>,
+    intra_process_capable: bool,
     message: PhantomData<T>,
 }
 
@@ -63,10 +119,20 @@ where
     /// Creates a new `Publisher`.
     ///
     /// Node and namespace changes are always applied _before_ topic remapping.
-    ///
-    /// # Panics
-    /// When the topic contains interior null bytes.
     pub fn new(node: &Node, topic: &str, qos: QoSProfile) -> Result<Self, RclrsError>
+    where
+        T: Message,
+    {
+        Self::new_with_options(node, topic, qos, PublisherOptions::default())
+    }
+
+    /// Creates a new `Publisher` with the given options.
+    pub fn new_with_options(
+        node: &Node,
+        topic: &str,
+        qos: QoSProfile,
+        options: PublisherOptions,
+    ) -> Result<Self, RclrsError>
     where
         T: Message,
     {
@@ -74,12 +140,15 @@ where
         let mut publisher_handle = unsafe { rcl_get_zero_initialized_publisher() };
         let type_support =
             <T as Message>::RmwMsg::get_type_support() as *const rosidl_message_type_support_t;
+        validate_topic_name(topic)?;
+        // Can't fail, since a valid topic name has no null bytes
         let topic_c_string = CString::new(topic).unwrap();
         let node_handle = &mut *node.handle.lock();
 
         // SAFETY: No preconditions for this function.
         let mut publisher_options = unsafe { rcl_publisher_get_default_options() };
         publisher_options.qos = qos.into();
+        publisher_options.allocator = node_allocator(node_handle);
         unsafe {
             // SAFETY: The publisher handle is zero-initialized as expected by this function.
             // The node handle is kept alive because it is co-owned by the subscription.
@@ -93,20 +162,34 @@ where
                 topic_c_string.as_ptr(),
                 &publisher_options,
             )
-            .ok()?;
+            .ok_with_context("rcl_publisher_init")?;
         }
 
         let handle = Arc::new(PublisherHandle {
             handle: Mutex::new(publisher_handle),
             node_handle: node.handle.clone(),
         });
+        // Subscriptions that ignore local publications filter by this GID when the RMW
+        // implementation doesn't support the option.
+        let gid = handle.gid()?;
+        let context_handle = &node.handle.context_handle;
+        context_handle.local_publishers.lock().push(gid);
 
         Ok(Self {
             handle,
+            intra_process_capable: options.use_intra_process && qos.is_intra_process_compatible(),
             message: PhantomData,
         })
     }
 
+    /// Returns `true` if this publisher can send messages through intra-process communication.
+    ///
+    /// This requires [`PublisherOptions::use_intra_process`] to be set, and a QoS profile with a
+    /// volatile durability and a `KeepLast` history with a non-zero depth.
+    pub fn is_intra_process_capable(&self) -> bool {
+        self.intra_process_capable
+    }
+
     /// Publishes a message.
     ///
     /// The [`MessageCow`] trait is implemented by any
@@ -120,11 +203,28 @@ where
     /// Hence, when a message will not be needed anymore after publishing, pass it by value.
     /// When a message will be needed again after publishing, pass it by reference, instead of cloning and passing by value.
     ///
+    /// Idiomatic messages are converted into their RMW-native counterpart before publishing, which
+    /// copies all strings and sequences in the message. RMW-native messages are published without
+    /// any conversion. To avoid the copy for a publisher of an idiomatic message type, build the
+    /// RMW-native message directly and use [`Publisher::publish_rmw()`].
+    ///
     /// Calling `publish()` is a potentially blocking call, see [this issue][1] for details.
     ///
     /// [1]: https://github.com/ros2/ros2/issues/255
     pub fn publish<'a, M: MessageCow<'a, T>>(&self, message: M) -> Result<(), RclrsError> {
         let rmw_message = T::into_rmw_message(message.into_cow());
+        self.publish_rmw(rmw_message.as_ref())
+    }
+
+    /// Publishes a message in its RMW-native representation.
+    ///
+    /// Unlike [`Publisher::publish()`], this never converts the message, which makes it the most
+    /// efficient way to publish with a publisher of an idiomatic message type.
+    ///
+    /// Calling `publish_rmw()` is a potentially blocking call, see [this issue][1] for details.
+    ///
+    /// [1]: https://github.com/ros2/ros2/issues/255
+    pub fn publish_rmw(&self, message: &<T as Message>::RmwMsg) -> Result<(), RclrsError> {
         let handle = &mut *self.handle.lock();
         let ret = unsafe {
             // SAFETY: The message type is guaranteed to match the publisher type by the type system.
@@ -132,12 +232,109 @@ where
             // The third argument is explictly allowed to be NULL.
             rcl_publish(
                 handle,
-                rmw_message.as_ref() as *const <T as Message>::RmwMsg as *mut _,
+                message as *const <T as Message>::RmwMsg as *mut _,
                 std::ptr::null_mut(),
             )
         };
         ret.ok()
     }
+
+    /// Manually asserts that this publisher is alive, without publishing a message.
+    ///
+    /// With the [`ManualByTopic`][1] liveliness policy, a publisher is only considered alive if it
+    /// publishes a message or asserts its liveliness at least once per
+    /// [`liveliness_lease_duration`][2]. This makes it possible to e.g. send heartbeats while there
+    /// is no data to publish. Subscriptions can observe the liveliness of their publishers with
+    /// the [`LivelinessChangedStatus`][3] event.
+    ///
+    /// [1]: crate::QoSLivelinessPolicy::ManualByTopic
+    /// [2]: crate::QoSProfile::liveliness_lease_duration
+    /// [3]: crate::LivelinessChangedStatus
+    pub fn assert_liveliness(&self) -> Result<(), RclrsError> {
+        // SAFETY: The publisher handle is valid. There are no other preconditions.
+        unsafe { rcl_publisher_assert_liveliness(&*self.handle.lock()).ok() }
+    }
+
+    /// Returns the number of subscriptions that are matched with this publisher.
+    ///
+    /// This can be used to skip the work of creating and publishing a message when nobody is
+    /// listening. Note that subscriptions are only matched after they have been discovered, so
+    /// the count may lag behind.
+    pub fn get_subscription_count(&self) -> Result<usize, RclrsError> {
+        let mut subscription_count = 0;
+        // SAFETY: The publisher handle is valid, and the count is a valid output argument.
+        unsafe {
+            rcl_publisher_get_subscription_count(&*self.handle.lock(), &mut subscription_count)
+                .ok()?
+        };
+        Ok(subscription_count)
+    }
+
+    /// Waits until at least `count` subscriptions are matched with this publisher.
+    ///
+    /// Returns `false` if that did not happen within the timeout. This is useful before
+    /// publishing a message that must not be missed, e.g. with volatile durability, where only
+    /// subscriptions matched at the time of publishing receive the message.
+    ///
+    /// The subscription count is polled, since there are no events for matched subscriptions in
+    /// all supported ROS distributions.
+    ///
+    /// # Example
+    /// ```
+    /// # use rclrs::{Context, RclrsError, QOS_PROFILE_DEFAULT};
+    /// # use std::time::Duration;
+    /// let context = Context::new([])?;
+    /// let node = context.create_node("my_node")?;
+    /// let publisher =
+    ///     node.create_publisher::<test_msgs::msg::Empty>("my_topic", QOS_PROFILE_DEFAULT)?;
+    /// // Nobody is listening
+    /// assert!(!publisher.wait_for_matched(1, Duration::from_millis(10))?);
+    /// # Ok::<(), RclrsError>(())
+    /// ```
+    pub fn wait_for_matched(&self, count: usize, timeout: Duration) -> Result<bool, RclrsError> {
+        let start = Instant::now();
+        loop {
+            if self.get_subscription_count()? >= count {
+                return Ok(true);
+            }
+            let remaining = match timeout.checked_sub(start.elapsed()) {
+                Some(remaining) if !remaining.is_zero() => remaining,
+                _ => return Ok(false),
+            };
+            std::thread::sleep(remaining.min(Duration::from_millis(10)));
+        }
+    }
+
+    /// Returns the globally unique identifier of this publisher.
+    ///
+    /// Messages received from this publisher report the same GID in their [`MessageInfo`][1].
+    ///
+    /// [1]: crate::MessageInfo
+    pub fn gid(&self) -> Result<Gid, RclrsError> {
+        self.handle.gid()
+    }
+
+    /// Returns the fully qualified topic name of the publisher.
+    ///
+    /// This is the name that the publisher is actually bound to, i.e. after the namespace of the
+    /// node has been applied, and the name has been remapped.
+    pub fn topic_name(&self) -> String {
+        let handle = &*self.handle.lock();
+        // SAFETY: The publisher handle is valid. The returned string is owned by the publisher,
+        // and is immediately converted to an owned string.
+        unsafe {
+            let topic_name = rcl_publisher_get_topic_name(handle);
+            if topic_name.is_null() {
+                return String::new();
+            }
+            CStr::from_ptr(topic_name).to_string_lossy().into_owned()
+        }
+    }
+
+    /// Returns the full name of the message type, e.g. `"std_msgs/msg/String"`.
+    pub fn type_name(&self) -> String {
+        message_type_name::<T>()
+    }
 }
 
 /// Convenience trait for [`Publisher::publish`].
@@ -157,3 +354,315 @@ impl<'a, T: Message> MessageCow<'a, T> for &'a T {
         Cow::Borrowed(self)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Context, LivelinessChangedStatus, QoSDurabilityPolicy, QoSDuration, QoSHistoryPolicy,
+        QoSLivelinessPolicy, RclReturnCode, RclrsErrorFilter, QOS_PROFILE_DEFAULT,
+    };
+
+    #[test]
+    fn subscription_count_increases_after_discovery() -> Result<(), RclrsError> {
+        let context = Context::new([])?;
+        let mut node = context.create_node("publisher_test_node")?;
+        let publisher = node.create_publisher::<test_msgs::msg::Empty>(
+            "publisher_subscription_count_test",
+            QOS_PROFILE_DEFAULT,
+        )?;
+        assert_eq!(publisher.get_subscription_count()?, 0);
+
+        let _subscription = node.create_subscription::<test_msgs::msg::Empty, _>(
+            "publisher_subscription_count_test",
+            QOS_PROFILE_DEFAULT,
+            |_| {},
+        )?;
+        let start = Instant::now();
+        while publisher.get_subscription_count()? == 0 && start.elapsed() < Duration::from_secs(5) {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(publisher.get_subscription_count()?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn publishes_idiomatic_and_rmw_messages() -> Result<(), RclrsError> {
+        let context = Context::new([])?;
+        let mut node = context.create_node("publisher_idiomatic_test_node")?;
+        // Transient local durability makes sure the messages are delivered even if the
+        // subscription is matched only after publishing.
+        let qos = QoSProfile {
+            durability: QoSDurabilityPolicy::TransientLocal,
+            history: QoSHistoryPolicy::KeepLast { depth: 2 },
+            ..QOS_PROFILE_DEFAULT
+        };
+        let publisher =
+            node.create_publisher::<test_msgs::msg::Strings>("publisher_idiomatic_test", qos)?;
+        let subscription = node.create_subscription::<test_msgs::msg::Strings, _>(
+            "publisher_idiomatic_test",
+            qos,
+            |_| {},
+        )?;
+
+        let message = test_msgs::msg::Strings {
+            string_value: String::from("idiomatic"),
+            ..Default::default()
+        };
+        publisher.publish(&message)?;
+        let rmw_message = test_msgs::msg::rmw::Strings {
+            string_value: "rmw".into(),
+            ..Default::default()
+        };
+        publisher.publish_rmw(&rmw_message)?;
+
+        let mut received = Vec::new();
+        let start = Instant::now();
+        while received.len() < 2 && start.elapsed() < Duration::from_secs(5) {
+            match subscription.take() {
+                Ok(message) => received.push(message.string_value),
+                Err(_) => std::thread::sleep(Duration::from_millis(10)),
+            }
+        }
+        assert_eq!(received, ["idiomatic", "rmw"]);
+        Ok(())
+    }
+
+    #[test]
+    fn waits_for_matched_subscription() -> Result<(), RclrsError> {
+        let context = Context::new([])?;
+        let mut node = context.create_node("publisher_matched_test_node")?;
+        let qos = QoSProfile {
+            durability: QoSDurabilityPolicy::TransientLocal,
+            ..QOS_PROFILE_DEFAULT
+        };
+        let publisher =
+            node.create_publisher::<test_msgs::msg::Empty>("publisher_matched_test", qos)?;
+        let start = Instant::now();
+        assert!(!publisher.wait_for_matched(1, Duration::from_millis(50))?);
+        assert!(start.elapsed() >= Duration::from_millis(50));
+
+        let subscription = node.create_subscription::<test_msgs::msg::Empty, _>(
+            "publisher_matched_test",
+            qos,
+            |_| {},
+        )?;
+        assert!(publisher.wait_for_matched(1, Duration::from_secs(5))?);
+        assert!(publisher.get_subscription_count()? >= 1);
+        publisher.publish(test_msgs::msg::Empty::default())?;
+        let start = Instant::now();
+        while subscription.take().is_err() {
+            assert!(start.elapsed() < Duration::from_secs(5));
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn intra_process_flag_is_stored() -> Result<(), RclrsError> {
+        let context = Context::new([])?;
+        let mut node = context.create_node("publisher_intra_process_test_node")?;
+        let publisher_options = PublisherOptions {
+            use_intra_process: true,
+        };
+        let subscription_options = crate::SubscriptionOptions {
+            use_intra_process: true,
+            ..Default::default()
+        };
+        let publisher = node.create_publisher_with_options::<test_msgs::msg::Empty>(
+            "publisher_intra_process_test",
+            QOS_PROFILE_DEFAULT,
+            publisher_options.clone(),
+        )?;
+        assert!(publisher.is_intra_process_capable());
+        let subscription = node.create_subscription_with_options::<test_msgs::msg::Empty, _>(
+            "publisher_intra_process_test",
+            QOS_PROFILE_DEFAULT,
+            subscription_options.clone(),
+            |_| {},
+        )?;
+        assert!(subscription.is_intra_process_capable());
+
+        // Not capable without opting in
+        let publisher = node.create_publisher::<test_msgs::msg::Empty>(
+            "publisher_intra_process_test",
+            QOS_PROFILE_DEFAULT,
+        )?;
+        assert!(!publisher.is_intra_process_capable());
+
+        // Not capable with a QoS profile that intra-process communication doesn't support
+        let qos = QoSProfile {
+            durability: QoSDurabilityPolicy::TransientLocal,
+            ..QOS_PROFILE_DEFAULT
+        };
+        let publisher = node.create_publisher_with_options::<test_msgs::msg::Empty>(
+            "publisher_intra_process_test",
+            qos,
+            publisher_options,
+        )?;
+        assert!(!publisher.is_intra_process_capable());
+        let qos = QoSProfile {
+            history: QoSHistoryPolicy::KeepAll,
+            ..QOS_PROFILE_DEFAULT
+        };
+        let subscription = node.create_subscription_with_options::<test_msgs::msg::Empty, _>(
+            "publisher_intra_process_test",
+            qos,
+            subscription_options,
+            |_| {},
+        )?;
+        assert!(!subscription.is_intra_process_capable());
+        Ok(())
+    }
+
+    #[test]
+    fn topic_and_type_names_are_reported() -> Result<(), RclrsError> {
+        let args = ["--ros-args", "-r", "publisher_remap_test:=remapped_topic"].map(String::from);
+        let context = Context::new(args)?;
+        let mut node = crate::Node::builder(&context, "publisher_names_test_node")
+            .namespace("/ns")
+            .build()?;
+        let publisher = node.create_publisher::<test_msgs::msg::Strings>(
+            "publisher_remap_test",
+            QOS_PROFILE_DEFAULT,
+        )?;
+        assert_eq!(publisher.topic_name(), "/ns/remapped_topic");
+        assert_eq!(publisher.type_name(), "test_msgs/msg/Strings");
+
+        let subscription = node.create_subscription::<test_msgs::msg::rmw::Empty, _>(
+            "/absolute_topic",
+            QOS_PROFILE_DEFAULT,
+            |_| {},
+        )?;
+        assert_eq!(subscription.topic_name(), "/absolute_topic");
+        assert_eq!(subscription.type_name(), "test_msgs/msg/Empty");
+        Ok(())
+    }
+
+    #[test]
+    fn publisher_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Publisher<test_msgs::msg::Empty>>();
+        assert_send_sync::<crate::Subscription<test_msgs::msg::Empty>>();
+    }
+
+    #[test]
+    fn publishes_from_multiple_threads() -> Result<(), RclrsError> {
+        let context = Context::new([])?;
+        let node = context.create_node("publisher_threads_test_node")?;
+        let publisher = node.create_publisher::<test_msgs::msg::Empty>(
+            "publisher_threads_test",
+            QOS_PROFILE_DEFAULT,
+        )?;
+        let publisher = std::sync::Arc::new(publisher);
+        let workers: Vec<_> = (0..4)
+            .map(|_| {
+                let publisher = publisher.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..100 {
+                        publisher.publish(test_msgs::msg::Empty::default())?;
+                    }
+                    Ok::<(), RclrsError>(())
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap()?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn gid_matches_message_info() -> Result<(), RclrsError> {
+        let context = Context::new([])?;
+        let mut node = context.create_node("publisher_gid_test_node")?;
+        let publisher_1 = node
+            .create_publisher::<test_msgs::msg::Empty>("publisher_gid_test", QOS_PROFILE_DEFAULT)?;
+        let publisher_2 = node
+            .create_publisher::<test_msgs::msg::Empty>("publisher_gid_test", QOS_PROFILE_DEFAULT)?;
+        assert_ne!(publisher_1.gid()?, publisher_2.gid()?);
+
+        let subscription = node.create_subscription::<test_msgs::msg::Empty, _>(
+            "publisher_gid_test",
+            QOS_PROFILE_DEFAULT,
+            |_| {},
+        )?;
+        assert!(publisher_1.wait_for_matched(1, Duration::from_secs(5))?);
+        publisher_1.publish(test_msgs::msg::Empty::default())?;
+        let start = Instant::now();
+        let (_, message_info) = loop {
+            match subscription.take_with_info() {
+                Ok(result) => break result,
+                Err(_) if start.elapsed() < Duration::from_secs(5) => {
+                    std::thread::sleep(Duration::from_millis(10))
+                }
+                Err(e) => return Err(e),
+            }
+        };
+        // Not every RMW implementation reports the publisher GID.
+        if message_info
+            .publisher_gid
+            .as_bytes()
+            .iter()
+            .any(|&byte| byte != 0)
+        {
+            assert_eq!(message_info.publisher_gid, publisher_1.gid()?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn asserting_liveliness_keeps_publisher_alive() -> Result<(), RclrsError> {
+        let context = Context::new([])?;
+        let mut node = context.create_node("publisher_liveliness_test_node")?;
+        let qos = QoSProfile {
+            liveliness: QoSLivelinessPolicy::ManualByTopic,
+            liveliness_lease_duration: QoSDuration::Custom(Duration::from_millis(200)),
+            ..QOS_PROFILE_DEFAULT
+        };
+        let publisher =
+            node.create_publisher::<test_msgs::msg::Empty>("publisher_liveliness_test", qos)?;
+        let subscription = node.create_subscription::<test_msgs::msg::Empty, _>(
+            "publisher_liveliness_test",
+            qos,
+            |_| {},
+        )?;
+        let latest_status = Arc::new(Mutex::new(None));
+        let event = node.create_subscription_event(&subscription, {
+            let latest_status = latest_status.clone();
+            move |status: LivelinessChangedStatus| *latest_status.lock() = Some(status)
+        });
+        let _event = match event {
+            Ok(event) => event,
+            // Not every RMW implementation supports this event
+            Err(RclrsError {
+                code: RclReturnCode::Unsupported,
+                ..
+            }) => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        let spin_for = |duration: Duration, assert_liveliness: bool| {
+            let start = Instant::now();
+            while start.elapsed() < duration {
+                if assert_liveliness {
+                    publisher.assert_liveliness()?;
+                }
+                crate::spin_once(&node, Some(Duration::from_millis(50))).timeout_ok()?;
+            }
+            Ok::<(), RclrsError>(())
+        };
+
+        // Asserting well within the lease duration keeps the publisher alive
+        spin_for(Duration::from_secs(1), true)?;
+        let status = latest_status
+            .lock()
+            .expect("The liveliness changed event was not reported");
+        assert_eq!((status.alive_count, status.not_alive_count), (1, 0));
+
+        // Without asserting, the lease runs out
+        spin_for(Duration::from_secs(1), false)?;
+        let status = latest_status.lock().unwrap();
+        assert_eq!((status.alive_count, status.not_alive_count), (0, 1));
+        Ok(())
+    }
+}
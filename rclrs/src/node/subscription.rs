@@ -1,18 +1,29 @@
+use crate::allocator::node_allocator;
 use crate::error::{RclReturnCode, SubscriberErrorCode, ToResult};
 use crate::qos::QoSProfile;
-use crate::Node;
+use crate::serialization::serialize_rmw_message;
+use crate::{
+    message_type_name, validate_topic_name, CallbackGroup, EventBase, Gid, MessageInfo, Node,
+    NodeHandle,
+};
 use crate::{rcl_bindings::*, RclrsError};
 
 use std::borrow::Borrow;
 use std::boxed::Box;
-use std::ffi::CString;
+use std::ffi::{CStr, CString};
 use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 use rosidl_runtime_rs::{Message, RmwMessage};
 
 use parking_lot::{Mutex, MutexGuard};
 
+#[cfg(feature = "async")]
+mod stream;
+#[cfg(feature = "async")]
+pub use stream::*;
+
 // SAFETY: The functions accessing this type, including drop(), shouldn't care about the thread
 // they are running in. Therefore, this type can be safely sent to another thread.
 unsafe impl Send for rcl_subscription_t {}
@@ -20,7 +31,7 @@ unsafe impl Send for rcl_subscription_t {}
 /// Internal struct used by subscriptions.
 pub struct SubscriptionHandle {
     handle: Mutex<rcl_subscription_t>,
-    node_handle: Arc<Mutex<rcl_node_t>>,
+    node_handle: Arc<NodeHandle>,
 }
 
 impl SubscriptionHandle {
@@ -46,6 +57,66 @@ pub trait SubscriptionBase: Send + Sync {
     fn handle(&self) -> &SubscriptionHandle;
     /// Tries to take a new message and run the callback with it.
     fn execute(&self) -> Result<(), RclrsError>;
+    /// Internal function to get the group that the callback belongs to.
+    fn callback_group(&self) -> &Arc<CallbackGroup>;
+}
+
+/// Options for creating a [`Subscription`].
+///
+/// More options may be added in the future, so this struct can not be constructed with a struct
+/// expression outside of rclrs. Instead, start from the default options and change the fields.
+///
+/// # Example
+/// ```
+/// # use rclrs::{Context, RclrsError, SubscriptionOptions, QOS_PROFILE_DEFAULT};
+/// let context = Context::new([])?;
+/// let mut node = context.create_node("my_node")?;
+/// let mut options = SubscriptionOptions::default();
+/// options.ignore_local_publications = true;
+/// let subscription = node.create_subscription_with_options::<test_msgs::msg::Empty, _>(
+///     "topic",
+///     QOS_PROFILE_DEFAULT,
+///     options,
+///     |_| {},
+/// )?;
+/// # Ok::<(), RclrsError>(())
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SubscriptionOptions {
+    /// Whether to ignore messages from publishers in the same context.
+    ///
+    /// This is useful for nodes that publish and subscribe on the same topic. It is implemented
+    /// by the RMW layer where supported. Since not every RMW implementation supports it, messages
+    /// are additionally filtered by the GIDs of the publishers in the same context when spinning,
+    /// like with [`Subscription::ignore_publisher()`].
+    pub ignore_local_publications: bool,
+    /// Whether to opt in to intra-process communication with publishers in the same process.
+    ///
+    /// rclrs does not have an intra-process path yet, so messages are still delivered through
+    /// the RMW layer. The flag is stored so that the subscription can be checked with
+    /// [`Subscription::is_intra_process_capable()`]. It is disabled by default.
+    pub use_intra_process: bool,
+    /// Whether to log a warning when messages are lost, e.g. because the history depth of the QoS
+    /// profile is too shallow for the publishing rate.
+    ///
+    /// Lost messages are detected with the [`MessageLostStatus`][1] event, which is not
+    /// available on Foxy, and not supported by every RMW implementation. In those cases, this
+    /// flag has no effect besides a single warning at creation. The warnings are logged to the
+    /// logger of the node, at most once per second, with the number of messages lost since the
+    /// previous warning.
+    ///
+    /// [1]: crate::MessageLostStatus
+    pub warn_on_drop: bool,
+    /// The group that the callback belongs to, which controls whether it may run at the same time
+    /// as other callbacks in a multi-threaded [`Executor`][1].
+    ///
+    /// By default, the callback belongs to the mutually exclusive default group of the node. See
+    /// [`CallbackGroup`][2].
+    ///
+    /// [1]: crate::Executor
+    /// [2]: crate::CallbackGroup
+    pub callback_group: Option<Arc<CallbackGroup>>,
 }
 
 /// Struct for receiving messages of type `T`.
@@ -66,6 +137,13 @@ where
     pub(crate) handle: Arc<SubscriptionHandle>,
     /// The callback function that runs when a message was received.
     pub callback: Mutex<Box<dyn FnMut(T) + 'static + Send>>,
+    drain_on_ready: AtomicBool,
+    ignored_publishers: Mutex<Vec<Gid>>,
+    ignore_local_publications: bool,
+    intra_process_capable: bool,
+    callback_group: Arc<CallbackGroup>,
+    // The event that logs lost messages, see SubscriptionOptions::warn_on_drop.
+    pub(crate) drop_warning: Option<Arc<dyn EventBase>>,
     message: PhantomData<T>,
 }
 
@@ -74,15 +152,27 @@ where
     T: Message,
 {
     /// Creates a new subscription.
-    ///
-    /// # Panics
-    /// When the topic contains interior null bytes.
     pub fn new<F>(
         node: &Node,
         topic: &str,
         qos: QoSProfile,
         callback: F,
     ) -> Result<Self, RclrsError>
+    where
+        T: Message,
+        F: FnMut(T) + 'static + Send,
+    {
+        Self::new_with_options(node, topic, qos, SubscriptionOptions::default(), callback)
+    }
+
+    /// Creates a new subscription with the given options.
+    pub fn new_with_options<F>(
+        node: &Node,
+        topic: &str,
+        qos: QoSProfile,
+        options: SubscriptionOptions,
+        callback: F,
+    ) -> Result<Self, RclrsError>
     where
         T: Message,
         F: FnMut(T) + 'static + Send,
@@ -91,12 +181,18 @@ where
         let mut subscription_handle = unsafe { rcl_get_zero_initialized_subscription() };
         let type_support =
             <T as Message>::RmwMsg::get_type_support() as *const rosidl_message_type_support_t;
+        validate_topic_name(topic)?;
+        // Can't fail, since a valid topic name has no null bytes
         let topic_c_string = CString::new(topic).unwrap();
         let node_handle = &mut *node.handle.lock();
 
         // SAFETY: No preconditions for this function.
         let mut subscription_options = unsafe { rcl_subscription_get_default_options() };
         subscription_options.qos = qos.into();
+        subscription_options.allocator = node_allocator(node_handle);
+        subscription_options
+            .rmw_subscription_options
+            .ignore_local_publications = options.ignore_local_publications;
         unsafe {
             // SAFETY: The subscription handle is zero-initialized as expected by this function.
             // The node handle is kept alive because it is co-owned by the subscription.
@@ -110,7 +206,7 @@ where
                 topic_c_string.as_ptr(),
                 &subscription_options,
             )
-            .ok()?;
+            .ok_with_context("rcl_subscription_init")?;
         }
 
         let handle = Arc::new(SubscriptionHandle {
@@ -121,10 +217,49 @@ where
         Ok(Self {
             handle,
             callback: Mutex::new(Box::new(callback)),
+            drain_on_ready: AtomicBool::new(false),
+            ignored_publishers: Mutex::new(Vec::new()),
+            ignore_local_publications: options.ignore_local_publications,
+            intra_process_capable: options.use_intra_process && qos.is_intra_process_compatible(),
+            callback_group: options
+                .callback_group
+                .unwrap_or_else(|| node.default_callback_group.clone()),
+            drop_warning: None,
             message: PhantomData,
         })
     }
 
+    /// Returns `true` if this subscription can receive messages through intra-process
+    /// communication.
+    ///
+    /// This requires [`SubscriptionOptions::use_intra_process`] to be set, and a QoS profile with
+    /// a volatile durability and a `KeepLast` history with a non-zero depth.
+    pub fn is_intra_process_capable(&self) -> bool {
+        self.intra_process_capable
+    }
+
+    /// Returns the fully qualified topic name of the subscription.
+    ///
+    /// This is the name that the subscription is actually bound to, i.e. after the namespace of
+    /// the node has been applied, and the name has been remapped.
+    pub fn topic_name(&self) -> String {
+        let handle = &*self.handle.lock();
+        // SAFETY: The subscription handle is valid. The returned string is owned by the
+        // subscription, and is immediately converted to an owned string.
+        unsafe {
+            let topic_name = rcl_subscription_get_topic_name(handle);
+            if topic_name.is_null() {
+                return String::new();
+            }
+            CStr::from_ptr(topic_name).to_string_lossy().into_owned()
+        }
+    }
+
+    /// Returns the full name of the message type, e.g. `"std_msgs/msg/String"`.
+    pub fn type_name(&self) -> String {
+        message_type_name::<T>()
+    }
+
     /// Fetches a new message.
     ///
     /// When there is no new message, this will return a
@@ -165,6 +300,199 @@ where
         ret.ok()?;
         Ok(T::from_rmw_message(rmw_message))
     }
+
+    /// Fetches a new message together with its [`MessageInfo`].
+    ///
+    /// This behaves like [`take`][1], but additionally reports metadata such as the GID of the
+    /// publisher that sent the message.
+    ///
+    /// [1]: Self::take
+    pub fn take_with_info(&self) -> Result<(T, MessageInfo), RclrsError> {
+        let mut rmw_message = <T as Message>::RmwMsg::default();
+        // SAFETY: Getting a zero-initialized value is always safe.
+        let mut message_info = unsafe { rmw_get_zero_initialized_message_info() };
+        let handle = &mut *self.handle.lock();
+        let ret = unsafe {
+            // SAFETY: The first three pointers are valid/initialized, and do not need to be valid
+            // beyond the function call.
+            // The last pointer is explicitly allowed to be NULL.
+            rcl_take(
+                handle,
+                &mut rmw_message as *mut <T as Message>::RmwMsg as *mut _,
+                &mut message_info,
+                std::ptr::null_mut(),
+            )
+        };
+        ret.ok()?;
+        Ok((
+            T::from_rmw_message(rmw_message),
+            MessageInfo::from(&message_info),
+        ))
+    }
+
+    /// Fetches all currently available messages, without blocking.
+    ///
+    /// Messages are taken until no more are available. When there is no message at all, an empty
+    /// `Vec` is returned instead of an error.
+    pub fn take_all(&self) -> Result<Vec<T>, RclrsError> {
+        let mut messages = Vec::new();
+        loop {
+            match self.take() {
+                Ok(message) => messages.push(message),
+                Err(RclrsError {
+                    code:
+                        RclReturnCode::SubscriberError(SubscriberErrorCode::SubscriptionTakeFailed),
+                    ..
+                }) => return Ok(messages),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Sets whether all available messages are handled when the subscription becomes ready.
+    ///
+    /// By default, [`spin_once`][1] takes only one message from a ready subscription and runs the
+    /// callback with it, and any further messages are handled in later iterations. When this is
+    /// enabled, all available messages are taken as with [`take_all`][2], and the callback runs
+    /// once for each of them. This reduces the number of wakeups in high-throughput scenarios.
+    ///
+    /// [1]: crate::spin_once
+    /// [2]: Self::take_all
+    pub fn set_drain_on_ready(&self, drain_on_ready: bool) {
+        self.drain_on_ready.store(drain_on_ready, Ordering::Relaxed);
+    }
+
+    /// Returns whether all available messages are handled when the subscription becomes ready.
+    ///
+    /// See [`set_drain_on_ready`][1].
+    ///
+    /// [1]: Self::set_drain_on_ready
+    pub fn drain_on_ready(&self) -> bool {
+        self.drain_on_ready.load(Ordering::Relaxed)
+    }
+
+    /// Skips messages from the given publisher when spinning.
+    ///
+    /// The messages of the publisher are discarded instead of being passed to the callback. This
+    /// is done by comparing the [`publisher_gid`][1] of each message, which works with any RMW
+    /// implementation. Messages fetched directly with e.g. [`take`][2] are not filtered.
+    ///
+    /// # Example
+    /// ```
+    /// # use rclrs::{Context, RclrsError, QOS_PROFILE_DEFAULT};
+    /// let context = Context::new([])?;
+    /// let mut node = context.create_node("my_node")?;
+    /// let publisher = node.create_publisher::<test_msgs::msg::Empty>("topic", QOS_PROFILE_DEFAULT)?;
+    /// let subscription = node.create_subscription::<test_msgs::msg::Empty, _>(
+    ///     "topic",
+    ///     QOS_PROFILE_DEFAULT,
+    ///     |_| {},
+    /// )?;
+    /// // Don't receive our own messages
+    /// subscription.ignore_publisher(publisher.gid()?);
+    /// # Ok::<(), RclrsError>(())
+    /// ```
+    ///
+    /// [1]: crate::MessageInfo::publisher_gid
+    /// [2]: Self::take
+    pub fn ignore_publisher(&self, gid: Gid) {
+        let ignored_publishers = &mut *self.ignored_publishers.lock();
+        if !ignored_publishers.contains(&gid) {
+            ignored_publishers.push(gid);
+        }
+    }
+
+    // Like take(), but skips messages from ignored publishers.
+    fn take_unignored(&self) -> Result<T, RclrsError> {
+        let mut ignored_publishers = self.ignored_publishers.lock().clone();
+        if self.ignore_local_publications {
+            // Not every RMW implementation supports the option, so the local publishers are
+            // filtered here as well.
+            let context_handle = &self.handle.node_handle.context_handle;
+            ignored_publishers.extend(context_handle.local_publishers.lock().iter().copied());
+        }
+        if ignored_publishers.is_empty() {
+            return self.take();
+        }
+        loop {
+            let (msg, info) = self.take_with_info()?;
+            if !ignored_publishers.contains(&info.publisher_gid) {
+                return Ok(msg);
+            }
+        }
+    }
+
+    /// Fetches the newest available message, without blocking.
+    ///
+    /// All messages that are currently queued are taken, and all but the newest one are
+    /// discarded. When no message is available, `Ok(None)` is returned instead of an error.
+    ///
+    /// This is meant for polling the subscription, e.g. once per iteration of a control loop,
+    /// instead of reacting to each message in a callback. Consider creating such a subscription
+    /// with a callback that does nothing, and not spinning its node, since spinning would take
+    /// the messages away.
+    pub fn take_latest(&self) -> Result<Option<T>, RclrsError> {
+        let mut latest = None;
+        let mut rmw_message = <T as Message>::RmwMsg::default();
+        let handle = &mut *self.handle.lock();
+        loop {
+            let ret = unsafe {
+                // SAFETY: The first two pointers are valid/initialized, and do not need to be
+                // valid beyond the function call.
+                // The latter two pointers are explicitly allowed to be NULL.
+                rcl_take(
+                    handle,
+                    &mut rmw_message as *mut <T as Message>::RmwMsg as *mut _,
+                    std::ptr::null_mut(),
+                    std::ptr::null_mut(),
+                )
+            };
+            match ret.ok() {
+                // Only the newest message needs to be converted
+                Ok(()) => latest = Some(std::mem::take(&mut rmw_message)),
+                Err(RclrsError {
+                    code:
+                        RclReturnCode::SubscriberError(SubscriberErrorCode::SubscriptionTakeFailed),
+                    ..
+                }) => break,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(latest.map(T::from_rmw_message))
+    }
+
+    /// Fetches a new message, together with its serialized representation.
+    ///
+    /// This is meant for e.g. forwarding messages to another system while also inspecting them.
+    /// The message is only taken once, and then serialized like with
+    /// [`MessageSerialization::serialize_cdr()`][1]. When no message is available, `Ok(None)` is
+    /// returned instead of an error.
+    ///
+    /// [1]: crate::MessageSerialization::serialize_cdr
+    pub fn take_both(&self) -> Result<Option<(T, Vec<u8>)>, RclrsError> {
+        let mut rmw_message = <T as Message>::RmwMsg::default();
+        let ret = unsafe {
+            // SAFETY: The first two pointers are valid/initialized, and do not need to be valid
+            // beyond the function call.
+            // The latter two pointers are explicitly allowed to be NULL.
+            rcl_take(
+                &*self.handle.lock(),
+                &mut rmw_message as *mut <T as Message>::RmwMsg as *mut _,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            )
+        };
+        match ret.ok() {
+            Ok(()) => {}
+            Err(RclrsError {
+                code: RclReturnCode::SubscriberError(SubscriberErrorCode::SubscriptionTakeFailed),
+                ..
+            }) => return Ok(None),
+            Err(e) => return Err(e),
+        }
+        let bytes = serialize_rmw_message(&rmw_message)?;
+        Ok(Some((T::from_rmw_message(rmw_message), bytes)))
+    }
 }
 
 impl<T> SubscriptionBase for Subscription<T>
@@ -175,20 +503,330 @@ where
         self.handle.borrow()
     }
 
+    fn callback_group(&self) -> &Arc<CallbackGroup> {
+        &self.callback_group
+    }
+
     fn execute(&self) -> Result<(), RclrsError> {
-        let msg = match self.take() {
-            Ok(msg) => msg,
-            Err(RclrsError {
-                code: RclReturnCode::SubscriberError(SubscriberErrorCode::SubscriptionTakeFailed),
-                ..
-            }) => {
-                // Spurious wakeup – this may happen even when a waitset indicated that this
-                // subscription was ready, so it shouldn't be an error.
+        loop {
+            let msg = match self.take_unignored() {
+                Ok(msg) => msg,
+                Err(RclrsError {
+                    code:
+                        RclReturnCode::SubscriberError(SubscriberErrorCode::SubscriptionTakeFailed),
+                    ..
+                }) => {
+                    // Spurious wakeup – this may happen even when a waitset indicated that this
+                    // subscription was ready, so it shouldn't be an error. This is also how
+                    // draining the subscription ends.
+                    return Ok(());
+                }
+                Err(e) => return Err(e),
+            };
+            (*self.callback.lock())(msg);
+            if !self.drain_on_ready() {
                 return Ok(());
             }
-            Err(e) => return Err(e),
+        }
+    }
+}
+
+// Creates the callback of the event that logs lost messages, see
+// SubscriptionOptions::warn_on_drop.
+#[cfg(not(ros_distro = "foxy"))]
+pub(crate) fn drop_warning_callback(
+    logger_name: String,
+    topic_name: String,
+) -> impl FnMut(crate::MessageLostStatus) + 'static + Send {
+    const PERIOD: std::time::Duration = std::time::Duration::from_secs(1);
+    let last_warned = crate::LastLogged::new();
+    // Messages that are lost while the warning is throttled are reported with the next warning
+    let mut lost_since_warning = 0;
+    move |status| {
+        lost_since_warning += status.total_count_change;
+        if lost_since_warning > 0 && crate::throttle_elapsed(&last_warned, PERIOD) {
+            crate::log_warn!(
+                logger_name,
+                "Lost {} messages on topic '{}', the history depth may be too shallow",
+                lost_since_warning,
+                topic_name
+            );
+            lost_since_warning = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Context, MessageSerialization, QoSHistoryPolicy, QOS_PROFILE_DEFAULT};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn take_latest_returns_newest_message() -> Result<(), RclrsError> {
+        let context = Context::new([])?;
+        let mut node = context.create_node("subscription_take_latest_test_node")?;
+        let publisher = node.create_publisher::<test_msgs::msg::Strings>(
+            "subscription_take_latest_test",
+            QOS_PROFILE_DEFAULT,
+        )?;
+        let subscription = node.create_subscription::<test_msgs::msg::Strings, _>(
+            "subscription_take_latest_test",
+            QOS_PROFILE_DEFAULT,
+            |_| {},
+        )?;
+        // Another subscription on the same topic shows when both messages have been delivered
+        let probe = node.create_subscription::<test_msgs::msg::Strings, _>(
+            "subscription_take_latest_test",
+            QOS_PROFILE_DEFAULT,
+            |_| {},
+        )?;
+        assert!(subscription.take_latest()?.is_none());
+
+        publisher.wait_for_matched(2, Duration::from_secs(5))?;
+        for text in ["old", "new"] {
+            publisher.publish(test_msgs::msg::Strings {
+                string_value: text.to_owned(),
+                ..Default::default()
+            })?;
+        }
+        let start = Instant::now();
+        let mut delivered = 0;
+        while delivered < 2 {
+            assert!(start.elapsed() < Duration::from_secs(5));
+            delivered += probe.take_all()?.len();
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        let latest = subscription.take_latest()?;
+        assert_eq!(latest.unwrap().string_value, "new");
+        assert!(subscription.take_latest()?.is_none());
+        Ok(())
+    }
+
+    // Spins the node until `count` messages have been received, or a timeout has passed, and
+    // then a bit longer to catch any extra messages.
+    fn spin_until_received(node: &Node, received: &Mutex<Vec<String>>, count: usize) {
+        let start = Instant::now();
+        while received.lock().len() < count && start.elapsed() < Duration::from_secs(5) {
+            let _ = crate::spin_once(node, Some(Duration::from_millis(100)));
+        }
+        for _ in 0..3 {
+            let _ = crate::spin_once(node, Some(Duration::from_millis(100)));
+        }
+    }
+
+    #[test]
+    fn local_publications_are_ignored() -> Result<(), RclrsError> {
+        let context = Context::new([])?;
+        let mut node = context.create_node("subscription_ignore_local_test_node")?;
+        let local_publisher = node.create_publisher::<test_msgs::msg::Strings>(
+            "subscription_ignore_local_test",
+            QOS_PROFILE_DEFAULT,
+        )?;
+        // A publisher in another context is not local
+        let other_context = Context::new([])?;
+        let other_node = other_context.create_node("subscription_ignore_local_other_node")?;
+        let other_publisher = other_node.create_publisher::<test_msgs::msg::Strings>(
+            "subscription_ignore_local_test",
+            QOS_PROFILE_DEFAULT,
+        )?;
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let options = SubscriptionOptions {
+            ignore_local_publications: true,
+            ..Default::default()
+        };
+        let _subscription = node.create_subscription_with_options::<test_msgs::msg::Strings, _>(
+            "subscription_ignore_local_test",
+            QOS_PROFILE_DEFAULT,
+            options,
+            {
+                let received = received.clone();
+                move |msg| received.lock().push(msg.string_value)
+            },
+        )?;
+
+        let start = Instant::now();
+        while (local_publisher.get_subscription_count()? == 0
+            || other_publisher.get_subscription_count()? == 0)
+            && start.elapsed() < Duration::from_secs(5)
+        {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        for (publisher, text) in [(&local_publisher, "local"), (&other_publisher, "other")] {
+            publisher.publish(test_msgs::msg::Strings {
+                string_value: text.to_owned(),
+                ..Default::default()
+            })?;
+        }
+        spin_until_received(&node, &received, 1);
+        assert_eq!(*received.lock(), ["other"]);
+        Ok(())
+    }
+
+    #[test]
+    fn ignored_publishers_are_filtered() -> Result<(), RclrsError> {
+        let context = Context::new([])?;
+        let mut node = context.create_node("subscription_ignore_publisher_test_node")?;
+        let ignored_publisher = node.create_publisher::<test_msgs::msg::Strings>(
+            "subscription_ignore_publisher_test",
+            QOS_PROFILE_DEFAULT,
+        )?;
+        let publisher = node.create_publisher::<test_msgs::msg::Strings>(
+            "subscription_ignore_publisher_test",
+            QOS_PROFILE_DEFAULT,
+        )?;
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let subscription = node.create_subscription::<test_msgs::msg::Strings, _>(
+            "subscription_ignore_publisher_test",
+            QOS_PROFILE_DEFAULT,
+            {
+                let received = received.clone();
+                move |msg| received.lock().push(msg.string_value)
+            },
+        )?;
+        subscription.ignore_publisher(ignored_publisher.gid()?);
+
+        let start = Instant::now();
+        while (ignored_publisher.get_subscription_count()? == 0
+            || publisher.get_subscription_count()? == 0)
+            && start.elapsed() < Duration::from_secs(5)
+        {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        for (publisher, text) in [(&ignored_publisher, "ignored"), (&publisher, "received")] {
+            publisher.publish(test_msgs::msg::Strings {
+                string_value: text.to_owned(),
+                ..Default::default()
+            })?;
+        }
+        spin_until_received(&node, &received, 1);
+        assert_eq!(*received.lock(), ["received"]);
+        Ok(())
+    }
+
+    #[test]
+    fn take_all_drains_available_messages() -> Result<(), RclrsError> {
+        let context = Context::new([])?;
+        let mut node = context.create_node("subscription_take_all_test_node")?;
+        let qos = QoSProfile {
+            history: QoSHistoryPolicy::KeepLast { depth: 10 },
+            ..QOS_PROFILE_DEFAULT
+        };
+        let publisher =
+            node.create_publisher::<test_msgs::msg::Empty>("subscription_take_all_test", qos)?;
+        let subscription = node.create_subscription::<test_msgs::msg::Empty, _>(
+            "subscription_take_all_test",
+            qos,
+            |_| {},
+        )?;
+        assert!(subscription.take_all()?.is_empty());
+
+        let start = Instant::now();
+        while publisher.get_subscription_count()? == 0 && start.elapsed() < Duration::from_secs(5) {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        for _ in 0..5 {
+            publisher.publish(test_msgs::msg::Empty::default())?;
+        }
+        // Give the middleware some time to deliver all messages
+        std::thread::sleep(Duration::from_millis(100));
+        assert_eq!(subscription.take_all()?.len(), 5);
+        assert!(subscription.take_all()?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn drain_on_ready_handles_all_messages_at_once() -> Result<(), RclrsError> {
+        let context = Context::new([])?;
+        let mut node = context.create_node("subscription_drain_test_node")?;
+        let qos = QoSProfile {
+            history: QoSHistoryPolicy::KeepLast { depth: 10 },
+            ..QOS_PROFILE_DEFAULT
+        };
+        let publisher =
+            node.create_publisher::<test_msgs::msg::Empty>("subscription_drain_test", qos)?;
+        let received = Arc::new(Mutex::new(0));
+        let subscription = node.create_subscription::<test_msgs::msg::Empty, _>(
+            "subscription_drain_test",
+            qos,
+            {
+                let received = received.clone();
+                move |_| *received.lock() += 1
+            },
+        )?;
+        assert!(!subscription.drain_on_ready());
+        subscription.set_drain_on_ready(true);
+
+        let start = Instant::now();
+        while publisher.get_subscription_count()? == 0 && start.elapsed() < Duration::from_secs(5) {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        for _ in 0..5 {
+            publisher.publish(test_msgs::msg::Empty::default())?;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+        crate::spin_once(&node, Some(Duration::from_secs(5)))?;
+        assert_eq!(*received.lock(), 5);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(not(ros_distro = "foxy"))]
+    fn subscription_with_drop_warning() -> Result<(), RclrsError> {
+        let context = Context::new([])?;
+        let mut node = context.create_node("drop_warning_test_node")?;
+        // Without support for the event, the subscription only warns that it can't detect drops
+        if !crate::message_lost_event_supported(&mut node)? {
+            return Ok(());
+        }
+        let options = SubscriptionOptions {
+            warn_on_drop: true,
+            ..Default::default()
+        };
+        let subscription = node.create_subscription_with_options::<test_msgs::msg::Empty, _>(
+            "drop_warning_test",
+            QOS_PROFILE_DEFAULT,
+            options,
+            |_| {},
+        )?;
+        assert!(subscription.drop_warning.is_some());
+        // The event is owned by the subscription, and waited on by the node
+        assert_eq!(node.live_events().len(), 1);
+        drop(subscription);
+        assert!(node.live_events().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn take_both_returns_message_and_bytes_of_one_sample() -> Result<(), RclrsError> {
+        let context = Context::new([])?;
+        let mut node = context.create_node("take_both_test_node")?;
+        let qos = QoSProfile {
+            history: QoSHistoryPolicy::KeepLast { depth: 10 },
+            ..QOS_PROFILE_DEFAULT
+        };
+        let publisher = node.create_publisher::<test_msgs::msg::Strings>("take_both_test", qos)?;
+        let subscription =
+            node.create_subscription::<test_msgs::msg::Strings, _>("take_both_test", qos, |_| {})?;
+        assert!(subscription.take_both()?.is_none());
+        publisher.wait_for_matched(1, Duration::from_secs(5))?;
+
+        let sent = test_msgs::msg::Strings {
+            string_value: String::from("forwarded"),
+            ..Default::default()
+        };
+        publisher.publish(&sent)?;
+        let start = Instant::now();
+        let (received, bytes) = loop {
+            if let Some(both) = subscription.take_both()? {
+                break both;
+            }
+            assert!(start.elapsed() < Duration::from_secs(5));
+            std::thread::sleep(Duration::from_millis(10));
         };
-        (*self.callback.lock())(msg);
+        assert_eq!(received, sent);
+        assert_eq!(test_msgs::msg::Strings::deserialize_cdr(&bytes)?, received);
+        assert_eq!(bytes, received.serialize_cdr()?);
         Ok(())
     }
 }
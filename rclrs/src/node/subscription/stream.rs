@@ -0,0 +1,187 @@
+use crate::error::{RclReturnCode, SubscriberErrorCode};
+use crate::{
+    Context, GuardCondition, RclrsError, Subscription, SubscriptionBase, WaitSet, WaitSetCapacities,
+};
+
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context as TaskContext, Poll};
+use std::thread::JoinHandle;
+
+use futures::channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use futures::Stream;
+use rosidl_runtime_rs::Message;
+
+/// A stream of the messages received by a subscription, created by
+/// [`Subscription::into_stream()`].
+///
+/// The subscription is waited on by a dedicated thread, which forwards each message into the
+/// stream. Dropping the stream stops the thread and drops the subscription.
+///
+/// The stream ends when an error occurs while waiting or taking a message.
+pub struct SubscriptionStream<T: Message> {
+    receiver: UnboundedReceiver<T>,
+    // Wakes up the worker thread so that it notices that the stream has been dropped.
+    stop_condition: Arc<GuardCondition>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl<T> Subscription<T>
+where
+    T: Message,
+{
+    /// Converts the subscription into a stream of messages, for use in `async` code.
+    ///
+    /// The callback of the subscription is not called anymore. The subscription should not be
+    /// created through [`Node::create_subscription`][1], since spinning the node would then take
+    /// messages away from the stream – use [`Subscription::new`] instead.
+    ///
+    /// # Example
+    /// ```
+    /// # use rclrs::{Context, RclrsError, Subscription, QOS_PROFILE_DEFAULT};
+    /// use futures::StreamExt;
+    ///
+    /// let context = Context::new([])?;
+    /// let node = context.create_node("my_node")?;
+    /// let subscription = Subscription::<test_msgs::msg::Empty>::new(
+    ///     &node,
+    ///     "topic",
+    ///     QOS_PROFILE_DEFAULT,
+    ///     |_| {},
+    /// )?;
+    /// let mut stream = subscription.into_stream()?;
+    /// // In an async function:
+    /// // while let Some(message) = stream.next().await { ... }
+    /// # Ok::<(), RclrsError>(())
+    /// ```
+    ///
+    /// [1]: crate::Node::create_subscription
+    pub fn into_stream(self) -> Result<SubscriptionStream<T>, RclrsError> {
+        let context = Context {
+            handle: self.handle.node_handle.context_handle.clone(),
+        };
+        let stop_condition = Arc::new(GuardCondition::new(&context)?);
+        let (sender, receiver) = mpsc::unbounded();
+        let worker = std::thread::spawn({
+            let subscription = Arc::new(self);
+            let stop_condition = stop_condition.clone();
+            move || {
+                // Errors can't be reported from the thread, they just end the stream.
+                let _ = forward_messages(&context, subscription, stop_condition, sender);
+            }
+        });
+        Ok(SubscriptionStream {
+            receiver,
+            stop_condition,
+            worker: Some(worker),
+        })
+    }
+}
+
+// The loop of the worker thread of a SubscriptionStream.
+fn forward_messages<T: Message>(
+    context: &Context,
+    subscription: Arc<Subscription<T>>,
+    stop_condition: Arc<GuardCondition>,
+    sender: UnboundedSender<T>,
+) -> Result<(), RclrsError> {
+    let capacities = WaitSetCapacities {
+        subscriptions: 1,
+        guard_conditions: 1,
+        ..Default::default()
+    };
+    let mut wait_set = WaitSet::new(capacities, context)?;
+    wait_set.add_subscription(subscription.clone() as Arc<dyn SubscriptionBase>)?;
+    wait_set.add_guard_condition(stop_condition)?;
+    loop {
+        let ready_entities = wait_set.wait(None)?;
+        if !ready_entities.guard_conditions.is_empty() {
+            return Ok(());
+        }
+        if ready_entities.subscriptions.is_empty() {
+            continue;
+        }
+        loop {
+            match subscription.take() {
+                Ok(message) => {
+                    if sender.unbounded_send(message).is_err() {
+                        // The stream has been dropped
+                        return Ok(());
+                    }
+                }
+                Err(RclrsError {
+                    code:
+                        RclReturnCode::SubscriberError(SubscriberErrorCode::SubscriptionTakeFailed),
+                    ..
+                }) => break,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+impl<T: Message> Stream for SubscriptionStream<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<T>> {
+        Pin::new(&mut self.receiver).poll_next(cx)
+    }
+}
+
+impl<T: Message> Drop for SubscriptionStream<T> {
+    fn drop(&mut self) {
+        // If triggering fails, the worker will still stop once it tries to forward a message.
+        let _ = self.stop_condition.trigger();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{QoSDurabilityPolicy, QoSHistoryPolicy, QoSProfile, QOS_PROFILE_DEFAULT};
+    use futures::StreamExt;
+    use std::time::Duration;
+
+    #[test]
+    fn stream_yields_published_messages() -> Result<(), RclrsError> {
+        let context = Context::new([])?;
+        let node = context.create_node("subscription_stream_test_node")?;
+        // Transient local durability makes sure the messages are delivered even if the
+        // subscription is matched only after publishing.
+        let qos = QoSProfile {
+            durability: QoSDurabilityPolicy::TransientLocal,
+            history: QoSHistoryPolicy::KeepLast { depth: 3 },
+            ..QOS_PROFILE_DEFAULT
+        };
+        let publisher =
+            node.create_publisher::<test_msgs::msg::Strings>("subscription_stream_test", qos)?;
+        for text in ["one", "two", "three"] {
+            publisher.publish(test_msgs::msg::Strings {
+                string_value: text.to_owned(),
+                ..Default::default()
+            })?;
+        }
+        let subscription = Subscription::<test_msgs::msg::Strings>::new(
+            &node,
+            "subscription_stream_test",
+            qos,
+            |_| {},
+        )?;
+        let stream = subscription.into_stream()?;
+
+        let (result_sender, result_receiver) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let messages: Vec<_> = futures::executor::block_on(stream.take(3).collect());
+            let _ = result_sender.send(messages);
+        });
+        let messages = result_receiver
+            .recv_timeout(Duration::from_secs(5))
+            .expect("Timed out waiting for messages");
+        let texts: Vec<_> = messages.into_iter().map(|m| m.string_value).collect();
+        assert_eq!(texts, ["one", "two", "three"]);
+        Ok(())
+    }
+}
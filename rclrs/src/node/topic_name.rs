@@ -0,0 +1,242 @@
+use crate::error::{RclErrorCode, RclErrorMsg, RclReturnCode};
+use crate::rcl_bindings::*;
+use crate::{RclrsError, ToResult, TopicNameError, TopicNameErrorKind};
+
+use std::borrow::Cow;
+use std::ffi::CString;
+
+// The validation results of rcl_validate_topic_name(), as defined in rcl/validate_topic_name.h.
+const RCL_TOPIC_NAME_VALID: i32 = 0;
+const RCL_TOPIC_NAME_INVALID_IS_EMPTY_STRING: i32 = 1;
+const RCL_TOPIC_NAME_INVALID_ENDS_WITH_FORWARD_SLASH: i32 = 2;
+const RCL_TOPIC_NAME_INVALID_CONTAINS_UNALLOWED_CHARACTERS: i32 = 3;
+const RCL_TOPIC_NAME_INVALID_NAME_TOKEN_STARTS_WITH_NUMBER: i32 = 4;
+const RCL_TOPIC_NAME_INVALID_UNMATCHED_CURLY_BRACE: i32 = 5;
+const RCL_TOPIC_NAME_INVALID_MISPLACED_TILDE: i32 = 6;
+const RCL_TOPIC_NAME_INVALID_TILDE_NOT_FOLLOWED_BY_FORWARD_SLASH: i32 = 7;
+const RCL_TOPIC_NAME_INVALID_SUBSTITUTION_CONTAINS_UNALLOWED_CHARACTERS: i32 = 8;
+const RCL_TOPIC_NAME_INVALID_SUBSTITUTION_STARTS_WITH_NUMBER: i32 = 9;
+
+/// Checks whether a topic name is valid.
+///
+/// The name may be relative, private (starting with `~/`) or fully qualified, and may contain
+/// substitutions such as `{node}`. The rules are checked by `rcl_validate_topic_name()`, and
+/// additionally by `rmw_validate_full_topic_name()` for names without substitutions, which e.g.
+/// rejects repeated forward slashes.
+///
+/// Publishers and subscriptions check their topic name with this function when they are created.
+/// When the name is invalid, the error has the [`TopicNameInvalid`][1] code, and its
+/// [`source`][2] is a [`TopicNameError`] describing the problem.
+///
+/// # Example
+/// ```
+/// # use rclrs::{validate_topic_name, TopicNameError, TopicNameErrorKind};
+/// assert!(validate_topic_name("~/chatter").is_ok());
+/// let err = validate_topic_name("chatter/").unwrap_err();
+/// let topic_name_err = std::error::Error::source(&err)
+///     .and_then(|source| source.downcast_ref::<TopicNameError>())
+///     .unwrap();
+/// assert_eq!(topic_name_err.kind, TopicNameErrorKind::EndsWithForwardSlash);
+/// assert_eq!(topic_name_err.index, 7);
+/// ```
+///
+/// [1]: crate::RclErrorCode::TopicNameInvalid
+/// [2]: std::error::Error::source
+pub fn validate_topic_name(name: &str) -> Result<(), RclrsError> {
+    // rcl would only see the name up to an interior null byte
+    let c_name = CString::new(name).map_err(|e| {
+        topic_name_error(
+            name,
+            TopicNameErrorKind::UnallowedCharacter,
+            e.nul_position(),
+        )
+    })?;
+    let mut validation_result = 0;
+    let mut invalid_index = 0;
+    // SAFETY: The name is a valid null-terminated string and not stored. The output arguments
+    // are valid.
+    unsafe {
+        rcl_validate_topic_name(c_name.as_ptr(), &mut validation_result, &mut invalid_index)
+            .ok()?;
+    }
+    let kind = match validation_result {
+        RCL_TOPIC_NAME_VALID => return validate_full_topic_name(name),
+        RCL_TOPIC_NAME_INVALID_IS_EMPTY_STRING => TopicNameErrorKind::Empty,
+        RCL_TOPIC_NAME_INVALID_ENDS_WITH_FORWARD_SLASH => TopicNameErrorKind::EndsWithForwardSlash,
+        RCL_TOPIC_NAME_INVALID_CONTAINS_UNALLOWED_CHARACTERS => {
+            TopicNameErrorKind::UnallowedCharacter
+        }
+        RCL_TOPIC_NAME_INVALID_NAME_TOKEN_STARTS_WITH_NUMBER => {
+            TopicNameErrorKind::TokenStartsWithNumber
+        }
+        RCL_TOPIC_NAME_INVALID_UNMATCHED_CURLY_BRACE => TopicNameErrorKind::UnmatchedCurlyBrace,
+        RCL_TOPIC_NAME_INVALID_MISPLACED_TILDE => TopicNameErrorKind::MisplacedTilde,
+        RCL_TOPIC_NAME_INVALID_TILDE_NOT_FOLLOWED_BY_FORWARD_SLASH => {
+            TopicNameErrorKind::TildeNotFollowedByForwardSlash
+        }
+        RCL_TOPIC_NAME_INVALID_SUBSTITUTION_CONTAINS_UNALLOWED_CHARACTERS => {
+            TopicNameErrorKind::SubstitutionContainsUnallowedCharacters
+        }
+        RCL_TOPIC_NAME_INVALID_SUBSTITUTION_STARTS_WITH_NUMBER => {
+            TopicNameErrorKind::SubstitutionStartsWithNumber
+        }
+        _ => return Err(RclReturnCode::RclError(RclErrorCode::TopicNameInvalid).into()),
+    };
+    Err(topic_name_error(name, kind, invalid_index))
+}
+
+// Checks the rules that rcl_validate_topic_name() leaves to the validation of the expanded name.
+//
+// The name is made fully qualified by replacing a leading "~" or prepending "/". Names with
+// substitutions are skipped, since the substituted name is only known to rcl.
+fn validate_full_topic_name(name: &str) -> Result<(), RclrsError> {
+    if name.contains('{') {
+        return Ok(());
+    }
+    // The index in the original name is the index in the full name plus this offset
+    let (full_name, offset): (Cow<str>, isize) = if let Some(rest) = name.strip_prefix('~') {
+        (Cow::Borrowed(rest), 1)
+    } else if name.starts_with('/') {
+        (Cow::Borrowed(name), 0)
+    } else {
+        (Cow::Owned(format!("/{}", name)), -1)
+    };
+    let c_full_name = CString::new(full_name.as_ref()).unwrap();
+    let mut validation_result = 0;
+    let mut invalid_index = 0;
+    // SAFETY: The name is a valid null-terminated string and not stored. The output arguments
+    // are valid.
+    unsafe {
+        rmw_validate_full_topic_name(
+            c_full_name.as_ptr(),
+            &mut validation_result,
+            &mut invalid_index,
+        )
+        .ok()?;
+    }
+    let kind = match validation_result as u32 {
+        RMW_TOPIC_INVALID_CONTAINS_REPEATED_FORWARD_SLASH => {
+            TopicNameErrorKind::RepeatedForwardSlash
+        }
+        RMW_TOPIC_INVALID_TOO_LONG => TopicNameErrorKind::TooLong,
+        // Everything else has already been checked by rcl_validate_topic_name()
+        _ => return Ok(()),
+    };
+    let index = (invalid_index as isize + offset).max(0) as usize;
+    Err(topic_name_error(name, kind, index))
+}
+
+fn topic_name_error(name: &str, kind: TopicNameErrorKind, index: usize) -> RclrsError {
+    RclrsError {
+        code: RclReturnCode::RclError(RclErrorCode::TopicNameInvalid),
+        msg: Some(RclErrorMsg::TopicName(TopicNameError {
+            name: name.to_owned(),
+            kind,
+            index,
+        })),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Context, QOS_PROFILE_DEFAULT};
+
+    fn error_kind(name: &str) -> Option<TopicNameErrorKind> {
+        let err = validate_topic_name(name).err()?;
+        assert_eq!(
+            err.code,
+            RclReturnCode::RclError(RclErrorCode::TopicNameInvalid)
+        );
+        let topic_name_err = std::error::Error::source(&err)
+            .and_then(|source| source.downcast_ref::<TopicNameError>())
+            .unwrap();
+        assert_eq!(topic_name_err.name, name);
+        Some(topic_name_err.kind)
+    }
+
+    #[test]
+    fn valid_topic_names() {
+        for name in [
+            "chatter",
+            "/ns/chatter",
+            "~/chatter",
+            "{node}/chatter",
+            "a_1/b",
+        ] {
+            assert_eq!(error_kind(name), None, "{}", name);
+        }
+    }
+
+    #[test]
+    fn invalid_topic_names() {
+        let cases = [
+            ("", TopicNameErrorKind::Empty),
+            ("chatter/", TopicNameErrorKind::EndsWithForwardSlash),
+            ("/ns//chatter", TopicNameErrorKind::RepeatedForwardSlash),
+            ("ns//chatter", TopicNameErrorKind::RepeatedForwardSlash),
+            ("~//chatter", TopicNameErrorKind::RepeatedForwardSlash),
+            ("chat-ter", TopicNameErrorKind::UnallowedCharacter),
+            ("/ns/1chatter", TopicNameErrorKind::TokenStartsWithNumber),
+            ("{node", TopicNameErrorKind::UnmatchedCurlyBrace),
+            ("ns/~/chatter", TopicNameErrorKind::MisplacedTilde),
+        ];
+        for (name, kind) in cases {
+            assert_eq!(error_kind(name), Some(kind), "{}", name);
+        }
+    }
+
+    #[test]
+    fn null_byte_is_an_unallowed_character() {
+        let err = validate_topic_name("chat\0ter").unwrap_err();
+        let topic_name_err = std::error::Error::source(&err)
+            .and_then(|source| source.downcast_ref::<TopicNameError>())
+            .unwrap();
+        assert_eq!(topic_name_err.kind, TopicNameErrorKind::UnallowedCharacter);
+        assert_eq!(topic_name_err.index, 4);
+    }
+
+    #[test]
+    fn repeated_slash_index_refers_to_original_name() {
+        for name in ["ns//chatter", "/ns//chatter", "~/ns//chatter"] {
+            let err = validate_topic_name(name).unwrap_err();
+            let topic_name_err = std::error::Error::source(&err)
+                .and_then(|source| source.downcast_ref::<TopicNameError>())
+                .unwrap();
+            // One of the two slashes
+            let index = name.find("//").unwrap();
+            assert!(
+                (index..=index + 1).contains(&topic_name_err.index),
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn publisher_and_subscription_validate_topic_name() -> Result<(), RclrsError> {
+        let context = Context::new([])?;
+        let mut node = context.create_node("topic_name_test_node")?;
+        let err = node
+            .create_publisher::<test_msgs::msg::Empty>("ns//chatter", QOS_PROFILE_DEFAULT)
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.code,
+            RclReturnCode::RclError(RclErrorCode::TopicNameInvalid)
+        );
+        let err = node
+            .create_subscription::<test_msgs::msg::Empty, _>(
+                "chatter/",
+                QOS_PROFILE_DEFAULT,
+                |_| {},
+            )
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.code,
+            RclReturnCode::RclError(RclErrorCode::TopicNameInvalid)
+        );
+        Ok(())
+    }
+}
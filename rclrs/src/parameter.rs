@@ -0,0 +1,164 @@
+use rcl_interfaces::msg::{ParameterType, ParameterValue as ParameterValueMsg};
+
+/// The value of a parameter.
+///
+/// The variants correspond to the types in `rcl_interfaces/msg/ParameterType`, except for
+/// `PARAMETER_NOT_SET`. An unset parameter is represented by the absence of a value, e.g.
+/// `Option<ParameterValue>`.
+///
+/// # Example
+/// ```
+/// # use rclrs::ParameterValue;
+/// let value = ParameterValue::IntegerArray(vec![1, 2, 3]);
+/// let msg = value.to_msg();
+/// assert_eq!(msg.integer_array_value, [1, 2, 3]);
+/// assert_eq!(ParameterValue::from_msg(msg), Some(value));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParameterValue {
+    /// A boolean value, with the `PARAMETER_BOOL` type.
+    Bool(bool),
+    /// An integer value, with the `PARAMETER_INTEGER` type.
+    Integer(i64),
+    /// A floating-point value, with the `PARAMETER_DOUBLE` type.
+    Double(f64),
+    /// A string value, with the `PARAMETER_STRING` type.
+    String(String),
+    /// An array of bytes, with the `PARAMETER_BYTE_ARRAY` type.
+    ByteArray(Vec<u8>),
+    /// An array of booleans, with the `PARAMETER_BOOL_ARRAY` type.
+    BoolArray(Vec<bool>),
+    /// An array of integers, with the `PARAMETER_INTEGER_ARRAY` type.
+    IntegerArray(Vec<i64>),
+    /// An array of floating-point values, with the `PARAMETER_DOUBLE_ARRAY` type.
+    DoubleArray(Vec<f64>),
+    /// An array of strings, with the `PARAMETER_STRING_ARRAY` type.
+    StringArray(Vec<String>),
+}
+
+impl ParameterValue {
+    /// Returns the type of the value, as one of the constants in
+    /// `rcl_interfaces/msg/ParameterType`.
+    pub fn type_tag(&self) -> u8 {
+        match self {
+            ParameterValue::Bool(_) => ParameterType::PARAMETER_BOOL,
+            ParameterValue::Integer(_) => ParameterType::PARAMETER_INTEGER,
+            ParameterValue::Double(_) => ParameterType::PARAMETER_DOUBLE,
+            ParameterValue::String(_) => ParameterType::PARAMETER_STRING,
+            ParameterValue::ByteArray(_) => ParameterType::PARAMETER_BYTE_ARRAY,
+            ParameterValue::BoolArray(_) => ParameterType::PARAMETER_BOOL_ARRAY,
+            ParameterValue::IntegerArray(_) => ParameterType::PARAMETER_INTEGER_ARRAY,
+            ParameterValue::DoubleArray(_) => ParameterType::PARAMETER_DOUBLE_ARRAY,
+            ParameterValue::StringArray(_) => ParameterType::PARAMETER_STRING_ARRAY,
+        }
+    }
+
+    /// Converts an `rcl_interfaces/msg/ParameterValue` message into a value.
+    ///
+    /// Only the field that matches the `type_` of the message is used. Returns `None` if the type
+    /// is `PARAMETER_NOT_SET` or unknown.
+    pub fn from_msg(msg: ParameterValueMsg) -> Option<Self> {
+        let value = match msg.type_ {
+            ParameterType::PARAMETER_BOOL => ParameterValue::Bool(msg.bool_value),
+            ParameterType::PARAMETER_INTEGER => ParameterValue::Integer(msg.integer_value),
+            ParameterType::PARAMETER_DOUBLE => ParameterValue::Double(msg.double_value),
+            ParameterType::PARAMETER_STRING => ParameterValue::String(msg.string_value),
+            ParameterType::PARAMETER_BYTE_ARRAY => ParameterValue::ByteArray(msg.byte_array_value),
+            ParameterType::PARAMETER_BOOL_ARRAY => ParameterValue::BoolArray(msg.bool_array_value),
+            ParameterType::PARAMETER_INTEGER_ARRAY => {
+                ParameterValue::IntegerArray(msg.integer_array_value)
+            }
+            ParameterType::PARAMETER_DOUBLE_ARRAY => {
+                ParameterValue::DoubleArray(msg.double_array_value)
+            }
+            ParameterType::PARAMETER_STRING_ARRAY => {
+                ParameterValue::StringArray(msg.string_array_value)
+            }
+            ParameterType::PARAMETER_NOT_SET => return None,
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    /// Converts this value into an `rcl_interfaces/msg/ParameterValue` message.
+    ///
+    /// The `type_` and the matching field of the message are set, all other fields keep their
+    /// default values.
+    pub fn to_msg(&self) -> ParameterValueMsg {
+        let mut msg = ParameterValueMsg {
+            type_: self.type_tag(),
+            ..Default::default()
+        };
+        match self {
+            ParameterValue::Bool(value) => msg.bool_value = *value,
+            ParameterValue::Integer(value) => msg.integer_value = *value,
+            ParameterValue::Double(value) => msg.double_value = *value,
+            ParameterValue::String(value) => msg.string_value = value.clone(),
+            ParameterValue::ByteArray(value) => msg.byte_array_value = value.clone(),
+            ParameterValue::BoolArray(value) => msg.bool_array_value = value.clone(),
+            ParameterValue::IntegerArray(value) => msg.integer_array_value = value.clone(),
+            ParameterValue::DoubleArray(value) => msg.double_array_value = value.clone(),
+            ParameterValue::StringArray(value) => msg.string_array_value = value.clone(),
+        }
+        msg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_msg() {
+        let values = [
+            ParameterValue::Bool(true),
+            ParameterValue::Integer(-42),
+            ParameterValue::Double(1.5),
+            ParameterValue::String(String::from("value")),
+            ParameterValue::ByteArray(vec![0, 255]),
+            ParameterValue::BoolArray(vec![true, false]),
+            ParameterValue::IntegerArray(vec![1, i64::MAX]),
+            ParameterValue::DoubleArray(vec![-0.5, 2.0]),
+            ParameterValue::StringArray(vec![String::from("a"), String::new()]),
+        ];
+        for (i, value) in values.into_iter().enumerate() {
+            let msg = value.to_msg();
+            // The type tags are numbered in the order of the variants
+            assert_eq!(msg.type_, i as u8 + 1);
+            assert_eq!(ParameterValue::from_msg(msg), Some(value));
+        }
+    }
+
+    #[test]
+    fn unset_value_is_not_converted() {
+        let msg = ParameterValueMsg {
+            type_: ParameterType::PARAMETER_NOT_SET,
+            integer_value: 1,
+            ..Default::default()
+        };
+        assert_eq!(ParameterValue::from_msg(msg), None);
+        let msg = ParameterValueMsg {
+            type_: 10,
+            ..Default::default()
+        };
+        assert_eq!(ParameterValue::from_msg(msg), None);
+    }
+
+    #[test]
+    fn other_fields_are_ignored() {
+        let msg = ParameterValueMsg {
+            type_: ParameterType::PARAMETER_BOOL,
+            bool_value: true,
+            integer_value: 5,
+            string_value: String::from("ignored"),
+            ..Default::default()
+        };
+        assert_eq!(
+            ParameterValue::from_msg(msg),
+            Some(ParameterValue::Bool(true))
+        );
+        let msg = ParameterValue::Integer(5).to_msg();
+        assert!(!msg.bool_value);
+        assert!(msg.string_value.is_empty());
+    }
+}
@@ -179,6 +179,15 @@ pub struct QoSProfile {
     pub avoid_ros_namespace_conventions: bool,
 }
 
+impl QoSProfile {
+    // Whether a future intra-process path could deliver messages with this profile, using the
+    // same rules as rclcpp: a volatile durability, and a bounded, non-empty history.
+    pub(crate) fn is_intra_process_compatible(&self) -> bool {
+        matches!(self.durability, QoSDurabilityPolicy::Volatile)
+            && matches!(self.history, QoSHistoryPolicy::KeepLast { depth } if depth > 0)
+    }
+}
+
 impl From<QoSProfile> for rmw_qos_profile_t {
     fn from(qos: QoSProfile) -> Self {
         Self {
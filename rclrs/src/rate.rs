@@ -0,0 +1,126 @@
+use crate::{Clock, ClockType, RclrsError, Time};
+
+use std::time::Duration;
+
+/// A helper for running a loop at a fixed rate.
+///
+/// This is the equivalent of `rclcpp::Rate`. It uses a steady clock, so it is not affected by
+/// changes to the system time.
+///
+/// # Example
+/// ```
+/// # use rclrs::{Rate, RclrsError};
+/// let mut rate = Rate::new(100.0)?;
+/// for _ in 0..3 {
+///     // Do some work here
+///     rate.sleep();
+/// }
+/// # Ok::<(), RclrsError>(())
+/// ```
+pub struct Rate {
+    clock: Clock,
+    period: Duration,
+    last: Time,
+}
+
+impl Rate {
+    /// Creates a rate with the given frequency in Hz.
+    ///
+    /// The first period starts now.
+    ///
+    /// # Panics
+    /// When the frequency is not a positive, finite number.
+    pub fn new(frequency: f64) -> Result<Self, RclrsError> {
+        assert!(
+            frequency.is_finite() && frequency > 0.0,
+            "Invalid rate frequency {}",
+            frequency
+        );
+        Self::from_period(Duration::from_secs_f64(1.0 / frequency))
+    }
+
+    /// Creates a rate with the given period.
+    ///
+    /// The first period starts now.
+    pub fn from_period(period: Duration) -> Result<Self, RclrsError> {
+        let clock = Clock::new(ClockType::SteadyTime)?;
+        let last = clock.now();
+        Ok(Self {
+            clock,
+            period,
+            last,
+        })
+    }
+
+    /// Returns the period of the rate.
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    /// Sleeps for the remainder of the current period.
+    ///
+    /// Returns `false` when the period was already over, i.e. the loop is running too slowly. In
+    /// that case, this does not sleep, and the next period starts now instead of catching up.
+    pub fn sleep(&mut self) -> bool {
+        let period_nsec = i64::try_from(self.period.as_nanos()).unwrap_or(i64::MAX);
+        let target = Time {
+            nsec: self.last.nsec.saturating_add(period_nsec),
+            clock_type: self.last.clock_type,
+        };
+        let now = self.clock.now();
+        match target.duration_since(now) {
+            Some(remaining) => {
+                std::thread::sleep(remaining);
+                self.last = target;
+                true
+            }
+            None => {
+                self.last = now;
+                false
+            }
+        }
+    }
+
+    /// Starts a new period now.
+    pub fn reset(&mut self) {
+        self.last = self.clock.now();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[test]
+    fn sleeps_for_the_period() -> Result<(), RclrsError> {
+        let start = Instant::now();
+        let mut rate = Rate::new(50.0)?;
+        assert_eq!(rate.period(), Duration::from_millis(20));
+        let first = rate.last.nsec;
+        let mut overran = false;
+        for _ in 0..5 {
+            overran |= !rate.sleep();
+        }
+        // Each period starts at least one period after the previous one, even when the thread
+        // is woken up late.
+        assert!(start.elapsed() >= Duration::from_millis(100));
+        // Without overruns, which can happen on a loaded machine, the periods follow each other
+        // exactly, i.e. waking up late does not accumulate.
+        if !overran {
+            assert_eq!(rate.last.nsec - first, 100_000_000);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn reports_overrun() -> Result<(), RclrsError> {
+        let mut rate = Rate::new(100.0)?;
+        std::thread::sleep(Duration::from_millis(20));
+        let before_sleep = rate.clock.now();
+        assert!(!rate.sleep());
+        // The next period starts now instead of catching up
+        assert!(rate.last.nsec >= before_sleep.nsec);
+        Ok(())
+    }
+}
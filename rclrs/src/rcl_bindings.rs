@@ -3,5 +3,6 @@
 #![allow(non_camel_case_types)]
 #![allow(non_snake_case)]
 #![allow(clippy::all)]
+#![allow(missing_docs)]
 
 include!(concat!(env!("OUT_DIR"), "/rcl_bindings_generated.rs"));
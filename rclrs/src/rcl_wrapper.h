@@ -1,2 +1,9 @@
+#include <rcl/event.h>
+#include <rcl/logging.h>
 #include <rcl/rcl.h>
-#include <rcutils/error_handling.h>
\ No newline at end of file
+#include <rcl/validate_topic_name.h>
+#include <rcutils/error_handling.h>
+#include <rcutils/logging.h>
+#include <rmw/rmw.h>
+#include <rmw/validate_full_topic_name.h>
+#include <rosidl_typesupport_introspection_c/message_introspection.h>
@@ -0,0 +1,135 @@
+use crate::allocator::default_allocator;
+use crate::error::{RclrsError, ToResult};
+use crate::rcl_bindings::*;
+
+use std::borrow::Cow;
+
+use rosidl_runtime_rs::{Message, RmwMessage};
+
+/// Conversion of messages to and from their serialized representation.
+///
+/// The messages are serialized by the RMW implementation, in the format returned by
+/// [`Context::rmw_serialization_format()`][1]. For all DDS-based RMW implementations, this is
+/// CDR, i.e. the same bytes that are sent over the wire. This is useful e.g. for recording
+/// messages to a file.
+///
+/// This trait is implemented for all message types, both idiomatic and RMW-native. Both
+/// representations of a message serialize to the same bytes.
+///
+/// # Example
+/// ```
+/// # use rclrs::{MessageSerialization, RclrsError};
+/// let msg = test_msgs::msg::UnboundedSequences {
+///     int32_values: vec![1, 2, 3],
+///     ..Default::default()
+/// };
+/// let bytes = msg.serialize_cdr()?;
+/// let deserialized = test_msgs::msg::UnboundedSequences::deserialize_cdr(&bytes)?;
+/// assert_eq!(deserialized.int32_values, [1, 2, 3]);
+/// # Ok::<(), RclrsError>(())
+/// ```
+///
+/// [1]: crate::Context::rmw_serialization_format
+pub trait MessageSerialization: Message {
+    /// Serializes the message.
+    fn serialize_cdr(&self) -> Result<Vec<u8>, RclrsError>;
+
+    /// Deserializes a message from bytes created by [`serialize_cdr()`][1].
+    ///
+    /// Returns an error if the bytes are not a serialized message of this type.
+    ///
+    /// [1]: MessageSerialization::serialize_cdr
+    fn deserialize_cdr(bytes: &[u8]) -> Result<Self, RclrsError>;
+}
+
+impl<T: Message> MessageSerialization for T {
+    fn serialize_cdr(&self) -> Result<Vec<u8>, RclrsError> {
+        serialize_rmw_message(T::into_rmw_message(Cow::Borrowed(self)).as_ref())
+    }
+
+    fn deserialize_cdr(bytes: &[u8]) -> Result<Self, RclrsError> {
+        let type_support =
+            <T as Message>::RmwMsg::get_type_support() as *const rosidl_message_type_support_t;
+        // The serialized message borrows the bytes. It must not be finalized, since it does not
+        // own the buffer.
+        let serialized_message = rcutils_uint8_array_t {
+            buffer: bytes.as_ptr() as *mut u8,
+            buffer_length: bytes.len(),
+            buffer_capacity: bytes.len(),
+            allocator: default_allocator(),
+        };
+        let mut rmw_message = <T as Message>::RmwMsg::default();
+        // SAFETY: The serialized message is only read by this function, so it is fine that its
+        // buffer points to the borrowed bytes. The message matches the type support, and its
+        // sequences are compatible with the ones allocated by the type support.
+        unsafe {
+            rmw_deserialize(
+                &serialized_message,
+                type_support,
+                &mut rmw_message as *mut <T as Message>::RmwMsg as *mut _,
+            )
+            .ok_with_context("rmw_deserialize")?;
+        }
+        Ok(T::from_rmw_message(rmw_message))
+    }
+}
+
+// Serializes an RMW-native message. Also used by Subscription::take_both().
+pub(crate) fn serialize_rmw_message<M: RmwMessage>(rmw_message: &M) -> Result<Vec<u8>, RclrsError> {
+    let type_support = M::get_type_support() as *const rosidl_message_type_support_t;
+    // SAFETY: Getting a zero-initialized value is always safe
+    let mut serialized_message = unsafe { rcutils_get_zero_initialized_uint8_array() };
+    let allocator = default_allocator();
+    // SAFETY: The serialized message is zero-initialized as expected by this function.
+    // The allocator is copied. The buffer is only allocated when it is needed.
+    unsafe { rcutils_uint8_array_init(&mut serialized_message, 0, &allocator).ok()? };
+    // SAFETY: The message matches the type support. The serialized message is initialized,
+    // and its buffer is grown by this function as needed.
+    let ret = unsafe {
+        rmw_serialize(
+            rmw_message as *const M as *const _,
+            type_support,
+            &mut serialized_message,
+        )
+        .ok_with_context("rmw_serialize")
+    };
+    let bytes = ret.map(|()| {
+        if serialized_message.buffer_length == 0 {
+            return Vec::new();
+        }
+        // SAFETY: After a successful serialization, the buffer holds buffer_length bytes.
+        // They are copied before the buffer is freed.
+        unsafe {
+            std::slice::from_raw_parts(serialized_message.buffer, serialized_message.buffer_length)
+        }
+        .to_vec()
+    });
+    // SAFETY: The serialized message is initialized and not used anymore.
+    unsafe { rcutils_uint8_array_fini(&mut serialized_message).ok()? };
+    // Move the check after the fini()
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_with_sequences_round_trip() -> Result<(), RclrsError> {
+        let msg = test_msgs::msg::UnboundedSequences {
+            int32_values: vec![1, -2, 3],
+            string_values: vec![String::from("Hello"), String::from("World")],
+            ..Default::default()
+        };
+        let bytes = msg.serialize_cdr()?;
+        let deserialized = test_msgs::msg::UnboundedSequences::deserialize_cdr(&bytes)?;
+        assert_eq!(deserialized, msg);
+
+        // The RMW-native message has the same serialized representation
+        let rmw_msg = test_msgs::msg::UnboundedSequences::into_rmw_message(Cow::Borrowed(&msg));
+        assert_eq!(rmw_msg.serialize_cdr()?, bytes);
+        let rmw_deserialized = test_msgs::msg::rmw::UnboundedSequences::deserialize_cdr(&bytes)?;
+        assert_eq!(&rmw_deserialized, rmw_msg.as_ref());
+        Ok(())
+    }
+}
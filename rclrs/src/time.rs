@@ -0,0 +1,323 @@
+use crate::ClockType;
+
+use std::cmp::Ordering;
+use std::time::Duration;
+
+use builtin_interfaces::msg::rmw::Time as TimeMsg;
+use rosidl_runtime_rs::Sequence;
+
+const NSEC_PER_SEC: i64 = 1_000_000_000;
+
+/// A point in time, as reported by a [`Clock`][1].
+///
+/// Times are only comparable when they come from the same type of clock.
+///
+/// [1]: crate::Clock
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Time {
+    /// Nanoseconds since the epoch of the clock.
+    ///
+    /// For ROS time and system time, this is the Unix epoch.
+    pub nsec: i64,
+    /// The type of the clock that this time was obtained from.
+    pub clock_type: ClockType,
+}
+
+impl PartialOrd for Time {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.clock_type != other.clock_type {
+            return None;
+        }
+        Some(self.nsec.cmp(&other.nsec))
+    }
+}
+
+impl Time {
+    /// Returns the time elapsed since `earlier`, or `None` if `earlier` is later than this time
+    /// or is from a different type of clock.
+    pub fn duration_since(&self, earlier: Time) -> Option<Duration> {
+        if self.clock_type != earlier.clock_type {
+            return None;
+        }
+        let nsec = self.nsec.checked_sub(earlier.nsec)?;
+        u64::try_from(nsec).ok().map(Duration::from_nanos)
+    }
+
+    /// Converts a `builtin_interfaces/msg/Time` message into a time of the given clock type.
+    pub fn from_msg(msg: &TimeMsg, clock_type: ClockType) -> Self {
+        Self {
+            nsec: i64::from(msg.sec) * NSEC_PER_SEC + i64::from(msg.nanosec),
+            clock_type,
+        }
+    }
+
+    /// Converts this time into a `builtin_interfaces/msg/Time` message.
+    ///
+    /// Times before the epoch are represented with a negative `sec` and a positive `nanosec`, as
+    /// in `rclcpp`. Times that do not fit into the message saturate to the latest or earliest
+    /// time it can represent.
+    pub fn to_msg(&self) -> TimeMsg {
+        let sec = self.nsec.div_euclid(NSEC_PER_SEC);
+        let nanosec = self.nsec.rem_euclid(NSEC_PER_SEC) as u32;
+        match i32::try_from(sec) {
+            Ok(sec) => TimeMsg { sec, nanosec },
+            Err(_) if sec > 0 => TimeMsg {
+                sec: i32::MAX,
+                nanosec: (NSEC_PER_SEC - 1) as u32,
+            },
+            Err(_) => TimeMsg {
+                sec: i32::MIN,
+                nanosec: 0,
+            },
+        }
+    }
+
+    /// Converts a `builtin_interfaces/msg/Time` message into a time of the given clock type,
+    /// handling times before the epoch according to `policy`.
+    ///
+    /// A `nanosec` of a second or more is carried over into the seconds. Returns `None` only for
+    /// [`NegativeTimePolicy::Reject`].
+    ///
+    /// # Example
+    /// ```
+    /// # use rclrs::{ClockType, NegativeTimePolicy, Time};
+    /// # use builtin_interfaces::msg::rmw::Time as TimeMsg;
+    /// let msg = TimeMsg { sec: -1, nanosec: 500_000_000 };
+    /// let time = Time::from_msg_with_policy(&msg, ClockType::RosTime, NegativeTimePolicy::Clamp);
+    /// assert_eq!(time.unwrap().nsec, 0);
+    /// let time = Time::from_msg_with_policy(&msg, ClockType::RosTime, NegativeTimePolicy::Reject);
+    /// assert_eq!(time, None);
+    /// ```
+    pub fn from_msg_with_policy(
+        msg: &TimeMsg,
+        clock_type: ClockType,
+        policy: NegativeTimePolicy,
+    ) -> Option<Self> {
+        Self::from_msg(msg, clock_type).with_policy(policy)
+    }
+
+    /// Converts this time into a `builtin_interfaces/msg/Time` message, handling times before the
+    /// epoch according to `policy`.
+    ///
+    /// The `nanosec` of the message is always in `[0, 1e9)`. Returns `None` only for
+    /// [`NegativeTimePolicy::Reject`], which also rejects times whose seconds do not fit into the
+    /// message instead of saturating them.
+    ///
+    /// # Example
+    /// ```
+    /// # use rclrs::{ClockType, NegativeTimePolicy, Time};
+    /// let earlier = Time { nsec: 1_000, clock_type: ClockType::RosTime };
+    /// let later = Time { nsec: 1_500, clock_type: ClockType::RosTime };
+    /// // A difference that turned out negative
+    /// let difference = Time { nsec: earlier.nsec - later.nsec, ..earlier };
+    /// let msg = difference.to_msg_with_policy(NegativeTimePolicy::Clamp).unwrap();
+    /// assert_eq!((msg.sec, msg.nanosec), (0, 0));
+    /// assert!(difference.to_msg_with_policy(NegativeTimePolicy::Reject).is_none());
+    /// ```
+    pub fn to_msg_with_policy(&self, policy: NegativeTimePolicy) -> Option<TimeMsg> {
+        let time = self.with_policy(policy)?;
+        if policy == NegativeTimePolicy::Reject {
+            i32::try_from(time.nsec / NSEC_PER_SEC).ok()?;
+        }
+        Some(time.to_msg())
+    }
+
+    fn with_policy(self, policy: NegativeTimePolicy) -> Option<Self> {
+        match policy {
+            NegativeTimePolicy::Keep => Some(self),
+            NegativeTimePolicy::Clamp => Some(Self {
+                nsec: self.nsec.max(0),
+                ..self
+            }),
+            NegativeTimePolicy::Reject if self.nsec < 0 => None,
+            NegativeTimePolicy::Reject => Some(self),
+        }
+    }
+}
+
+/// How times before the epoch are converted to and from `builtin_interfaces/msg/Time` messages.
+///
+/// Such times often come from subtracting times, e.g. when a time stamp lies slightly in the
+/// future. See [`Time::to_msg_with_policy()`] and [`Time::from_msg_with_policy()`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NegativeTimePolicy {
+    /// Keep negative times, with a negative `sec` and a positive `nanosec` in the message, as in
+    /// `rclcpp`. This is what [`Time::to_msg()`] and [`Time::from_msg()`] do.
+    Keep,
+    /// Clamp negative times to the epoch.
+    Clamp,
+    /// Fail the conversion of negative times.
+    Reject,
+}
+
+impl Default for NegativeTimePolicy {
+    fn default() -> Self {
+        Self::Keep
+    }
+}
+
+/// Converts a sequence of time messages, such as the time stamps of a trajectory, into times of
+/// the given clock type.
+///
+/// # Example
+/// ```
+/// # use rclrs::{times_from_msgs, times_to_msgs, ClockType, Time};
+/// let times = [0, 1_500_000_000].map(|nsec| Time {
+///     nsec,
+///     clock_type: ClockType::RosTime,
+/// });
+/// let msgs = times_to_msgs(&times);
+/// assert_eq!((msgs[1].sec, msgs[1].nanosec), (1, 500_000_000));
+/// assert_eq!(times_from_msgs(&msgs, ClockType::RosTime), times);
+/// ```
+pub fn times_from_msgs(msgs: &[TimeMsg], clock_type: ClockType) -> Vec<Time> {
+    msgs.iter()
+        .map(|msg| Time::from_msg(msg, clock_type))
+        .collect()
+}
+
+/// Converts times into a sequence of time messages.
+///
+/// See [`Time::to_msg()`] and [`times_from_msgs()`].
+pub fn times_to_msgs(times: &[Time]) -> Sequence<TimeMsg> {
+    times.iter().map(Time::to_msg).collect()
+}
+
+/// Converts a sequence of time messages, which are relative to some point in time such as the
+/// start of a trajectory, into durations.
+///
+/// Returns `None` if any of the messages is negative, since a [`Duration`] can not be negative.
+///
+/// # Example
+/// ```
+/// # use rclrs::{durations_from_msgs, durations_to_msgs};
+/// # use std::time::Duration;
+/// let durations = [Duration::ZERO, Duration::from_millis(2500)];
+/// let msgs = durations_to_msgs(&durations);
+/// assert_eq!((msgs[1].sec, msgs[1].nanosec), (2, 500_000_000));
+/// assert_eq!(durations_from_msgs(&msgs).unwrap(), durations);
+/// ```
+pub fn durations_from_msgs(msgs: &[TimeMsg]) -> Option<Vec<Duration>> {
+    msgs.iter()
+        .map(|msg| {
+            let sec = u64::try_from(msg.sec).ok()?;
+            Some(Duration::from_secs(sec) + Duration::from_nanos(msg.nanosec.into()))
+        })
+        .collect()
+}
+
+/// Converts durations into a sequence of time messages.
+///
+/// The seconds saturate at the limits of `i32`.
+pub fn durations_to_msgs(durations: &[Duration]) -> Sequence<TimeMsg> {
+    durations
+        .iter()
+        .map(|duration| TimeMsg {
+            sec: i32::try_from(duration.as_secs()).unwrap_or(i32::MAX),
+            nanosec: duration.subsec_nanos(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn durations_round_trip_through_msgs() {
+        let durations = vec![
+            Duration::ZERO,
+            Duration::from_nanos(1),
+            Duration::from_millis(999),
+            Duration::new(12, 345_678_901),
+        ];
+        let msgs = durations_to_msgs(&durations);
+        assert_eq!(msgs.len(), durations.len());
+        assert_eq!((msgs[3].sec, msgs[3].nanosec), (12, 345_678_901));
+        assert_eq!(durations_from_msgs(&msgs), Some(durations));
+        let negative = TimeMsg {
+            sec: -1,
+            nanosec: 0,
+        };
+        assert_eq!(durations_from_msgs(&[negative]), None);
+    }
+
+    #[test]
+    fn times_round_trip_through_msgs() {
+        let times = [-1, 0, 1_999_999_999].map(|nsec| Time {
+            nsec,
+            clock_type: ClockType::SystemTime,
+        });
+        let msgs = times_to_msgs(&times);
+        assert_eq!((msgs[0].sec, msgs[0].nanosec), (-1, 999_999_999));
+        assert_eq!(times_from_msgs(&msgs, ClockType::SystemTime), times);
+    }
+
+    #[test]
+    fn time_to_msg_saturates() {
+        let to_msg = |nsec| {
+            let msg = Time {
+                nsec,
+                clock_type: ClockType::SystemTime,
+            }
+            .to_msg();
+            (msg.sec, msg.nanosec)
+        };
+        assert_eq!(to_msg(i64::MAX), (i32::MAX, 999_999_999));
+        assert_eq!(to_msg(i64::MIN), (i32::MIN, 0));
+        let min = i64::from(i32::MIN) * NSEC_PER_SEC;
+        assert_eq!(to_msg(min), (i32::MIN, 0));
+        assert_eq!(to_msg(min - 1), (i32::MIN, 0));
+    }
+
+    #[test]
+    fn negative_time_policies() {
+        use NegativeTimePolicy::*;
+        let time = |nsec| Time {
+            nsec,
+            clock_type: ClockType::RosTime,
+        };
+        let msg = |sec, nanosec| TimeMsg { sec, nanosec };
+
+        // Exactly zero is not negative
+        for policy in [Keep, Clamp, Reject] {
+            assert_eq!(time(0).to_msg_with_policy(policy), Some(msg(0, 0)));
+            let from_msg = Time::from_msg_with_policy(&msg(0, 0), ClockType::RosTime, policy);
+            assert_eq!(from_msg, Some(time(0)));
+        }
+
+        // Less than a second before the epoch
+        let sub_second = time(-1);
+        assert_eq!(
+            sub_second.to_msg_with_policy(Keep),
+            Some(msg(-1, 999_999_999))
+        );
+        assert_eq!(sub_second.to_msg_with_policy(Clamp), Some(msg(0, 0)));
+        assert_eq!(sub_second.to_msg_with_policy(Reject), None);
+        let sub_second_msg = msg(-1, 999_999_999);
+        let from_msg =
+            |policy| Time::from_msg_with_policy(&sub_second_msg, ClockType::RosTime, policy);
+        assert_eq!(from_msg(Keep), Some(sub_second));
+        assert_eq!(from_msg(Clamp), Some(time(0)));
+        assert_eq!(from_msg(Reject), None);
+
+        // Large positive times
+        let max = time(i64::from(i32::MAX) * NSEC_PER_SEC + 999_999_999);
+        for policy in [Keep, Clamp, Reject] {
+            assert_eq!(
+                max.to_msg_with_policy(policy),
+                Some(msg(i32::MAX, 999_999_999))
+            );
+        }
+        let too_large = time(i64::MAX);
+        assert_eq!(
+            too_large.to_msg_with_policy(Clamp),
+            Some(msg(i32::MAX, 999_999_999))
+        );
+        assert_eq!(too_large.to_msg_with_policy(Reject), None);
+        // The nanoseconds of the message are carried over
+        let from_msg =
+            Time::from_msg_with_policy(&msg(1, 2_500_000_000), ClockType::RosTime, Reject);
+        assert_eq!(from_msg, Some(time(3_500_000_000)));
+    }
+}
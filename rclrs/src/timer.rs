@@ -0,0 +1,267 @@
+use crate::allocator::context_allocator;
+use crate::error::{RclReturnCode, TimerErrorCode};
+use crate::rcl_bindings::*;
+use crate::{CallbackGroup, Clock, Context, ContextHandle, RclrsError, ToResult};
+
+use std::boxed::Box;
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+
+// SAFETY: The functions accessing this type, including drop(), shouldn't care about the thread
+// they are running in. Therefore, this type can be safely sent to another thread.
+unsafe impl Send for rcl_timer_t {}
+
+/// A waitable entity that becomes ready periodically, or once after a delay.
+///
+/// Timers are measured with a [`Clock`]. When the clock is a ROS clock with a time override,
+/// such as simulation time, the timer follows the overridden time.
+///
+/// The callback is run by [`spin_once`][1] when the timer is ready, if the timer was created
+/// through [`Node::create_timer`][2] or [`Node::create_one_shot_timer`][3].
+///
+/// # Example
+/// ```
+/// # use rclrs::{Context, RclrsError};
+/// # use std::time::Duration;
+/// let context = Context::new([])?;
+/// let mut node = context.create_node("my_node")?;
+/// let timer = node.create_timer(Duration::from_millis(10), || println!("Tick"))?;
+/// // This returns once the timer has fired
+/// rclrs::spin_once(&node, None)?;
+/// timer.cancel()?;
+/// # Ok::<(), RclrsError>(())
+/// ```
+///
+/// [1]: crate::spin_once
+/// [2]: crate::Node::create_timer
+/// [3]: crate::Node::create_one_shot_timer
+pub struct Timer {
+    // rcl registers the address of the timer with the clock for ROS time, so it must not move.
+    pub(crate) handle: Box<Mutex<rcl_timer_t>>,
+    // The timer reads the time from the clock, so the clock must outlive it.
+    clock: Clock,
+    // Used to ensure the context is alive while the timer is alive.
+    _context_handle: Arc<ContextHandle>,
+    callback: Mutex<Box<dyn FnMut() + 'static + Send>>,
+    one_shot: bool,
+    // Set when the timer is created through a node, see CallbackGroup.
+    pub(crate) callback_group: Option<Arc<CallbackGroup>>,
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        // Finalizing the timer removes its jump callback from the clock, so the clock is locked.
+        let _rcl_clock = self.clock.rcl_clock.lock();
+        // SAFETY: No preconditions for this function (besides passing in a valid timer).
+        if let Err(e) = unsafe { rcl_timer_fini(&mut *self.handle.lock()).ok() } {
+            // Panicking in drop() would abort the program if it is already unwinding.
+            crate::log_error!("rclrs", "Failed to finalize timer: {}", e);
+        }
+    }
+}
+
+impl Timer {
+    /// Creates a timer that becomes ready every `period`, starting one period from now.
+    pub fn new<F>(
+        context: &Context,
+        clock: &Clock,
+        period: Duration,
+        callback: F,
+    ) -> Result<Self, RclrsError>
+    where
+        F: FnMut() + 'static + Send,
+    {
+        Self::new_with_mode(context, clock, period, Box::new(callback), false)
+    }
+
+    /// Creates a timer that becomes ready once, after `delay`.
+    ///
+    /// The timer is canceled when it is executed, so the callback runs only once. It can be
+    /// started again with [`Timer::reset()`].
+    pub fn new_one_shot<F>(
+        context: &Context,
+        clock: &Clock,
+        delay: Duration,
+        callback: F,
+    ) -> Result<Self, RclrsError>
+    where
+        F: FnMut() + 'static + Send,
+    {
+        Self::new_with_mode(context, clock, delay, Box::new(callback), true)
+    }
+
+    fn new_with_mode(
+        context: &Context,
+        clock: &Clock,
+        period: Duration,
+        callback: Box<dyn FnMut() + 'static + Send>,
+        one_shot: bool,
+    ) -> Result<Self, RclrsError> {
+        let period = i64::try_from(period.as_nanos()).map_err(|_| RclrsError {
+            code: RclReturnCode::InvalidArgument,
+            msg: None,
+        })?;
+        // SAFETY: Getting a zero-initialized value is always safe.
+        let handle = Box::new(Mutex::new(unsafe { rcl_get_zero_initialized_timer() }));
+        let context_handle = &mut *context.handle.rcl_context.lock();
+        let allocator = context_allocator(context_handle);
+        let rcl_clock = &mut *clock.rcl_clock.lock();
+        unsafe {
+            // SAFETY: The timer is zero-initialized as expected by this function, and is boxed so
+            // that it does not move. The clock and context are kept alive because they are
+            // co-owned by the timer. The callback is run by rclrs instead of rcl.
+            rcl_timer_init(
+                &mut *handle.lock(),
+                rcl_clock,
+                context_handle,
+                period,
+                None,
+                allocator,
+            )
+            .ok_with_context("rcl_timer_init")?;
+        }
+        Ok(Self {
+            handle,
+            clock: clock.clone(),
+            _context_handle: context.handle.clone(),
+            callback: Mutex::new(callback),
+            one_shot,
+            callback_group: None,
+        })
+    }
+
+    /// Returns the period of the timer, or the delay of a one-shot timer.
+    pub fn period(&self) -> Duration {
+        let mut period = 0;
+        // SAFETY: The timer is valid, and the period is a valid output argument.
+        unsafe { rcl_timer_get_period(&*self.handle.lock(), &mut period) };
+        Duration::from_nanos(period.max(0) as u64)
+    }
+
+    /// Returns `true` if this timer fires only once.
+    pub fn is_one_shot(&self) -> bool {
+        self.one_shot
+    }
+
+    /// Cancels the timer, so that it does not become ready anymore.
+    ///
+    /// The timer can be started again with [`Timer::reset()`].
+    pub fn cancel(&self) -> Result<(), RclrsError> {
+        // SAFETY: No preconditions for this function (besides passing in a valid timer).
+        unsafe { rcl_timer_cancel(&mut *self.handle.lock()) }.ok()
+    }
+
+    /// Returns `true` if the timer has been canceled, or if a one-shot timer has fired.
+    pub fn is_canceled(&self) -> Result<bool, RclrsError> {
+        let mut is_canceled = false;
+        // SAFETY: The timer is valid, and the flag is a valid output argument.
+        unsafe { rcl_timer_is_canceled(&*self.handle.lock(), &mut is_canceled).ok()? };
+        Ok(is_canceled)
+    }
+
+    /// Restarts the timer, so that the next period starts now.
+    ///
+    /// This also starts a canceled timer again.
+    pub fn reset(&self) -> Result<(), RclrsError> {
+        // SAFETY: No preconditions for this function (besides passing in a valid timer).
+        unsafe { rcl_timer_reset(&mut *self.handle.lock()) }.ok()
+    }
+
+    /// Runs the callback of this timer, if it is ready, and cancels it if it is a one-shot timer.
+    pub(crate) fn execute(&self) -> Result<(), RclrsError> {
+        {
+            let handle = &mut *self.handle.lock();
+            // SAFETY: No preconditions for this function (besides passing in a valid timer).
+            // This updates the time of the next call, and fails if the timer has been canceled in
+            // the meantime.
+            match unsafe { rcl_timer_call(handle) }.ok() {
+                Ok(()) => {}
+                Err(RclrsError {
+                    code: RclReturnCode::TimerError(TimerErrorCode::TimerCanceled),
+                    ..
+                }) => return Ok(()),
+                Err(e) => return Err(e),
+            }
+            if self.one_shot {
+                // Canceled before running the callback, so that the callback may reset the timer
+                // SAFETY: No preconditions for this function (besides passing in a valid timer).
+                unsafe { rcl_timer_cancel(handle) }.ok()?;
+            }
+        }
+        (*self.callback.lock())();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ClockType, RclrsErrorFilter};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Instant;
+
+    #[test]
+    fn periodic_timer_fires_repeatedly() -> Result<(), RclrsError> {
+        let context = Context::new([])?;
+        let mut node = context.create_node("timer_periodic_test_node")?;
+        let count = Arc::new(AtomicUsize::new(0));
+        let timer = node.create_timer(Duration::from_millis(10), {
+            let count = count.clone();
+            move || {
+                count.fetch_add(1, Ordering::Relaxed);
+            }
+        })?;
+        assert_eq!(timer.period(), Duration::from_millis(10));
+        assert!(!timer.is_one_shot());
+        let start = Instant::now();
+        while count.load(Ordering::Relaxed) < 3 {
+            assert!(start.elapsed() < Duration::from_secs(5));
+            crate::spin_once(&node, Some(Duration::from_millis(100)))?;
+        }
+        timer.cancel()?;
+        assert!(timer.is_canceled()?);
+        Ok(())
+    }
+
+    #[test]
+    fn one_shot_timer_fires_once() -> Result<(), RclrsError> {
+        let context = Context::new([])?;
+        let mut node = context.create_node("timer_one_shot_test_node")?;
+        let count = Arc::new(AtomicUsize::new(0));
+        let timer = node.create_one_shot_timer(Duration::from_millis(20), {
+            let count = count.clone();
+            move || {
+                count.fetch_add(1, Ordering::Relaxed);
+            }
+        })?;
+        assert!(timer.is_one_shot());
+        // Keep spinning well past the delay
+        let start = Instant::now();
+        while start.elapsed() < Duration::from_millis(200) {
+            crate::spin_once(&node, Some(Duration::from_millis(10))).timeout_ok()?;
+        }
+        assert_eq!(count.load(Ordering::Relaxed), 1);
+        assert!(timer.is_canceled()?);
+
+        // Resetting starts the timer again
+        timer.reset()?;
+        assert!(!timer.is_canceled()?);
+        while count.load(Ordering::Relaxed) < 2 {
+            assert!(start.elapsed() < Duration::from_secs(5));
+            crate::spin_once(&node, Some(Duration::from_millis(100)))?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn timer_with_steady_clock() -> Result<(), RclrsError> {
+        let context = Context::new([])?;
+        let clock = Clock::new(ClockType::SteadyTime)?;
+        let timer = Timer::new(&context, &clock, Duration::from_secs(1), || {})?;
+        assert_eq!(timer.period(), Duration::from_secs(1));
+        assert!(!timer.is_canceled()?);
+        Ok(())
+    }
+}
@@ -15,31 +15,157 @@
 // DISTRIBUTION A. Approved for public release; distribution unlimited.
 // OPSEC #4584.
 
+use crate::allocator::context_allocator;
 use crate::error::{to_rcl_result, RclReturnCode, RclrsError, ToResult};
 use crate::rcl_bindings::*;
-use crate::{Context, SubscriptionBase};
+use crate::{Context, ContextHandle, EventBase, Node, SubscriptionBase, Timer};
 
+use std::collections::VecDeque;
 use std::sync::Arc;
 use std::time::Duration;
 use std::vec::Vec;
 
 use parking_lot::Mutex;
 
+mod guard_condition;
+pub use guard_condition::*;
+
 /// A struct for waiting on subscriptions and other waitable entities to become ready.
+///
+/// A wait set can be reused for multiple calls to [`WaitSet::wait`]. The registered entities stay
+/// in the wait set until [`WaitSet::clear`] is called, so for a node whose set of entities does
+/// not change, the wait set only needs to be built once.
 pub struct WaitSet {
     handle: rcl_wait_set_t,
     // Used to ensure the context is alive while the wait set is alive.
-    _context_handle: Arc<Mutex<rcl_context_t>>,
+    _context_handle: Arc<ContextHandle>,
     // The subscriptions that are currently registered in the wait set.
     // This correspondence is an invariant that must be maintained by all functions,
     // even in the error case.
     subscriptions: Vec<Arc<dyn SubscriptionBase>>,
+    // The guard conditions that are currently registered in the wait set.
+    guard_conditions: Vec<Arc<GuardCondition>>,
+    // The timers that are currently registered in the wait set.
+    timers: Vec<Arc<Timer>>,
+    // The events that are currently registered in the wait set.
+    events: Vec<Arc<dyn EventBase>>,
+}
+
+/// The number of entities of each kind that can be added to a [`WaitSet`].
+///
+/// More kinds of entities may be added in the future, so this struct can not be constructed
+/// with a struct expression outside of rclrs. Instead, start from the default, which is zero for
+/// every kind, and change the fields.
+///
+/// # Example
+/// ```
+/// # use rclrs::{Context, RclrsError, WaitSet, WaitSetCapacities};
+/// let context = Context::new([])?;
+/// let mut capacities = WaitSetCapacities::default();
+/// capacities.subscriptions = 2;
+/// capacities.guard_conditions = 1;
+/// let wait_set = WaitSet::new(capacities, &context)?;
+/// # Ok::<(), RclrsError>(())
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct WaitSetCapacities {
+    /// How often [`WaitSet::add_subscription`] may be called.
+    pub subscriptions: usize,
+    /// How often [`WaitSet::add_guard_condition`] may be called.
+    pub guard_conditions: usize,
+    /// How often [`WaitSet::add_timer`] may be called.
+    pub timers: usize,
+    /// How often [`WaitSet::add_event`] may be called.
+    pub events: usize,
 }
 
 /// A list of entities that are ready, returned by [`WaitSet::wait`].
 pub struct ReadyEntities {
     /// A list of subscriptions that have potentially received messages.
     pub subscriptions: Vec<Arc<dyn SubscriptionBase>>,
+    /// A list of guard conditions that have been triggered.
+    pub guard_conditions: Vec<Arc<GuardCondition>>,
+    /// A list of timers that are ready to be called.
+    pub timers: Vec<Arc<Timer>>,
+    /// A list of events whose status has potentially changed.
+    pub events: Vec<Arc<dyn EventBase>>,
+}
+
+impl ReadyEntities {
+    // Executes the callbacks of all ready entities, as done when spinning.
+    pub(crate) fn execute(self) -> Result<(), RclrsError> {
+        for ready_subscription in self.subscriptions {
+            ready_subscription.execute()?;
+        }
+        for ready_guard_condition in self.guard_conditions {
+            ready_guard_condition.execute();
+        }
+        for ready_timer in self.timers {
+            ready_timer.execute()?;
+        }
+        for ready_event in self.events {
+            ready_event.execute()?;
+        }
+        Ok(())
+    }
+
+    // Like execute(), but runs the subscription and timer callbacks on up to `number_of_threads`
+    // threads, as allowed by their callback groups. The other callbacks run on this thread.
+    pub(crate) fn execute_in_parallel(self, number_of_threads: usize) -> Result<(), RclrsError> {
+        type Job = Box<dyn FnOnce() -> Result<(), RclrsError> + Send>;
+        let mut jobs: VecDeque<Job> = VecDeque::new();
+        for ready_subscription in self.subscriptions {
+            jobs.push_back(Box::new(move || {
+                let callback_group = ready_subscription.callback_group();
+                callback_group.run(|| ready_subscription.execute())
+            }));
+        }
+        for ready_timer in self.timers {
+            jobs.push_back(Box::new(move || match &ready_timer.callback_group {
+                Some(callback_group) => callback_group.run(|| ready_timer.execute()),
+                None => ready_timer.execute(),
+            }));
+        }
+        let workers: Vec<_> = {
+            let number_of_workers = number_of_threads.min(jobs.len());
+            let jobs = Arc::new(Mutex::new(jobs));
+            (0..number_of_workers)
+                .map(|_| {
+                    let jobs = jobs.clone();
+                    std::thread::spawn(move || -> Result<(), RclrsError> {
+                        loop {
+                            // The lock must be released before the job runs
+                            let job = jobs.lock().pop_front();
+                            match job {
+                                Some(job) => job()?,
+                                None => return Ok(()),
+                            }
+                        }
+                    })
+                })
+                .collect()
+        };
+
+        let mut result = Ok(());
+        for ready_guard_condition in self.guard_conditions {
+            ready_guard_condition.execute();
+        }
+        for ready_event in self.events {
+            if let Err(e) = ready_event.execute() {
+                result = Err(e);
+                break;
+            }
+        }
+        for worker in workers {
+            // A panic in a callback is passed on to the spinning thread
+            let worker_result = worker
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+            result = result.and(worker_result);
+        }
+        result
+    }
 }
 
 impl Drop for rcl_wait_set_t {
@@ -53,11 +179,10 @@ impl Drop for rcl_wait_set_t {
 }
 
 impl WaitSet {
-    /// Creates a new wait set.
-    ///
-    /// The given number of subscriptions is a capacity, corresponding to how often
-    /// [`WaitSet::add_subscription`] may be called.
-    pub fn new(number_of_subscriptions: usize, context: &Context) -> Result<Self, RclrsError> {
+    /// Creates a new wait set with the given capacities.
+    pub fn new(capacities: WaitSetCapacities, context: &Context) -> Result<Self, RclrsError> {
+        let context_handle = &mut *context.handle.rcl_context.lock();
+        let allocator = context_allocator(context_handle);
         let rcl_wait_set = unsafe {
             // SAFETY: Getting a zero-initialized value is always safe
             let mut rcl_wait_set = rcl_get_zero_initialized_wait_set();
@@ -65,31 +190,105 @@ impl WaitSet {
             // There are no other preconditions.
             rcl_wait_set_init(
                 &mut rcl_wait_set,
-                number_of_subscriptions,
-                0,
+                capacities.subscriptions,
+                capacities.guard_conditions,
+                capacities.timers,
                 0,
                 0,
-                0,
-                0,
-                &mut *context.handle.lock(),
-                rcutils_get_default_allocator(),
+                capacities.events,
+                context_handle,
+                allocator,
             )
-            .ok()?;
+            .ok_with_context("rcl_wait_set_init")?;
             rcl_wait_set
         };
         Ok(Self {
             handle: rcl_wait_set,
             _context_handle: context.handle.clone(),
             subscriptions: Vec::new(),
+            guard_conditions: Vec::new(),
+            timers: Vec::new(),
+            events: Vec::new(),
         })
     }
 
+    /// Creates a new wait set containing all live entities of the given node.
+    ///
+    /// The capacity of the wait set is exactly the number of these entities.
+    pub fn new_for_node(node: &Node) -> Result<Self, RclrsError> {
+        Self::new_for_nodes(&[node])
+    }
+
+    /// Creates a new wait set containing all live entities of the given nodes.
+    ///
+    /// The capacity of the wait set is exactly the number of these entities. This allows waiting
+    /// on several nodes at once, as done by the [`Executor`][1].
+    ///
+    /// All nodes must have been created from the same context. Otherwise, or if no nodes are
+    /// given, an error with the [`InvalidArgument`][2] code is returned.
+    ///
+    /// [1]: crate::Executor
+    /// [2]: crate::RclReturnCode::InvalidArgument
+    pub fn new_for_nodes(nodes: &[&Node]) -> Result<Self, RclrsError> {
+        let context_handle = match nodes.split_first() {
+            Some((first, rest))
+                if rest.iter().all(|node| {
+                    Arc::ptr_eq(&node.handle.context_handle, &first.handle.context_handle)
+                }) =>
+            {
+                first.handle.context_handle.clone()
+            }
+            _ => {
+                return Err(RclrsError {
+                    code: RclReturnCode::InvalidArgument,
+                    msg: None,
+                })
+            }
+        };
+        let live_subscriptions: Vec<_> = nodes
+            .iter()
+            .flat_map(|node| node.live_subscriptions())
+            .collect();
+        let live_guard_conditions: Vec<_> = nodes
+            .iter()
+            .flat_map(|node| node.live_guard_conditions())
+            .collect();
+        let live_timers: Vec<_> = nodes.iter().flat_map(|node| node.live_timers()).collect();
+        let live_events: Vec<_> = nodes.iter().flat_map(|node| node.live_events()).collect();
+        let ctx = Context {
+            handle: context_handle,
+        };
+        let capacities = WaitSetCapacities {
+            subscriptions: live_subscriptions.len(),
+            guard_conditions: live_guard_conditions.len(),
+            timers: live_timers.len(),
+            events: live_events.len(),
+        };
+        let mut wait_set = WaitSet::new(capacities, &ctx)?;
+        for live_subscription in live_subscriptions {
+            wait_set.add_subscription(live_subscription)?;
+        }
+        for live_guard_condition in live_guard_conditions {
+            wait_set.add_guard_condition(live_guard_condition)?;
+        }
+        for live_timer in live_timers {
+            wait_set.add_timer(live_timer)?;
+        }
+        for live_event in live_events {
+            wait_set.add_event(live_event)?;
+        }
+        Ok(wait_set)
+    }
+
     /// Removes all entities from the wait set.
     ///
     /// This effectively resets the wait set to the state it was in after being created by
     /// [`WaitSet::new`].
     pub fn clear(&mut self) {
         self.subscriptions.clear();
+        self.guard_conditions.clear();
+        self.timers.clear();
+        self.events.clear();
         // This cannot fail – the rcl_wait_set_clear function only checks that the input handle is
         // valid, which it always is in our case. Hence, only debug_assert instead of returning
         // Result.
@@ -110,19 +309,131 @@ impl WaitSet {
     pub fn add_subscription(
         &mut self,
         subscription: Arc<dyn SubscriptionBase>,
+    ) -> Result<(), RclrsError> {
+        Self::add_rcl_subscription(&mut self.handle, subscription.as_ref())?;
+        self.subscriptions.push(subscription);
+        Ok(())
+    }
+
+    // Helper for add_subscription() and repopulate()
+    fn add_rcl_subscription(
+        handle: &mut rcl_wait_set_t,
+        subscription: &dyn SubscriptionBase,
     ) -> Result<(), RclrsError> {
         unsafe {
             // SAFETY: I'm not sure if it's required, but the subscription pointer will remain valid
             // for as long as the wait set exists, because it's stored in self.subscriptions.
             // Passing in a null pointer for the third argument is explicitly allowed.
             rcl_wait_set_add_subscription(
-                &mut self.handle,
+                handle,
                 &*subscription.handle().lock(),
                 std::ptr::null_mut(),
             )
         }
-        .ok()?;
-        self.subscriptions.push(subscription);
+        .ok()
+    }
+
+    /// Adds a guard condition to the wait set.
+    ///
+    /// This will return an error if the number of guard conditions in the wait set is larger than
+    /// the capacity set in [`WaitSet::new`].
+    ///
+    /// The same guard condition must not be added to multiple wait sets, because that would make
+    /// it unsafe to simultaneously wait on those wait sets.
+    pub fn add_guard_condition(
+        &mut self,
+        guard_condition: Arc<GuardCondition>,
+    ) -> Result<(), RclrsError> {
+        Self::add_rcl_guard_condition(&mut self.handle, &guard_condition)?;
+        self.guard_conditions.push(guard_condition);
+        Ok(())
+    }
+
+    // Helper for add_guard_condition() and repopulate()
+    fn add_rcl_guard_condition(
+        handle: &mut rcl_wait_set_t,
+        guard_condition: &GuardCondition,
+    ) -> Result<(), RclrsError> {
+        unsafe {
+            // SAFETY: The guard condition pointer will remain valid for as long as the wait set
+            // exists, because it's stored in self.guard_conditions.
+            // Passing in a null pointer for the third argument is explicitly allowed.
+            rcl_wait_set_add_guard_condition(
+                handle,
+                &*guard_condition.handle.lock(),
+                std::ptr::null_mut(),
+            )
+        }
+        .ok()
+    }
+
+    /// Adds a timer to the wait set.
+    ///
+    /// This will return an error if the number of timers in the wait set is larger than the
+    /// capacity set in [`WaitSet::new`].
+    ///
+    /// The same timer must not be added to multiple wait sets, because that would make it unsafe
+    /// to simultaneously wait on those wait sets.
+    pub fn add_timer(&mut self, timer: Arc<Timer>) -> Result<(), RclrsError> {
+        Self::add_rcl_timer(&mut self.handle, &timer)?;
+        self.timers.push(timer);
+        Ok(())
+    }
+
+    // Helper for add_timer() and repopulate()
+    fn add_rcl_timer(handle: &mut rcl_wait_set_t, timer: &Timer) -> Result<(), RclrsError> {
+        unsafe {
+            // SAFETY: The timer pointer will remain valid for as long as the wait set exists,
+            // because it's stored in self.timers.
+            // Passing in a null pointer for the third argument is explicitly allowed.
+            rcl_wait_set_add_timer(handle, &*timer.handle.lock(), std::ptr::null_mut())
+        }
+        .ok()
+    }
+
+    /// Adds an event to the wait set.
+    ///
+    /// This will return an error if the number of events in the wait set is larger than the
+    /// capacity set in [`WaitSet::new`].
+    ///
+    /// The same event must not be added to multiple wait sets, because that would make it unsafe
+    /// to simultaneously wait on those wait sets.
+    pub fn add_event(&mut self, event: Arc<dyn EventBase>) -> Result<(), RclrsError> {
+        Self::add_rcl_event(&mut self.handle, event.as_ref())?;
+        self.events.push(event);
+        Ok(())
+    }
+
+    // Helper for add_event() and repopulate()
+    fn add_rcl_event(handle: &mut rcl_wait_set_t, event: &dyn EventBase) -> Result<(), RclrsError> {
+        unsafe {
+            // SAFETY: The event pointer will remain valid for as long as the wait set exists,
+            // because it's stored in self.events.
+            // Passing in a null pointer for the third argument is explicitly allowed.
+            rcl_wait_set_add_event(handle, &*event.handle().lock(), std::ptr::null_mut())
+        }
+        .ok()
+    }
+
+    /// Re-registers all entities with the `rcl` wait set.
+    ///
+    /// `rcl_wait()` sets the entries of entities that are not ready to null, so this needs to be
+    /// done before every wait for the wait set to be reusable.
+    fn repopulate(&mut self) -> Result<(), RclrsError> {
+        // SAFETY: No preconditions for this function (besides passing in a valid wait set).
+        unsafe { rcl_wait_set_clear(&mut self.handle) }.ok()?;
+        for subscription in &self.subscriptions {
+            Self::add_rcl_subscription(&mut self.handle, subscription.as_ref())?;
+        }
+        for guard_condition in &self.guard_conditions {
+            Self::add_rcl_guard_condition(&mut self.handle, guard_condition)?;
+        }
+        for timer in &self.timers {
+            Self::add_rcl_timer(&mut self.handle, timer)?;
+        }
+        for event in &self.events {
+            Self::add_rcl_event(&mut self.handle, event.as_ref())?;
+        }
         Ok(())
     }
 
@@ -138,7 +449,8 @@ impl WaitSet {
     /// that period of time has elapsed or the wait set becomes ready, which ever
     /// comes first.
     ///
-    /// This function does not change the entities registered in the wait set.
+    /// This function does not change the entities registered in the wait set, so it can be called
+    /// repeatedly on the same wait set.
     ///
     /// # Errors
     ///
@@ -160,6 +472,7 @@ impl WaitSet {
                 })
             }
         };
+        self.repopulate()?;
         // SAFETY: The comments in rcl mention "This function cannot operate on the same wait set
         // in multiple threads, and the wait sets may not share content."
         // We cannot currently guarantee that the wait sets may not share content, but it is
@@ -168,6 +481,9 @@ impl WaitSet {
         unsafe { rcl_wait(&mut self.handle, timeout_ns) }.ok()?;
         let mut ready_entities = ReadyEntities {
             subscriptions: Vec::new(),
+            guard_conditions: Vec::new(),
+            timers: Vec::new(),
+            events: Vec::new(),
         };
         for (i, subscription) in self.subscriptions.iter().enumerate() {
             // SAFETY: The `subscriptions` entry is an array of pointers, and this dereferencing is
@@ -178,6 +494,74 @@ impl WaitSet {
                 ready_entities.subscriptions.push(subscription.clone());
             }
         }
+        for (i, guard_condition) in self.guard_conditions.iter().enumerate() {
+            // SAFETY: The `guard_conditions` entry is an array of pointers, and this dereferencing
+            // is equivalent to
+            // https://github.com/ros2/rcl/blob/35a31b00a12f259d492bf53c0701003bd7f1745c/rcl/include/rcl/wait.h#L419
+            let wait_set_entry = unsafe { *self.handle.guard_conditions.add(i) };
+            if !wait_set_entry.is_null() {
+                ready_entities
+                    .guard_conditions
+                    .push(guard_condition.clone());
+            }
+        }
+        for (i, timer) in self.timers.iter().enumerate() {
+            // SAFETY: The `timers` entry is an array of pointers, and this dereferencing is
+            // equivalent to
+            // https://github.com/ros2/rcl/blob/35a31b00a12f259d492bf53c0701003bd7f1745c/rcl/include/rcl/wait.h#L419
+            let wait_set_entry = unsafe { *self.handle.timers.add(i) };
+            if !wait_set_entry.is_null() {
+                ready_entities.timers.push(timer.clone());
+            }
+        }
+        for (i, event) in self.events.iter().enumerate() {
+            // SAFETY: The `events` entry is an array of pointers, and this dereferencing is
+            // equivalent to
+            // https://github.com/ros2/rcl/blob/35a31b00a12f259d492bf53c0701003bd7f1745c/rcl/include/rcl/wait.h#L419
+            let wait_set_entry = unsafe { *self.handle.events.add(i) };
+            if !wait_set_entry.is_null() {
+                ready_entities.events.push(event.clone());
+            }
+        }
         Ok(ready_entities)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{QoSDurabilityPolicy, QoSProfile, QOS_PROFILE_DEFAULT};
+
+    #[test]
+    fn wait_set_can_be_reused() -> Result<(), RclrsError> {
+        let context = Context::new([])?;
+        let mut node = context.create_node("wait_set_test_node")?;
+        // Transient local durability makes sure the message is delivered even if the
+        // subscription is matched only after publishing.
+        let qos = QoSProfile {
+            durability: QoSDurabilityPolicy::TransientLocal,
+            ..QOS_PROFILE_DEFAULT
+        };
+        let publisher = node.create_publisher::<test_msgs::msg::Empty>("wait_set_test", qos)?;
+        let subscription =
+            node.create_subscription::<test_msgs::msg::Empty, _>("wait_set_test", qos, |_| {})?;
+        let mut wait_set = WaitSet::new_for_node(&node)?;
+
+        publisher.publish(test_msgs::msg::Empty::default())?;
+        let ready_entities = wait_set.wait(Some(Duration::from_secs(5)))?;
+        assert_eq!(ready_entities.subscriptions.len(), 1);
+        subscription.take()?;
+
+        // Nothing new has been published, so waiting on the same wait set times out
+        let error = wait_set
+            .wait(Some(Duration::from_millis(100)))
+            .err()
+            .unwrap();
+        assert_eq!(error.code, RclReturnCode::Timeout);
+
+        publisher.publish(test_msgs::msg::Empty::default())?;
+        let ready_entities = wait_set.wait(Some(Duration::from_secs(5)))?;
+        assert_eq!(ready_entities.subscriptions.len(), 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,144 @@
+use crate::allocator::context_allocator;
+use crate::rcl_bindings::*;
+use crate::{Context, ContextHandle, RclrsError, ToResult};
+
+use std::boxed::Box;
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+impl Drop for rcl_guard_condition_t {
+    fn drop(&mut self) {
+        // SAFETY: No preconditions for this function (besides passing in a valid guard condition).
+        if let Err(e) = unsafe { rcl_guard_condition_fini(self).ok() } {
+            // Panicking in drop() would abort the program if it is already unwinding.
+            crate::log_error!("rclrs", "Failed to finalize guard condition: {}", e);
+        }
+    }
+}
+
+// SAFETY: The functions accessing this type, including drop(), shouldn't care about the thread
+// they are running in. Therefore, this type can be safely sent to another thread.
+unsafe impl Send for rcl_guard_condition_t {}
+
+/// A waitable entity that is triggered manually.
+///
+/// A guard condition can be used to wake up a [`WaitSet`][1], and thereby also [`spin_once`][2],
+/// from another thread. This is the standard way to inject work into the executor, e.g. when a
+/// worker thread has produced data that should be published.
+///
+/// A guard condition that is triggered stays triggered until the wait set it is added to has
+/// been waited on.
+///
+/// # Example
+/// ```
+/// # use rclrs::{Context, RclrsError};
+/// # use std::time::Duration;
+/// let context = Context::new([])?;
+/// let mut node = context.create_node("my_node")?;
+/// let guard_condition = node.create_guard_condition()?;
+/// let worker = std::thread::spawn({
+///     let guard_condition = guard_condition.clone();
+///     move || guard_condition.trigger()
+/// });
+/// // This returns once the worker thread has triggered the guard condition
+/// rclrs::spin_once(&node, None)?;
+/// worker.join().unwrap()?;
+/// # Ok::<(), RclrsError>(())
+/// ```
+///
+/// [1]: crate::WaitSet
+/// [2]: crate::spin_once
+pub struct GuardCondition {
+    pub(crate) handle: Mutex<rcl_guard_condition_t>,
+    // Used to ensure the context is alive while the guard condition is alive.
+    _context_handle: Arc<ContextHandle>,
+    callback: Option<Mutex<Box<dyn FnMut() + 'static + Send>>>,
+}
+
+impl GuardCondition {
+    /// Creates a new guard condition without a callback.
+    pub fn new(context: &Context) -> Result<Self, RclrsError> {
+        Self::new_with_optional_callback(context, None)
+    }
+
+    /// Creates a new guard condition with a callback.
+    ///
+    /// The callback is run by [`spin_once`][1] when the guard condition was triggered, if the
+    /// guard condition was created through [`Node::create_guard_condition_with_callback`][2].
+    ///
+    /// [1]: crate::spin_once
+    /// [2]: crate::Node::create_guard_condition_with_callback
+    pub fn new_with_callback<F>(context: &Context, callback: F) -> Result<Self, RclrsError>
+    where
+        F: FnMut() + 'static + Send,
+    {
+        Self::new_with_optional_callback(context, Some(Box::new(callback)))
+    }
+
+    fn new_with_optional_callback(
+        context: &Context,
+        callback: Option<Box<dyn FnMut() + 'static + Send>>,
+    ) -> Result<Self, RclrsError> {
+        // SAFETY: Getting a zero-initialized value is always safe.
+        let mut guard_condition = unsafe { rcl_get_zero_initialized_guard_condition() };
+        let context_handle = &mut *context.handle.rcl_context.lock();
+        // SAFETY: No preconditions for this function.
+        let mut options = unsafe { rcl_guard_condition_get_default_options() };
+        options.allocator = context_allocator(context_handle);
+        unsafe {
+            // SAFETY: The guard condition is zero-initialized as expected by this function.
+            // The context is kept alive because it is co-owned by the guard condition.
+            rcl_guard_condition_init(&mut guard_condition, context_handle, options)
+                .ok_with_context("rcl_guard_condition_init")?;
+        }
+        Ok(Self {
+            handle: Mutex::new(guard_condition),
+            _context_handle: context.handle.clone(),
+            callback: callback.map(Mutex::new),
+        })
+    }
+
+    /// Triggers the guard condition, which wakes up any wait set it has been added to.
+    ///
+    /// This function can be called from any thread.
+    pub fn trigger(&self) -> Result<(), RclrsError> {
+        // SAFETY: No preconditions for this function (besides passing in a valid guard condition).
+        unsafe { rcl_trigger_guard_condition(&mut *self.handle.lock()) }.ok()
+    }
+
+    /// Runs the callback of this guard condition, if it has one.
+    pub(crate) fn execute(&self) {
+        if let Some(callback) = &self.callback {
+            (*callback.lock())();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::Duration;
+
+    #[test]
+    fn triggering_from_another_thread_wakes_up_spin() -> Result<(), RclrsError> {
+        let context = Context::new([])?;
+        let mut node = context.create_node("guard_condition_test_node")?;
+        let executed = Arc::new(AtomicBool::new(false));
+        let guard_condition = node.create_guard_condition_with_callback({
+            let executed = executed.clone();
+            move || executed.store(true, Ordering::Release)
+        })?;
+
+        let worker = std::thread::spawn({
+            let guard_condition = guard_condition.clone();
+            move || guard_condition.trigger()
+        });
+        crate::spin_once(&node, Some(Duration::from_secs(5)))?;
+        worker.join().unwrap()?;
+
+        assert!(executed.load(Ordering::Acquire));
+        Ok(())
+    }
+}
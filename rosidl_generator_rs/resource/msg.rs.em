@@ -1,4 +1,5 @@
 @{
+from rosidl_parser.definition import AbstractGenericString
 from rosidl_parser.definition import Array
 from rosidl_parser.definition import BasicType
 from rosidl_parser.definition import BoundedSequence
@@ -55,6 +56,18 @@ impl Default for @(type_name) {
   }
 }
 
+@[if msg_spec.constants]@
+impl @(type_name) {
+@[for constant in msg_spec.constants]@
+@[    if isinstance(constant.type, AbstractGenericString)]@
+  pub const @(get_rs_name(constant.name)): &'static str = @(constant_value_to_rs(constant.type, constant.value));
+@[    else]@
+  pub const @(get_rs_name(constant.name)): @(get_rmw_rs_type(constant.type)) = @(constant_value_to_rs(constant.type, constant.value));
+@[    end if]@
+@[end for]@
+}
+
+@[end if]@
 
 
 impl rosidl_runtime_rs::SequenceAlloc for @(type_name) {
@@ -112,6 +125,18 @@ impl Default for @(type_name) {
   }
 }
 
+@[if msg_spec.constants]@
+impl @(type_name) {
+@[for constant in msg_spec.constants]@
+@[    if isinstance(constant.type, AbstractGenericString)]@
+  pub const @(get_rs_name(constant.name)): &'static str = @(constant_value_to_rs(constant.type, constant.value));
+@[    else]@
+  pub const @(get_rs_name(constant.name)): @(get_rmw_rs_type(constant.type)) = @(constant_value_to_rs(constant.type, constant.value));
+@[    end if]@
+@[end for]@
+}
+
+@[end if]@
 impl rosidl_runtime_rs::Message for @(type_name) {
   type RmwMsg = crate::msg::rmw::@(type_name);
 
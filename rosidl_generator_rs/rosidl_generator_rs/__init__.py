@@ -12,6 +12,7 @@
 # See the License for the specific language governing permissions and
 # limitations under the License.
 
+import math
 import os
 import pathlib
 import subprocess
@@ -238,32 +239,32 @@ def primitive_value_to_rs(type_, value):
     assert False, "unknown primitive type '%s'" % type_
 
 
+# The largest finite value of an f32
+F32_MAX = 3.4028234663852886e+38
+
+
 def constant_value_to_rs(type_, value):
     assert value is not None
 
-    if type_ == 'bool':
-        return 'true' if value else 'false'
-
-    if type_ in [
-            'byte',
-            'char',
-            'int8',
-            'uint8',
-            'int16',
-            'uint16',
-            'int32',
-            'uint32',
-            'int64',
-            'uint64',
-            'float64',
-    ]:
+    if isinstance(type_, BasicType):
+        if type_.typename == 'boolean':
+            return 'true' if value else 'false'
+        if type_.typename in ['float', 'double']:
+            rs_type = 'f32' if type_.typename == 'float' else 'f64'
+            value = float(value)
+            if math.isnan(value):
+                return '%s::NAN' % rs_type
+            if math.isinf(value):
+                return '%s::%s' % (rs_type, 'INFINITY' if value > 0 else 'NEG_INFINITY')
+            if rs_type == 'f32' and abs(value) > F32_MAX:
+                raise ValueError(
+                    "constant value '%s' is out of range for type '%s'" % (value, rs_type))
+            # Float constants like 1.0 must not lose their decimal point
+            return repr(value)
         return str(value)
 
-    if type_ == 'float32':
-        return '%sf' % value
-
-    if type_ == 'string':
-        return '"%s"' % escape_string(value)
+    if isinstance(type_, AbstractGenericString):
+        return '"%s"' % value.replace('\\', '\\\\').replace('"', '\\"')
 
     assert False, "unknown constant type '%s'" % type_
 
@@ -0,0 +1,26 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rosidl_runtime_rs::Sequence;
+
+// Compares appending a large numeric buffer element by element and all at once.
+fn extend_benchmark(c: &mut Criterion) {
+    let buffer: Vec<f32> = (0..100_000).map(|i| i as f32).collect();
+    let mut group = c.benchmark_group("append 100k f32");
+    group.bench_function("extend", |b| {
+        b.iter(|| {
+            let mut seq = Sequence::<f32>::new(16);
+            seq.extend(black_box(&buffer).iter().copied());
+            seq
+        })
+    });
+    group.bench_function("extend_from_slice", |b| {
+        b.iter(|| {
+            let mut seq = Sequence::<f32>::new(16);
+            seq.extend_from_slice(black_box(&buffer));
+            seq
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, extend_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,72 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rosidl_runtime_rs::{Sequence, SequenceAlloc, SequencePool};
+
+// The number of sequences that have been allocated and freed by C.
+static INITS: AtomicUsize = AtomicUsize::new(0);
+static FINIS: AtomicUsize = AtomicUsize::new(0);
+
+// A byte whose sequences count their allocations. A Rust global allocator can't see the
+// allocations inside rosidl_runtime_c, so the SequenceAlloc functions are counted instead.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct CountedByte(u8);
+
+fn as_byte_sequence(seq: &Sequence<CountedByte>) -> &Sequence<u8> {
+    // SAFETY: CountedByte is a transparent wrapper around u8, so the sequences have the same
+    // layout.
+    unsafe { &*(seq as *const Sequence<CountedByte> as *const Sequence<u8>) }
+}
+
+fn as_byte_sequence_mut(seq: &mut Sequence<CountedByte>) -> &mut Sequence<u8> {
+    // SAFETY: See as_byte_sequence().
+    unsafe { &mut *(seq as *mut Sequence<CountedByte> as *mut Sequence<u8>) }
+}
+
+impl SequenceAlloc for CountedByte {
+    fn sequence_init(seq: &mut Sequence<Self>, size: libc::size_t) -> bool {
+        INITS.fetch_add(1, Ordering::Relaxed);
+        u8::sequence_init(as_byte_sequence_mut(seq), size)
+    }
+    fn sequence_fini(seq: &mut Sequence<Self>) {
+        FINIS.fetch_add(1, Ordering::Relaxed);
+        u8::sequence_fini(as_byte_sequence_mut(seq))
+    }
+    fn sequence_copy(in_seq: &Sequence<Self>, out_seq: &mut Sequence<Self>) -> bool {
+        INITS.fetch_add(1, Ordering::Relaxed);
+        u8::sequence_copy(as_byte_sequence(in_seq), as_byte_sequence_mut(out_seq))
+    }
+}
+
+// Compares allocating a fresh buffer for each message with reusing buffers from a pool, and
+// checks that the pool does not allocate anything after warm-up.
+fn pool_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("1k byte buffer");
+    group.bench_function("Sequence::new", |b| {
+        b.iter(|| {
+            let mut seq = Sequence::<CountedByte>::new(1000);
+            seq.fill(CountedByte(1));
+            black_box(seq)
+        })
+    });
+
+    let pool = SequencePool::<CountedByte>::new(1, 1000);
+    // Warm-up: the pool allocates its sequences up front
+    drop(pool.acquire(1000));
+    let inits = INITS.load(Ordering::Relaxed);
+    let finis = FINIS.load(Ordering::Relaxed);
+    group.bench_function("SequencePool::acquire", |b| {
+        b.iter(|| {
+            let mut seq = pool.acquire(1000);
+            seq.fill(CountedByte(1));
+            black_box(&*seq);
+        })
+    });
+    group.finish();
+    assert_eq!(INITS.load(Ordering::Relaxed), inits, "The pool allocated");
+    assert_eq!(FINIS.load(Ordering::Relaxed), finis, "The pool freed");
+}
+
+criterion_group!(benches, pool_benchmark);
+criterion_main!(benches);
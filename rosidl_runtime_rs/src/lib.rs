@@ -1,9 +1,23 @@
 #![warn(missing_docs)]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 //! Bindings to `rosidl_runtime_c` and related functionality for messages.
+//!
+//! The `std` feature is enabled by default. Without it, this crate only depends on `core` and
+//! `alloc`, and the functionality that requires the standard library, such as reading and writing
+//! byte sequences with `std::io` or pooling sequences, is not available.
+
+extern crate alloc;
 
 #[macro_use]
 mod sequence;
-pub use sequence::{BoundedSequence, Sequence, SequenceExceedsBoundsError};
+#[cfg(feature = "serde")]
+pub use sequence::WithBound;
+pub use sequence::{
+    BoundedSequence, DiffSpan, NanPolicy, Sequence, SequenceDiff, SequenceExceedsBoundsError,
+    SequenceRingBuffer, TryReserveError,
+};
+#[cfg(feature = "std")]
+pub use sequence::{PooledSequence, SequencePool, SequenceReader};
 
 mod string;
 pub use string::{BoundedString, BoundedWString, String, StringExceedsBoundsError, WString};
@@ -1,11 +1,37 @@
-use std::cmp::Ordering;
-use std::fmt::{self, Debug, Display};
-use std::hash::{Hash, Hasher};
-use std::iter::{Extend, FromIterator, FusedIterator};
-use std::ops::{Deref, DerefMut};
-
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt::{self, Debug, Display};
+use core::hash::{Hash, Hasher};
+use core::iter::{Extend, FromIterator, FusedIterator};
+use core::ops::{Deref, DerefMut, Range, RangeBounds};
+#[cfg(feature = "std")]
+use std::collections::HashSet;
+
+mod approx;
+pub use approx::NanPolicy;
+#[cfg(feature = "bytes")]
+mod bytes;
+mod diff;
+pub use diff::{DiffSpan, SequenceDiff};
+#[cfg(feature = "std")]
+mod io;
+#[cfg(feature = "std")]
+pub use io::SequenceReader;
+#[cfg(feature = "ndarray")]
+mod ndarray;
+#[cfg(feature = "std")]
+mod pool;
+#[cfg(feature = "std")]
+pub use pool::{PooledSequence, SequencePool};
+#[cfg(feature = "rayon")]
+mod rayon;
+mod ring_buffer;
+pub use ring_buffer::SequenceRingBuffer;
 #[cfg(feature = "serde")]
 mod serde;
+#[cfg(feature = "serde")]
+pub use self::serde::WithBound;
 
 use crate::traits::SequenceAlloc;
 
@@ -17,6 +43,9 @@ use crate::traits::SequenceAlloc;
 /// as `std_msgs__msg__String__Sequence`. See the [`Message`](crate::Message) trait for background
 /// information on this topic.
 ///
+/// With the `serde` feature, sequences are serialized as lists of their elements. Byte sequences,
+/// i.e. `Sequence<u8>`, are the exception: they are serialized as base64 strings in
+/// human-readable formats such as JSON, and as raw bytes in binary formats.
 ///
 /// # Example
 ///
@@ -41,6 +70,16 @@ pub struct Sequence<T: SequenceAlloc> {
     capacity: libc::size_t,
 }
 
+// Sequences are passed to C functions as the `__Sequence` structs generated by
+// rosidl_generator_c, which are laid out as `{ T* data; size_t size; size_t capacity; }`.
+// Any change to the fields above must keep that layout. The field offsets are checked in
+// test_layout_matches_c().
+const _: () = {
+    use core::mem::size_of;
+    assert!(size_of::<Sequence<i32>>() == size_of::<*mut i32>() + 2 * size_of::<usize>());
+    assert!(size_of::<Sequence<f64>>() == size_of::<Sequence<i32>>());
+};
+
 /// A bounded sequence.
 ///
 /// The layout of a concrete `BoundedSequence<T>` is the same as the corresponding `Sequence`
@@ -66,25 +105,37 @@ pub struct Sequence<T: SequenceAlloc> {
 /// // The default bounded sequence is empty
 /// assert!(BoundedSequence::<i32, 5>::default().is_empty());
 /// ```
-#[derive(Clone)]
 #[repr(transparent)]
 pub struct BoundedSequence<T: SequenceAlloc, const N: usize> {
     inner: Sequence<T>,
 }
 
 /// Error type for [`BoundedSequence::try_new()`].
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct SequenceExceedsBoundsError {
     len: usize,
     upper_bound: usize,
 }
 
+/// Error type for [`Sequence::try_reserve()`] and [`Sequence::try_with_capacity()`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TryReserveError {
+    // None if the requested capacity overflowed usize
+    requested: Option<usize>,
+}
+
 /// A by-value iterator created by [`Sequence::into_iter()`] and [`BoundedSequence::into_iter()`].
 pub struct SequenceIterator<T: SequenceAlloc> {
     seq: Sequence<T>,
     idx: usize,
 }
 
+// Debug helper returned by Sequence::debug_truncated()
+struct TruncatedDebug<'a, T> {
+    slice: &'a [T],
+    max: usize,
+}
+
 // ========================= impl for Sequence =========================
 
 impl<T: SequenceAlloc> Clone for Sequence<T> {
@@ -96,6 +147,13 @@ impl<T: SequenceAlloc> Clone for Sequence<T> {
             panic!("Cloning Sequence failed")
         }
     }
+
+    fn clone_from(&mut self, source: &Self) {
+        // The copy functions only reallocate when the capacity is not sufficient.
+        if !T::sequence_copy(source, self) {
+            panic!("Cloning Sequence failed")
+        }
+    }
 }
 
 impl<T: Debug + SequenceAlloc> Debug for Sequence<T> {
@@ -107,7 +165,7 @@ impl<T: Debug + SequenceAlloc> Debug for Sequence<T> {
 impl<T: SequenceAlloc> Default for Sequence<T> {
     fn default() -> Self {
         Self {
-            data: std::ptr::null_mut(),
+            data: core::ptr::null_mut(),
             size: 0,
             capacity: 0,
         }
@@ -145,13 +203,16 @@ impl<T: SequenceAlloc> Extend<T> for Sequence<T> {
         let mut cur_idx = self.size;
         // Convenience closure for resizing self
         let resize = |seq: &mut Self, new_size: usize| {
-            let old_seq = std::mem::replace(seq, Sequence::new(new_size));
+            let old_seq = core::mem::replace(seq, Sequence::new(new_size));
             for (i, elem) in old_seq.into_iter().enumerate().take(new_size) {
                 seq[i] = elem;
             }
         };
         // First, when there is a size hint > 0 (lower bound), make room for
-        // that many elements.
+        // that many elements. For iterators with an exact size hint, such as
+        // the iterators of Vec and slices, this is the only allocation: the
+        // loop below never needs to grow the sequence, and there is nothing
+        // to shrink afterwards.
         let num_remaining = it.size_hint().0;
         if num_remaining > 0 {
             let new_size = self.size.saturating_add(num_remaining);
@@ -192,6 +253,19 @@ impl<T: SequenceAlloc> From<Vec<T>> for Sequence<T> {
     }
 }
 
+/// Together with the `FromIterator` impl for `Result` in the standard library, this also allows
+/// collecting fallible items into a `Result<Sequence<T>, E>`. Collecting stops at the first
+/// error, and the elements collected so far are dropped.
+///
+/// # Example
+///
+/// ```
+/// # use rosidl_runtime_rs::{Sequence, seq};
+/// let parsed: Result<Sequence<i32>, _> = ["1", "2", "3"].iter().map(|s| s.parse()).collect();
+/// assert_eq!(parsed, Ok(seq![1, 2, 3]));
+/// let parsed: Result<Sequence<i32>, _> = ["1", "x", "3"].iter().map(|s| s.parse()).collect();
+/// assert!(parsed.is_err());
+/// ```
 impl<T: SequenceAlloc> FromIterator<T> for Sequence<T> {
     fn from_iter<I>(iter: I) -> Self
     where
@@ -217,6 +291,22 @@ impl<T: SequenceAlloc> IntoIterator for Sequence<T> {
     }
 }
 
+impl<'a, T: SequenceAlloc> IntoIterator for &'a Sequence<T> {
+    type Item = &'a T;
+    type IntoIter = core::slice::Iter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.as_slice().iter()
+    }
+}
+
+impl<'a, T: SequenceAlloc> IntoIterator for &'a mut Sequence<T> {
+    type Item = &'a mut T;
+    type IntoIter = core::slice::IterMut<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.as_mut_slice().iter_mut()
+    }
+}
+
 impl<T: SequenceAlloc + Ord> Ord for Sequence<T> {
     fn cmp(&self, other: &Self) -> Ordering {
         self.as_slice().cmp(other.as_slice())
@@ -253,13 +343,388 @@ where
         seq
     }
 
+    /// Creates a sequence of `len` elements, where each element is the result of calling `f`
+    /// with its index.
+    ///
+    /// This is analogous to [`std::array::from_fn()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rosidl_runtime_rs::{Sequence, seq};
+    /// let squares = Sequence::from_fn(4, |i| (i * i) as i32);
+    /// assert_eq!(squares, seq![0, 1, 4, 9]);
+    /// ```
+    pub fn from_fn<F>(len: usize, mut f: F) -> Self
+    where
+        F: FnMut(usize) -> T,
+    {
+        let mut seq = Self::new(len);
+        for (i, element) in seq.iter_mut().enumerate() {
+            *element = f(i);
+        }
+        seq
+    }
+
+    /// Converts each element with `f`, producing a sequence of a different element type.
+    ///
+    /// The new sequence is allocated once with the final length.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rosidl_runtime_rs::{Sequence, seq};
+    /// let ints: Sequence<i32> = seq![1, 2, 3];
+    /// let halves = ints.map(|x| f64::from(x) / 2.0);
+    /// assert_eq!(halves, seq![0.5, 1.0, 1.5]);
+    /// ```
+    pub fn map<B, F>(self, mut f: F) -> Sequence<B>
+    where
+        B: SequenceAlloc,
+        F: FnMut(T) -> B,
+    {
+        let mut seq = Sequence::<B>::new(self.len());
+        for (target, element) in seq.iter_mut().zip(self) {
+            *target = f(element);
+        }
+        seq
+    }
+
+    /// Returns an iterator over the indices and mutable references of the elements.
+    ///
+    /// This is a shorthand for `seq.iter_mut().enumerate()`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rosidl_runtime_rs::{Sequence, seq};
+    /// let mut seq: Sequence<i32> = seq![1, 2, 3, 4];
+    /// for (i, element) in seq.enumerate_mut() {
+    ///     if i % 2 == 0 {
+    ///         *element = -*element;
+    ///     }
+    /// }
+    /// assert_eq!(seq, seq![-1, 2, -3, 4]);
+    /// ```
+    pub fn enumerate_mut(&mut self) -> impl Iterator<Item = (usize, &mut T)> {
+        self.as_mut_slice().iter_mut().enumerate()
+    }
+
+    /// Rotates the elements in-place so that the element at index `mid` becomes the first one.
+    ///
+    /// This is the same as [`slice::rotate_left()`]. The elements are only moved around, so the
+    /// sequence keeps its memory.
+    ///
+    /// # Panics
+    /// When `mid` is greater than the length of the sequence.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rosidl_runtime_rs::{Sequence, seq};
+    /// // Dropping the oldest sample of a ring buffer, and reusing its slot for the newest one
+    /// let mut samples: Sequence<i32> = seq![1, 2, 3, 4];
+    /// samples.rotate_left(1);
+    /// *samples.last_mut().unwrap() = 5;
+    /// assert_eq!(samples, seq![2, 3, 4, 5]);
+    /// ```
+    pub fn rotate_left(&mut self, mid: usize) {
+        self.as_mut_slice().rotate_left(mid)
+    }
+
+    /// Rotates the elements in-place so that the last `k` elements become the first ones.
+    ///
+    /// This is the same as [`slice::rotate_right()`]. The elements are only moved around, so the
+    /// sequence keeps its memory.
+    ///
+    /// # Panics
+    /// When `k` is greater than the length of the sequence.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rosidl_runtime_rs::{Sequence, seq};
+    /// let mut seq: Sequence<i32> = seq![1, 2, 3, 4];
+    /// seq.rotate_right(1);
+    /// assert_eq!(seq, seq![4, 1, 2, 3]);
+    /// ```
+    pub fn rotate_right(&mut self, k: usize) {
+        self.as_mut_slice().rotate_right(k)
+    }
+
+    /// Returns the element at index `i` converted to `U`, or `None` if the index is out of bounds.
+    ///
+    /// This is a shorthand for `seq.get(i).copied().map(U::from)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rosidl_runtime_rs::{Sequence, seq};
+    /// let seq: Sequence<i32> = seq![i32::MAX, -1];
+    /// assert_eq!(seq.get_as::<i64>(0), Some(2147483647));
+    /// assert_eq!(seq.get_as::<f64>(1), Some(-1.0));
+    /// assert_eq!(seq.get_as::<i64>(2), None);
+    /// ```
+    pub fn get_as<U: From<T>>(&self, i: usize) -> Option<U>
+    where
+        T: Copy,
+    {
+        self.get(i).copied().map(U::from)
+    }
+
+    /// Returns the `n`-th element from the end, or `None` if `n` is out of bounds.
+    ///
+    /// `get_back(0)` is the last element, like [`last()`](slice::last).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rosidl_runtime_rs::{Sequence, seq};
+    /// let seq: Sequence<i32> = seq![1, 2, 3];
+    /// assert_eq!(seq.get_back(0), Some(&3));
+    /// assert_eq!(seq.get_back(2), Some(&1));
+    /// assert_eq!(seq.get_back(3), None);
+    /// ```
+    pub fn get_back(&self, n: usize) -> Option<&T> {
+        self.iter().nth_back(n)
+    }
+
+    /// Returns a mutable reference to the `n`-th element from the end, or `None` if `n` is out of
+    /// bounds.
+    ///
+    /// See [`get_back()`](Self::get_back).
+    pub fn get_back_mut(&mut self, n: usize) -> Option<&mut T> {
+        self.iter_mut().nth_back(n)
+    }
+
+    /// Clones the elements into a [`HashSet`], which removes duplicates.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rosidl_runtime_rs::{Sequence, seq};
+    /// let seq: Sequence<i32> = seq![3, 1, 3, 2, 1];
+    /// let set = seq.to_hash_set();
+    /// assert_eq!(set.len(), 3);
+    /// assert!(set.contains(&2));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn to_hash_set(&self) -> HashSet<T>
+    where
+        T: Clone + Eq + Hash,
+    {
+        self.iter().cloned().collect()
+    }
+
+    /// Clones the elements into a [`BTreeSet`], which removes duplicates and sorts the elements.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rosidl_runtime_rs::{Sequence, seq};
+    /// let seq: Sequence<i32> = seq![3, 1, 3, 2, 1];
+    /// let set = seq.to_btree_set();
+    /// assert!(set.into_iter().eq([1, 2, 3]));
+    /// ```
+    pub fn to_btree_set(&self) -> BTreeSet<T>
+    where
+        T: Clone + Ord,
+    {
+        self.iter().cloned().collect()
+    }
+
+    /// Copies the elements in `range` into a new sequence.
+    ///
+    /// Indexing with a range, like `&seq[1..3]`, only borrows the elements as a slice. This
+    /// creates an independent sequence instead, e.g. for putting it into another message.
+    ///
+    /// # Panics
+    /// When the range is out of bounds, like slice indexing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rosidl_runtime_rs::{Sequence, seq};
+    /// let seq: Sequence<i32> = seq![1, 2, 3, 4];
+    /// assert_eq!(seq.sub_sequence(1..3), seq![2, 3]);
+    /// assert_eq!(seq.sub_sequence(2..), seq![3, 4]);
+    /// ```
+    pub fn sub_sequence<R: RangeBounds<usize>>(&self, range: R) -> Self
+    where
+        T: Clone,
+    {
+        let bounds = (range.start_bound().cloned(), range.end_bound().cloned());
+        Self::from(&self[bounds])
+    }
+
+    /// Splits the sequence into runs of consecutive elements that belong to the same group, and
+    /// returns their index ranges.
+    ///
+    /// `same_group` is called with each pair of neighboring elements. The ranges are in order,
+    /// and together cover the entire sequence. An empty sequence has no runs.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rosidl_runtime_rs::{Sequence, seq};
+    /// let labels: Sequence<u8> = seq![1, 1, 2, 2, 2, 3];
+    /// assert_eq!(labels.group_runs_by(|a, b| a == b), [0..2, 2..5, 5..6]);
+    /// ```
+    pub fn group_runs_by<F>(&self, mut same_group: F) -> Vec<Range<usize>>
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        let mut runs = Vec::new();
+        let mut start = 0;
+        for (i, pair) in self.windows(2).enumerate() {
+            if !same_group(&pair[0], &pair[1]) {
+                runs.push(start..i + 1);
+                start = i + 1;
+            }
+        }
+        if start < self.len() {
+            runs.push(start..self.len());
+        }
+        runs
+    }
+
+    /// Calls `f` on each overlapping window of `size` consecutive elements, and collects the
+    /// results.
+    ///
+    /// The windows are in order, so the result has `len() - size + 1` elements. If the sequence
+    /// is shorter than `size`, the result is empty.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rosidl_runtime_rs::{Sequence, seq};
+    /// let ranges: Sequence<f32> = seq![1.0, 2.0, 4.0, 7.0];
+    /// let moving_average = ranges.windows_map(2, |w| w.iter().sum::<f32>() / w.len() as f32);
+    /// assert_eq!(moving_average, [1.5, 3.0, 5.5]);
+    /// ```
+    pub fn windows_map<B, F>(&self, size: usize, f: F) -> Vec<B>
+    where
+        F: FnMut(&[T]) -> B,
+    {
+        assert!(size != 0, "window size must be non-zero");
+        self.windows(size).map(f).collect()
+    }
+
+    /// Computes the difference between this sequence and `other`.
+    ///
+    /// The result is a minimal edit script, as a list of spans of common, removed and added
+    /// elements, computed with Myers' diff algorithm. This is useful e.g. for only forwarding the
+    /// changed parts of a large array field to a downstream consumer.
+    ///
+    /// The runtime is O((N + M) * D), where D is the number of removed and added elements, so
+    /// this is cheap for sequences that differ in few places.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rosidl_runtime_rs::{seq, DiffSpan, Sequence};
+    /// let old: Sequence<i32> = seq![1, 2, 3, 4];
+    /// let diff = old.diff(&[1, 3, 4, 5]);
+    /// assert_eq!(
+    ///     diff.spans(),
+    ///     [
+    ///         DiffSpan::Common { old: 0..1, new: 0..1 },
+    ///         DiffSpan::Removed(1..2),
+    ///         DiffSpan::Common { old: 2..4, new: 1..3 },
+    ///         DiffSpan::Added(3..4),
+    ///     ]
+    /// );
+    /// assert_eq!(diff.edit_distance(), 2);
+    /// ```
+    pub fn diff(&self, other: &[T]) -> SequenceDiff
+    where
+        T: PartialEq,
+    {
+        diff::diff(self.as_slice(), other)
+    }
+
+    /// Returns `true` if both sequences point to the same memory.
+    ///
+    /// This is an identity check, not an equality check, and is much cheaper than comparing the
+    /// elements. Note that empty sequences often have no memory at all, so two distinct empty
+    /// sequences may compare as identical.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rosidl_runtime_rs::{Sequence, seq};
+    /// let seq: Sequence<i32> = seq![1, 2, 3];
+    /// let copy = seq.clone();
+    /// assert!(seq.ptr_eq(&seq));
+    /// assert!(!seq.ptr_eq(&copy));
+    /// ```
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        core::ptr::eq(self.data, other.data)
+    }
+
+    /// Takes the elements out of the sequence, leaving an empty sequence in its place.
+    ///
+    /// This is equivalent to [`core::mem::take()`], and does not allocate or copy anything: the
+    /// returned sequence owns the original allocation, and the sequence left behind has no
+    /// capacity.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rosidl_runtime_rs::{Sequence, seq};
+    /// # #[derive(Default)]
+    /// # struct Msg { values: Sequence<i32> }
+    /// let mut values = Sequence::<i32>::default();
+    /// let mut messages = Vec::new();
+    /// for i in 0..3 {
+    ///     values.extend([i, i + 1]);
+    ///     // Move the sequence into the message, and start the next one from scratch.
+    ///     messages.push(Msg { values: values.take() });
+    ///     assert!(values.is_empty());
+    /// }
+    /// assert_eq!(messages[2].values, seq![2, 3]);
+    /// ```
+    pub fn take(&mut self) -> Self {
+        core::mem::take(self)
+    }
+
+    /// Returns a `Debug` representation that shows at most `max` elements.
+    ///
+    /// When the sequence is longer than that, only the first and last elements are shown, with an
+    /// ellipsis and the total number of elements. This is useful for logging sequences that can be
+    /// very long. The regular `Debug` impl always shows all elements.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rosidl_runtime_rs::Sequence;
+    /// let seq = Sequence::from_fn(100, |i| i as i32);
+    /// assert_eq!(
+    ///     format!("{:?}", seq.debug_truncated(4)),
+    ///     "[0, 1, ..., 98, 99] (100 elements)"
+    /// );
+    /// ```
+    pub fn debug_truncated(&self, max: usize) -> impl Debug + '_
+    where
+        T: Debug,
+    {
+        TruncatedDebug {
+            slice: self.as_slice(),
+            max,
+        }
+    }
+
     /// Extracts a slice containing the entire sequence.
     ///
     /// Equivalent to `&seq[..]`.
     pub fn as_slice(&self) -> &[T] {
         // SAFETY: self.data points to self.size consecutive, initialized elements and
         // isn't modified externally.
-        unsafe { std::slice::from_raw_parts(self.data, self.size) }
+        unsafe { core::slice::from_raw_parts(self.data, self.size) }
     }
 
     /// Extracts a mutable slice containing the entire sequence.
@@ -268,36 +733,373 @@ where
     pub fn as_mut_slice(&mut self) -> &mut [T] {
         // SAFETY: self.data points to self.size consecutive, initialized elements and
         // isn't modified externally.
-        unsafe { std::slice::from_raw_parts_mut(self.data, self.size) }
+        unsafe { core::slice::from_raw_parts_mut(self.data, self.size) }
     }
 }
 
 impl<T: Default + SequenceAlloc> Sequence<T> {
-    /// Internal function for the sequence_copy impl. To be removed when rosidl#650 is backported and released.
-    pub fn resize_to_at_least(&mut self, len: usize) {
-        let allocation_size = std::mem::size_of::<Self>() * len;
-        if self.capacity < len {
-            // SAFETY: The memory in self.data is owned by C.
-            let data = unsafe { libc::realloc(self.data as *mut _, allocation_size) } as *mut T;
-            if data.is_null() {
+    /// Creates an empty sequence with room for at least `capacity` elements, without panicking
+    /// when the allocation fails.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rosidl_runtime_rs::Sequence;
+    /// let seq = Sequence::<f32>::try_with_capacity(10).unwrap();
+    /// assert!(seq.is_empty());
+    /// ```
+    pub fn try_with_capacity(capacity: usize) -> Result<Self, TryReserveError> {
+        let mut seq = Self::default();
+        seq.try_reserve(capacity)?;
+        Ok(seq)
+    }
+
+    /// Tries to reserve capacity for at least `additional` more elements than the current length.
+    ///
+    /// The length of the sequence is not changed. Unlike most other operations on sequences, this
+    /// returns an error instead of panicking when the memory can not be allocated. In that case,
+    /// the sequence is left unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rosidl_runtime_rs::{Sequence, seq};
+    /// let mut seq: Sequence<u8> = seq![1, 2, 3];
+    /// seq.try_reserve(100).unwrap();
+    /// assert_eq!(&seq[..], &[1, 2, 3]);
+    /// assert!(seq.try_reserve(usize::MAX).is_err());
+    /// ```
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let requested = self
+            .size
+            .checked_add(additional)
+            .ok_or(TryReserveError { requested: None })?;
+        self.grow(requested)
+    }
+
+    /// Appends all elements of a slice to the sequence.
+    ///
+    /// This is analogous to [`Vec::extend_from_slice()`]. Unlike [`Extend::extend()`], which
+    /// handles one element at a time, this grows the sequence at most once, and then copies all
+    /// elements at once. That makes it the fastest way to append large numeric buffers.
+    ///
+    /// When the sequence needs to grow, its capacity is at least doubled, so that repeatedly
+    /// appending small slices does not reallocate every time.
+    ///
+    /// # Panics
+    /// When the allocation fails.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rosidl_runtime_rs::{Sequence, seq};
+    /// let mut samples: Sequence<f32> = seq![0.5];
+    /// samples.extend_from_slice(&[1.5, 2.5]);
+    /// assert_eq!(samples, seq![0.5, 1.5, 2.5]);
+    /// ```
+    pub fn extend_from_slice(&mut self, other: &[T])
+    where
+        T: Copy,
+    {
+        let old_size = self.size;
+        let new_size = old_size
+            .checked_add(other.len())
+            .expect("capacity overflow");
+        if new_size > self.capacity {
+            let capacity = new_size.max(self.capacity.saturating_mul(2));
+            if self.grow(capacity).is_err() {
                 panic!("realloc failed");
             }
-            // Initialize the new memory
-            for i in self.capacity..len {
-                // SAFETY: i is in bounds, and write() is appropriate for initializing uninitialized memory
-                unsafe {
-                    data.add(i).write(T::default());
-                }
+        }
+        self.size = new_size;
+        self[old_size..].copy_from_slice(other);
+    }
+
+    /// Resizes the sequence to `new_len` elements, filling new elements with clones of `value`.
+    ///
+    /// This is analogous to [`Vec::resize()`].
+    ///
+    /// # Panics
+    /// When the allocation fails.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rosidl_runtime_rs::{Sequence, seq};
+    /// let mut seq: Sequence<i32> = seq![1, 2];
+    /// seq.resize(4, 7);
+    /// assert_eq!(seq, seq![1, 2, 7, 7]);
+    /// seq.resize(1, 7);
+    /// assert_eq!(seq, seq![1]);
+    /// ```
+    pub fn resize(&mut self, new_len: usize, value: T)
+    where
+        T: Clone,
+    {
+        self.resize_with(new_len, || value.clone())
+    }
+
+    /// Resizes the sequence to `new_len` elements, filling new elements with the results of `f`.
+    ///
+    /// This is analogous to [`Vec::resize_with()`].
+    ///
+    /// # Panics
+    /// When the allocation fails.
+    pub fn resize_with<F>(&mut self, new_len: usize, mut f: F)
+    where
+        F: FnMut() -> T,
+    {
+        let old_len = self.size;
+        self.resize_to_at_least(new_len);
+        if new_len > old_len {
+            for element in &mut self[old_len..] {
+                *element = f();
+            }
+        }
+    }
+
+    /// Retains only the elements for which `f` returns `true`.
+    ///
+    /// This is analogous to [`Vec::retain()`]. The order of the retained elements is preserved.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rosidl_runtime_rs::{Sequence, seq};
+    /// let mut seq: Sequence<i32> = seq![1, 2, 3, 4];
+    /// seq.retain(|x| x % 2 == 0);
+    /// assert_eq!(seq, seq![2, 4]);
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.retain_mut(|element| f(element))
+    }
+
+    /// Retains only the elements for which `f` returns `true`, passing a mutable reference to it.
+    ///
+    /// This is analogous to [`Vec::retain_mut()`]. Changes that `f` makes to retained elements
+    /// are kept. The removed elements are dropped right away, and their slots stay allocated as
+    /// spare capacity.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rosidl_runtime_rs::{Sequence, seq};
+    /// let mut seq: Sequence<i32> = seq![1, -2, 3, -4];
+    /// seq.retain_mut(|x| {
+    ///     *x *= 10;
+    ///     *x > 0
+    /// });
+    /// assert_eq!(seq, seq![10, 30]);
+    /// ```
+    pub fn retain_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        let mut kept = 0;
+        for i in 0..self.size {
+            if f(&mut self[i]) {
+                // Moves the element into the first vacated slot, if any
+                self.swap(kept, i);
+                kept += 1;
+            } else {
+                // The slot is left with a default value, which is finalized with the sequence.
+                drop(core::mem::take(&mut self[i]));
+            }
+        }
+        self.size = kept;
+    }
+
+    /// Splits off the first element, returning it together with the remaining sequence.
+    ///
+    /// This is the owning counterpart of [`slice::split_first()`]. The remaining elements are
+    /// moved within the existing allocation, so nothing is copied or allocated. Returns `None` if
+    /// the sequence is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rosidl_runtime_rs::{Sequence, seq};
+    /// let seq: Sequence<i32> = seq![1, 2, 3];
+    /// let (first, rest) = seq.into_split_first().unwrap();
+    /// assert_eq!(first, 1);
+    /// assert_eq!(rest, seq![2, 3]);
+    /// ```
+    pub fn into_split_first(mut self) -> Option<(T, Self)> {
+        if self.is_empty() {
+            return None;
+        }
+        // The vacated slot is moved to the end, where it stays allocated as spare capacity.
+        let first = core::mem::take(&mut self[0]);
+        self.rotate_left(1);
+        self.size -= 1;
+        Some((first, self))
+    }
+
+    /// Splits off the last element, returning it together with the remaining sequence.
+    ///
+    /// This is the owning counterpart of [`slice::split_last()`]. Nothing is copied or
+    /// allocated. Returns `None` if the sequence is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rosidl_runtime_rs::{Sequence, seq};
+    /// let seq: Sequence<i32> = seq![1, 2, 3];
+    /// let (last, rest) = seq.into_split_last().unwrap();
+    /// assert_eq!(last, 3);
+    /// assert_eq!(rest, seq![1, 2]);
+    /// ```
+    pub fn into_split_last(mut self) -> Option<(T, Self)> {
+        let last = core::mem::take(self.last_mut()?);
+        self.size -= 1;
+        Some((last, self))
+    }
+
+    /// Splits the sequence into two at the given index.
+    ///
+    /// This is the owning counterpart of [`slice::split_at()`]. The first sequence keeps the
+    /// elements `[0, mid)` in the existing allocation, the elements `[mid, len)` are moved into a
+    /// newly allocated sequence.
+    ///
+    /// # Panics
+    /// When `mid > len`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rosidl_runtime_rs::{Sequence, seq};
+    /// let seq: Sequence<i32> = seq![1, 2, 3, 4];
+    /// let (left, right) = seq.into_split_at(1);
+    /// assert_eq!(left, seq![1]);
+    /// assert_eq!(right, seq![2, 3, 4]);
+    /// ```
+    pub fn into_split_at(mut self, mid: usize) -> (Self, Self) {
+        assert!(mid <= self.size, "mid > len");
+        let tail = Sequence::from_fn(self.size - mid, |i| core::mem::take(&mut self[mid + i]));
+        self.size = mid;
+        (self, tail)
+    }
+
+    /// Splits the sequence into chunks of exactly `n` elements, and a remainder of fewer than `n`
+    /// elements.
+    ///
+    /// This is the owning counterpart of [`slice::chunks_exact()`], e.g. for processing samples in
+    /// fixed-size batches. The returned iterator moves each chunk into a newly allocated sequence
+    /// when it is reached, and the remainder, i.e. the last `len % n` elements, is moved into a
+    /// sequence right away. No elements are cloned.
+    ///
+    /// # Panics
+    /// When `n` is 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rosidl_runtime_rs::{Sequence, seq};
+    /// let samples: Sequence<f32> = seq![1.0, 2.0, 3.0, 4.0, 5.0];
+    /// let (chunks, remainder) = samples.into_chunks_exact(2);
+    /// let sums: Vec<f32> = chunks.map(|chunk| chunk.iter().sum()).collect();
+    /// assert_eq!(sums, [3.0, 7.0]);
+    /// assert_eq!(remainder, seq![5.0]);
+    /// ```
+    pub fn into_chunks_exact(
+        self,
+        n: usize,
+    ) -> (
+        impl ExactSizeIterator<Item = Self> + DoubleEndedIterator,
+        Self,
+    ) {
+        assert!(n != 0, "chunk size must be non-zero");
+        let exact_len = self.size - self.size % n;
+        let (mut chunks, remainder) = self.into_split_at(exact_len);
+        let iter = (0..exact_len / n)
+            .map(move |i| Sequence::from_fn(n, |j| core::mem::take(&mut chunks[i * n + j])));
+        (iter, remainder)
+    }
+
+    /// Converts the sequence into an array, if it has exactly `M` elements.
+    ///
+    /// The elements are moved into the array, so e.g. strings are not copied. Otherwise, the
+    /// sequence is returned unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rosidl_runtime_rs::{Sequence, seq};
+    /// let seq: Sequence<i32> = seq![1, 2, 3];
+    /// let seq = seq.try_into_array::<2>().unwrap_err();
+    /// let array: [i32; 3] = seq.try_into_array().unwrap();
+    /// assert_eq!(array, [1, 2, 3]);
+    /// ```
+    pub fn try_into_array<const M: usize>(mut self) -> Result<[T; M], Self> {
+        if self.size != M {
+            return Err(self);
+        }
+        // The vacated slots stay allocated until the sequence is dropped.
+        let elements: Vec<T> = self.iter_mut().map(core::mem::take).collect();
+        // Can't fail, since the length has been checked above.
+        Ok(elements.try_into().unwrap_or_else(|_| unreachable!()))
+    }
+
+    /// Internal function for the sequence_copy impl. To be removed when rosidl#650 is backported and released.
+    ///
+    /// Afterwards, the sequence has exactly `len` elements. Elements beyond `len` are kept
+    /// allocated, since the C fini functions finalize all elements up to the capacity.
+    pub fn resize_to_at_least(&mut self, len: usize) {
+        if self.grow(len).is_err() {
+            panic!("realloc failed");
+        }
+        self.size = len;
+    }
+
+    // Makes sure that the capacity is at least the given number of elements. New elements are
+    // initialized with their default value.
+    fn grow(&mut self, capacity: usize) -> Result<(), TryReserveError> {
+        if self.capacity >= capacity {
+            return Ok(());
+        }
+        let err = TryReserveError {
+            requested: Some(capacity),
+        };
+        let allocation_size = core::mem::size_of::<T>()
+            .checked_mul(capacity)
+            .filter(|&size| size <= isize::MAX as usize)
+            .ok_or(err)?;
+        // SAFETY: The memory in self.data is owned by C.
+        let data = unsafe { libc::realloc(self.data as *mut _, allocation_size) } as *mut T;
+        if data.is_null() {
+            // The old memory is still valid when realloc fails.
+            return Err(err);
+        }
+        // Initialize the new memory
+        for i in self.capacity..capacity {
+            // SAFETY: i is in bounds, and write() is appropriate for initializing uninitialized memory
+            unsafe {
+                data.add(i).write(T::default());
             }
-            self.data = data;
-            self.size = len;
-            self.capacity = len;
         }
+        self.data = data;
+        self.capacity = capacity;
+        Ok(())
     }
 }
 
 // ========================= impl for BoundedSequence =========================
 
+impl<T: SequenceAlloc, const N: usize> Clone for BoundedSequence<T, N> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.inner.clone_from(&source.inner);
+    }
+}
+
 impl<T: Debug + SequenceAlloc, const N: usize> Debug for BoundedSequence<T, N> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         self.as_slice().fmt(f)
@@ -308,7 +1110,7 @@ impl<T: SequenceAlloc, const N: usize> Default for BoundedSequence<T, N> {
     fn default() -> Self {
         Self {
             inner: Sequence {
-                data: std::ptr::null_mut(),
+                data: core::ptr::null_mut(),
                 size: 0,
                 capacity: 0,
             },
@@ -337,6 +1139,9 @@ impl<T: SequenceAlloc, const N: usize> Drop for BoundedSequence<T, N> {
 
 impl<T: SequenceAlloc + Eq, const N: usize> Eq for BoundedSequence<T, N> {}
 
+/// Appends elements until the upper bound is reached.
+///
+/// **Elements beyond the upper bound are silently dropped.**
 impl<T: SequenceAlloc, const N: usize> Extend<T> for BoundedSequence<T, N> {
     fn extend<I>(&mut self, iter: I)
     where
@@ -370,6 +1175,10 @@ impl<T: SequenceAlloc, const N: usize> TryFrom<Vec<T>> for BoundedSequence<T, N>
     }
 }
 
+/// Collects at most `N` elements from the iterator.
+///
+/// **Elements beyond the upper bound are silently dropped.** Use
+/// [`BoundedSequence::try_from_iter()`] to get an error instead.
 impl<T: SequenceAlloc, const N: usize> FromIterator<T> for BoundedSequence<T, N> {
     fn from_iter<I>(iter: I) -> Self
     where
@@ -391,10 +1200,10 @@ impl<T: SequenceAlloc, const N: usize> IntoIterator for BoundedSequence<T, N> {
     type Item = T;
     type IntoIter = SequenceIterator<T>;
     fn into_iter(mut self) -> Self::IntoIter {
-        let seq = std::mem::replace(
+        let seq = core::mem::replace(
             &mut self.inner,
             Sequence {
-                data: std::ptr::null_mut(),
+                data: core::ptr::null_mut(),
                 size: 0,
                 capacity: 0,
             },
@@ -403,6 +1212,22 @@ impl<T: SequenceAlloc, const N: usize> IntoIterator for BoundedSequence<T, N> {
     }
 }
 
+impl<'a, T: SequenceAlloc, const N: usize> IntoIterator for &'a BoundedSequence<T, N> {
+    type Item = &'a T;
+    type IntoIter = core::slice::Iter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.as_slice().iter()
+    }
+}
+
+impl<'a, T: SequenceAlloc, const N: usize> IntoIterator for &'a mut BoundedSequence<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = core::slice::IterMut<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.as_mut_slice().iter_mut()
+    }
+}
+
 impl<T: SequenceAlloc + Ord, const N: usize> Ord for BoundedSequence<T, N> {
     fn cmp(&self, other: &Self) -> Ordering {
         self.as_slice().cmp(other.as_slice())
@@ -449,6 +1274,127 @@ where
         Ok(seq)
     }
 
+    /// Attempts to collect the elements of an iterator into a bounded sequence.
+    ///
+    /// Unlike the [`FromIterator`] impl, which drops elements beyond the upper bound, this
+    /// returns an error when the iterator yields more than `N` elements. The iterator is not
+    /// consumed past the first excess element, so the length in the error is `N + 1`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rosidl_runtime_rs::BoundedSequence;
+    /// assert!(BoundedSequence::<i32, 3>::try_from_iter(0..3).is_ok());
+    /// assert!(BoundedSequence::<i32, 3>::try_from_iter(0..4).is_err());
+    /// ```
+    pub fn try_from_iter<I>(iter: I) -> Result<Self, SequenceExceedsBoundsError>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let inner: Sequence<T> = iter.into_iter().take(N.saturating_add(1)).collect();
+        if inner.len() > N {
+            return Err(SequenceExceedsBoundsError {
+                len: inner.len(),
+                upper_bound: N,
+            });
+        }
+        Ok(Self { inner })
+    }
+
+    /// Returns an iterator over the indices and mutable references of the elements.
+    ///
+    /// See [`Sequence::enumerate_mut()`].
+    pub fn enumerate_mut(&mut self) -> impl Iterator<Item = (usize, &mut T)> {
+        self.inner.enumerate_mut()
+    }
+
+    /// Rotates the elements in-place so that the element at index `mid` becomes the first one.
+    ///
+    /// See [`Sequence::rotate_left()`].
+    pub fn rotate_left(&mut self, mid: usize) {
+        self.inner.rotate_left(mid)
+    }
+
+    /// Rotates the elements in-place so that the last `k` elements become the first ones.
+    ///
+    /// See [`Sequence::rotate_right()`].
+    pub fn rotate_right(&mut self, k: usize) {
+        self.inner.rotate_right(k)
+    }
+
+    /// Returns the element at index `i` converted to `U`, or `None` if the index is out of bounds.
+    ///
+    /// See [`Sequence::get_as()`].
+    pub fn get_as<U: From<T>>(&self, i: usize) -> Option<U>
+    where
+        T: Copy,
+    {
+        self.inner.get_as(i)
+    }
+
+    /// See [`Sequence::get_back()`].
+    pub fn get_back(&self, n: usize) -> Option<&T> {
+        self.inner.get_back(n)
+    }
+
+    /// See [`Sequence::get_back_mut()`].
+    pub fn get_back_mut(&mut self, n: usize) -> Option<&mut T> {
+        self.inner.get_back_mut(n)
+    }
+
+    /// See [`Sequence::to_hash_set()`].
+    #[cfg(feature = "std")]
+    pub fn to_hash_set(&self) -> HashSet<T>
+    where
+        T: Clone + Eq + Hash,
+    {
+        self.inner.to_hash_set()
+    }
+
+    /// See [`Sequence::to_btree_set()`].
+    pub fn to_btree_set(&self) -> BTreeSet<T>
+    where
+        T: Clone + Ord,
+    {
+        self.inner.to_btree_set()
+    }
+
+    /// See [`Sequence::group_runs_by()`].
+    pub fn group_runs_by<F>(&self, same_group: F) -> Vec<Range<usize>>
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        self.inner.group_runs_by(same_group)
+    }
+
+    /// See [`Sequence::windows_map()`].
+    pub fn windows_map<B, F>(&self, size: usize, f: F) -> Vec<B>
+    where
+        F: FnMut(&[T]) -> B,
+    {
+        self.inner.windows_map(size, f)
+    }
+
+    /// Computes the difference between this sequence and `other`.
+    ///
+    /// See [`Sequence::diff()`].
+    pub fn diff(&self, other: &[T]) -> SequenceDiff
+    where
+        T: PartialEq,
+    {
+        self.inner.diff(other)
+    }
+
+    /// Returns a `Debug` representation that shows at most `max` elements.
+    ///
+    /// See [`Sequence::debug_truncated()`].
+    pub fn debug_truncated(&self, max: usize) -> impl Debug + '_
+    where
+        T: Debug,
+    {
+        self.inner.debug_truncated(max)
+    }
+
     /// Extracts a slice containing the entire sequence.
     ///
     /// Equivalent to `&seq[..]`.
@@ -464,6 +1410,87 @@ where
     }
 }
 
+impl<T, const N: usize> BoundedSequence<T, N>
+where
+    T: Default + SequenceAlloc,
+{
+    /// Resizes the sequence to `new_len` elements, filling new elements with clones of `value`.
+    ///
+    /// # Panics
+    /// When `new_len` is greater than `N`, or when the allocation fails. See
+    /// [`try_resize()`](Self::try_resize) for a non-panicking alternative.
+    pub fn resize(&mut self, new_len: usize, value: T)
+    where
+        T: Clone,
+    {
+        self.try_resize(new_len, value).unwrap()
+    }
+
+    /// Resizes the sequence to `new_len` elements, filling new elements with the results of `f`.
+    ///
+    /// # Panics
+    /// When `new_len` is greater than `N`, or when the allocation fails. See
+    /// [`try_resize_with()`](Self::try_resize_with) for a non-panicking alternative.
+    pub fn resize_with<F>(&mut self, new_len: usize, f: F)
+    where
+        F: FnMut() -> T,
+    {
+        self.try_resize_with(new_len, f).unwrap()
+    }
+
+    /// Attempts to resize the sequence to `new_len` elements, filling new elements with clones
+    /// of `value`.
+    ///
+    /// If `new_len` is greater than `N`, this function returns an error and leaves the sequence
+    /// unchanged.
+    ///
+    /// # Panics
+    /// When the allocation fails.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rosidl_runtime_rs::BoundedSequence;
+    /// let mut seq = BoundedSequence::<i32, 3>::new(1);
+    /// assert!(seq.try_resize(3, 5).is_ok());
+    /// assert_eq!(&seq[..], &[0, 5, 5]);
+    /// assert!(seq.try_resize(4, 5).is_err());
+    /// assert_eq!(seq.len(), 3);
+    /// ```
+    pub fn try_resize(&mut self, new_len: usize, value: T) -> Result<(), SequenceExceedsBoundsError>
+    where
+        T: Clone,
+    {
+        self.try_resize_with(new_len, || value.clone())
+    }
+
+    /// Attempts to resize the sequence to `new_len` elements, filling new elements with the
+    /// results of `f`.
+    ///
+    /// If `new_len` is greater than `N`, this function returns an error and leaves the sequence
+    /// unchanged.
+    ///
+    /// # Panics
+    /// When the allocation fails.
+    pub fn try_resize_with<F>(
+        &mut self,
+        new_len: usize,
+        f: F,
+    ) -> Result<(), SequenceExceedsBoundsError>
+    where
+        F: FnMut() -> T,
+    {
+        if new_len > N {
+            return Err(SequenceExceedsBoundsError {
+                len: new_len,
+                upper_bound: N,
+            });
+        }
+        self.inner.resize_with(new_len, f);
+        Ok(())
+    }
+}
+
 // ========================= impl for SequenceIterator =========================
 
 impl<T: SequenceAlloc> Iterator for SequenceIterator<T> {
@@ -477,7 +1504,7 @@ impl<T: SequenceAlloc> Iterator for SequenceIterator<T> {
             let ptr = self.seq.data.add(self.idx);
             let elem = ptr.read();
             // Need to make sure that dropping the sequence later will not fini() the elements
-            ptr.write(std::mem::zeroed::<T>());
+            ptr.write(core::mem::zeroed::<T>());
             elem
         };
         self.idx += 1;
@@ -485,21 +1512,79 @@ impl<T: SequenceAlloc> Iterator for SequenceIterator<T> {
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let len = (self.seq.size + 1) - self.idx;
+        let len = self.seq.size - self.idx;
         (len, Some(len))
     }
 }
 
 impl<T: SequenceAlloc> ExactSizeIterator for SequenceIterator<T> {
     fn len(&self) -> usize {
-        (self.seq.size + 1) - self.idx
+        self.seq.size - self.idx
     }
 }
 
 impl<T: SequenceAlloc> FusedIterator for SequenceIterator<T> {}
 
+// ========================= impl for TruncatedDebug =========================
+
+impl<'a, T: Debug> Debug for TruncatedDebug<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        struct Ellipsis;
+        impl Debug for Ellipsis {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+                f.write_str("...")
+            }
+        }
+
+        let len = self.slice.len();
+        if len <= self.max {
+            return self.slice.fmt(f);
+        }
+        // Doesn't overflow, since max < len
+        let head = (self.max + 1) / 2;
+        let tail = self.max / 2;
+        f.debug_list()
+            .entries(&self.slice[..head])
+            .entry(&Ellipsis)
+            .entries(&self.slice[len - tail..])
+            .finish()?;
+        write!(f, " ({} elements)", len)
+    }
+}
+
+// ========================= impl for TryReserveError =========================
+
+impl Display for TryReserveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self.requested {
+            Some(capacity) => write!(
+                f,
+                "Failed to allocate memory for a sequence with capacity {}",
+                capacity
+            ),
+            None => write!(f, "Requested sequence capacity exceeds usize::MAX"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TryReserveError {}
+
 // ========================= impl for StringExceedsBoundsError =========================
 
+impl SequenceExceedsBoundsError {
+    /// Returns the length that was requested.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the upper bound of the sequence, which the length exceeded.
+    pub fn upper_bound(&self) -> usize {
+        self.upper_bound
+    }
+}
+
 impl Display for SequenceExceedsBoundsError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         write!(
@@ -510,6 +1595,7 @@ impl Display for SequenceExceedsBoundsError {
     }
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for SequenceExceedsBoundsError {}
 
 macro_rules! impl_sequence_alloc_for_primitive_type {
@@ -527,7 +1613,7 @@ macro_rules! impl_sequence_alloc_for_primitive_type {
                     // This allocates space and sets seq.size and seq.capacity to size
                     let ret = $init_func(seq as *mut _, size);
                     // Zero memory, since it will be uninitialized if there is no default value
-                    std::ptr::write_bytes(seq.data, 0u8, size);
+                    core::ptr::write_bytes(seq.data, 0u8, size);
                     ret
                 }
             }
@@ -536,7 +1622,7 @@ macro_rules! impl_sequence_alloc_for_primitive_type {
                 unsafe { $fini_func(seq as *mut _) }
             }
             fn sequence_copy(in_seq: &Sequence<Self>, out_seq: &mut Sequence<Self>) -> bool {
-                let allocation_size = std::mem::size_of::<Self>() * in_seq.size;
+                let allocation_size = core::mem::size_of::<Self>() * in_seq.size;
                 if out_seq.capacity < in_seq.size {
                     // SAFETY: The memory in out_seq.data is owned by C.
                     let data = unsafe { libc::realloc(out_seq.data as *mut _, allocation_size) };
@@ -646,12 +1732,27 @@ impl_sequence_alloc_for_primitive_type!(
 /// let bounded: BoundedSequence<i32, 5> = seq![5 # 1, 2, 3];
 /// assert_eq!(&unbounded[..], &bounded[..])
 /// ```
+///
+/// The macro does not require `Sequence` or `BoundedSequence` to be in scope, so it can be used
+/// directly when filling in e.g. the fields of a message:
+///
+/// ```
+/// # use rosidl_runtime_rs::seq;
+/// let names: rosidl_runtime_rs::BoundedSequence<rosidl_runtime_rs::String, 3> =
+///     seq![3 # "a".into(), "b".into()];
+/// let scores: rosidl_runtime_rs::Sequence<f64> = seq![0.5, 1.0];
+/// assert_eq!(names.len(), scores.len());
+/// ```
+///
+/// Note that sequences themselves can not be elements of sequences, since the ROS interface
+/// definition language does not allow nested sequences. Use a sequence of messages that contain
+/// sequences instead.
 #[macro_export]
 macro_rules! seq {
     [$( $elem:expr ),*] => {
         {
-            let len = seq!(@count_tts $($elem),*);
-            let mut seq = Sequence::new(len);
+            let len = $crate::seq!(@count_tts $($elem),*);
+            let mut seq = $crate::Sequence::new(len);
             let mut i = 0;
             $(
                 seq[i] = $elem;
@@ -663,8 +1764,8 @@ macro_rules! seq {
     };
     [$len:literal # $( $elem:expr ),*] => {
         {
-            let len = seq!(@count_tts $($elem),*);
-            let mut seq = BoundedSequence::<_, $len>::new(len);
+            let len = $crate::seq!(@count_tts $($elem),*);
+            let mut seq = $crate::BoundedSequence::<_, $len>::new(len);
             let mut i = 0;
             $(
                 seq[i] = $elem;
@@ -676,7 +1777,7 @@ macro_rules! seq {
     };
     // https://danielkeep.github.io/tlborm/book/blk-counting.html
     (@replace_expr ($_t:expr, $sub:expr)) => {$sub};
-    (@count_tts $($e:expr),*) => {<[()]>::len(&[$(seq!(@replace_expr ($e, ()))),*])};
+    (@count_tts $($e:expr),*) => {<[()]>::len(&[$($crate::seq!(@replace_expr ($e, ()))),*])};
 }
 
 #[cfg(test)]
@@ -723,4 +1824,557 @@ mod tests {
             seq_1 == seq_2
         }
     }
+
+    quickcheck! {
+        fn test_clone_from_reuses_allocation(xs: Vec<i32>) -> bool {
+            let source = Sequence::from(xs.clone());
+            let mut target = Sequence::<i32>::new(xs.len() + 1);
+            let data = target.data;
+            let capacity = target.capacity;
+            target.clone_from(&source);
+            target.data == data && target.capacity == capacity && target[..] == xs[..]
+        }
+    }
+
+    quickcheck! {
+        fn test_retain_mut(xs: Vec<i32>) -> bool {
+            let mut seq = Sequence::from(xs.clone());
+            let mut f = |x: &mut i32| {
+                *x = x.wrapping_mul(3);
+                *x % 2 == 0
+            };
+            seq.retain_mut(&mut f);
+            // Vec::retain_mut() requires Rust 1.61
+            let expected: Vec<i32> = xs
+                .into_iter()
+                .filter_map(|mut x| if f(&mut x) { Some(x) } else { None })
+                .collect();
+            seq[..] == expected[..]
+        }
+    }
+
+    #[test]
+    fn test_layout_matches_c() {
+        use core::mem::{size_of, MaybeUninit};
+        let seq = MaybeUninit::<Sequence<i32>>::uninit();
+        let base = seq.as_ptr();
+        // SAFETY: addr_of! only computes the field addresses, without reading the fields.
+        let offset_of = |field: *const u8| field as usize - base as usize;
+        let (data, size, capacity) = unsafe {
+            (
+                offset_of(core::ptr::addr_of!((*base).data).cast()),
+                offset_of(core::ptr::addr_of!((*base).size).cast()),
+                offset_of(core::ptr::addr_of!((*base).capacity).cast()),
+            )
+        };
+        assert_eq!(data, 0);
+        assert_eq!(size, size_of::<*mut i32>());
+        assert_eq!(capacity, size_of::<*mut i32>() + size_of::<usize>());
+    }
+
+    #[test]
+    fn test_get_back() {
+        let mut seq = Sequence::from(vec![1, 2, 3]);
+        assert_eq!(seq.get_back(0), Some(&3));
+        assert_eq!(seq.get_back(2), Some(&1));
+        assert_eq!(seq.get_back(3), None);
+        assert_eq!(seq.get_back(usize::MAX), None);
+        *seq.get_back_mut(1).unwrap() = 5;
+        assert_eq!(seq[..], [1, 5, 3]);
+        assert!(Sequence::<i32>::new(0).get_back(0).is_none());
+
+        let mut bounded = BoundedSequence::<i32, 4>::try_from_iter(1..4).unwrap();
+        assert_eq!(bounded.get_back(1), Some(&2));
+        assert_eq!(bounded.get_back_mut(3), None);
+        *bounded.get_back_mut(0).unwrap() = 7;
+        assert_eq!(bounded[..], [1, 2, 7]);
+    }
+
+    #[test]
+    fn test_to_btree_set() {
+        let seq = Sequence::from(vec![4, 1, 4, 4, 2, 1]);
+        assert_eq!(
+            seq.to_btree_set().into_iter().collect::<Vec<_>>(),
+            [1, 2, 4]
+        );
+        // The sequence is unchanged
+        assert_eq!(seq.len(), 6);
+
+        let bounded = BoundedSequence::<i32, 4>::try_from_iter([5, 5, 3]).unwrap();
+        assert_eq!(
+            bounded.to_btree_set().into_iter().collect::<Vec<_>>(),
+            [3, 5]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_to_hash_set() {
+        let seq = Sequence::from(vec![4, 1, 4, 4, 2, 1]);
+        let hash_set = seq.to_hash_set();
+        assert_eq!(hash_set.len(), 3);
+        assert!([1, 2, 4].iter().all(|x| hash_set.contains(x)));
+        // The sequence is unchanged
+        assert_eq!(seq.len(), 6);
+
+        let bounded = BoundedSequence::<i32, 4>::try_from_iter([5, 5, 3]).unwrap();
+        assert_eq!(bounded.to_hash_set(), seq![3, 5].to_hash_set());
+    }
+
+    #[test]
+    fn test_retain_mut_with_strings() {
+        let mut seq = Sequence::<crate::String>::from_fn(4, |i| i.to_string().as_str().into());
+        seq.retain_mut(|s| {
+            let keep = s.to_string() != "1";
+            *s = (s.to_string() + "!").as_str().into();
+            keep
+        });
+        assert_eq!(seq.len(), 3);
+        assert_eq!(seq[..], ["0!", "2!", "3!"].map(crate::String::from));
+        // The slot of the removed string stays allocated
+        assert_eq!(seq.capacity, 4);
+    }
+
+    #[test]
+    fn test_bounded_clone_from_reuses_allocation() {
+        let source = BoundedSequence::<i32, 8>::try_from_iter(1..4).unwrap();
+        let mut target = BoundedSequence::<i32, 8>::new(5);
+        let data = target.inner.data;
+        let capacity = target.inner.capacity;
+        target.clone_from(&source);
+        assert_eq!(target.inner.data, data);
+        assert_eq!(target.inner.capacity, capacity);
+        assert_eq!(target, source);
+    }
+
+    #[test]
+    fn test_debug_truncated() {
+        let seq = Sequence::from_fn(1000, |i| i as i32);
+        assert_eq!(
+            format!("{:?}", seq.debug_truncated(16)),
+            "[0, 1, 2, 3, 4, 5, 6, 7, ..., 992, 993, 994, 995, 996, 997, 998, 999] (1000 elements)"
+        );
+        assert_eq!(
+            format!("{:?}", seq.debug_truncated(3)),
+            "[0, 1, ..., 999] (1000 elements)"
+        );
+        assert_eq!(
+            format!("{:?}", seq.debug_truncated(0)),
+            "[...] (1000 elements)"
+        );
+        let short: Sequence<i32> = seq![1, 2, 3];
+        assert_eq!(format!("{:?}", short.debug_truncated(3)), "[1, 2, 3]");
+    }
+
+    #[test]
+    fn test_try_reserve_failure() {
+        let mut seq: Sequence<u64> = seq![1, 2, 3];
+        // The allocation size overflows
+        assert!(seq.try_reserve(usize::MAX / 4).is_err());
+        // The allocation size is representable, but the allocator can't provide it
+        assert!(seq.try_reserve(isize::MAX as usize / 8 - 3).is_err());
+        assert!(seq.try_reserve(usize::MAX).is_err());
+        assert_eq!(seq, seq![1, 2, 3]);
+        assert!(Sequence::<u64>::try_with_capacity(usize::MAX / 8).is_err());
+
+        seq.try_reserve(5).unwrap();
+        assert_eq!(seq, seq![1, 2, 3]);
+        seq.resize_to_at_least(8);
+        assert_eq!(seq, seq![1, 2, 3, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_extend_allocates_exactly() {
+        let mut seq: Sequence<i32> = seq![1, 2];
+        seq.extend(vec![3, 4, 5]);
+        assert_eq!(seq, seq![1, 2, 3, 4, 5]);
+        assert_eq!(seq.capacity, 5);
+        // The same goes for the iterator of a sequence
+        let mut other: Sequence<i32> = seq![1];
+        let mut iter = seq.clone().into_iter();
+        assert_eq!(iter.len(), 5);
+        iter.next();
+        assert_eq!(iter.size_hint(), (4, Some(4)));
+        other.extend(iter);
+        assert_eq!(other, seq![1, 2, 3, 4, 5]);
+        assert_eq!(other.capacity, 5);
+        // Without an exact size hint, the sequence is shrunk to fit at the end
+        seq.extend((6..20).filter(|x| x % 2 == 0));
+        assert_eq!(seq, seq![1, 2, 3, 4, 5, 6, 8, 10, 12, 14, 16, 18]);
+        assert_eq!(seq.capacity, 12);
+    }
+
+    #[test]
+    fn test_owning_splits() {
+        let strings = |items: &[&str]| -> Sequence<crate::String> {
+            items.iter().map(|&s| crate::String::from(s)).collect()
+        };
+        let seq = strings(&["a", "bb", "ccc", "dddd"]);
+
+        let (first, rest) = seq.into_split_first().unwrap();
+        assert_eq!(first.to_string(), "a");
+        assert_eq!(rest, strings(&["bb", "ccc", "dddd"]));
+
+        let (last, rest) = rest.into_split_last().unwrap();
+        assert_eq!(last.to_string(), "dddd");
+        assert_eq!(rest, strings(&["bb", "ccc"]));
+
+        let (left, right) = rest.clone().into_split_at(1);
+        assert_eq!(left, strings(&["bb"]));
+        assert_eq!(right, strings(&["ccc"]));
+        let (left, right) = rest.into_split_at(2);
+        assert_eq!(left, strings(&["bb", "ccc"]));
+        assert!(right.is_empty());
+
+        assert!(Sequence::<crate::String>::default()
+            .into_split_first()
+            .is_none());
+        assert!(Sequence::<crate::String>::default()
+            .into_split_last()
+            .is_none());
+    }
+
+    #[test]
+    fn test_enumerate_mut() {
+        let mut seq = Sequence::<u32>::new(5);
+        for (i, element) in seq.enumerate_mut() {
+            *element = i as u32 * 10;
+        }
+        assert_eq!(seq, seq![0, 10, 20, 30, 40]);
+        let mut bounded: BoundedSequence<u32, 5> = seq![5 # 1, 1, 1];
+        for (i, element) in bounded.enumerate_mut() {
+            *element += i as u32;
+        }
+        assert_eq!(&bounded[..], &[1, 2, 3]);
+    }
+
+    #[test]
+    fn test_ptr_eq() {
+        let seq: Sequence<i32> = seq![1, 2, 3];
+        let other: Sequence<i32> = seq![1, 2, 3];
+        assert!(seq.ptr_eq(&seq));
+        assert_eq!(seq, other);
+        assert!(!seq.ptr_eq(&other));
+    }
+
+    #[test]
+    fn test_from_fn() {
+        let seq = Sequence::from_fn(5, |i| (i * i) as i32);
+        assert_eq!(&seq[..], &[0, 1, 4, 9, 16]);
+        assert!(Sequence::<i32>::from_fn(0, |_| unreachable!()).is_empty());
+    }
+
+    #[test]
+    fn test_exceeds_bounds_error_accessors() {
+        let err = BoundedSequence::<u8, 3>::try_new(7).unwrap_err();
+        assert_eq!(err.len(), 7);
+        assert_eq!(err.upper_bound(), 3);
+        let copy = err;
+        assert_eq!(copy, err);
+    }
+
+    #[test]
+    fn test_bounded_try_from_iter() {
+        let seq = BoundedSequence::<i32, 4>::try_from_iter(0..4).unwrap();
+        assert_eq!(&seq[..], &[0, 1, 2, 3]);
+        let err = BoundedSequence::<i32, 4>::try_from_iter(0..5).unwrap_err();
+        assert_eq!(err.len, 5);
+        assert_eq!(err.upper_bound, 4);
+        // Does not hang on infinite iterators
+        assert!(BoundedSequence::<i32, 4>::try_from_iter(0..).is_err());
+    }
+
+    // Mirrors the struct generated by rosidl_generator_c for the C type.
+    #[repr(C)]
+    struct CSequence<T> {
+        data: *mut T,
+        size: libc::size_t,
+        capacity: libc::size_t,
+    }
+
+    #[test]
+    fn test_layout_matches_c_sequence() {
+        // A sequence initialized by C can be read from Rust
+        let mut c_seq = CSequence::<i32> {
+            data: core::ptr::null_mut(),
+            size: 0,
+            capacity: 0,
+        };
+        // SAFETY: The C function only requires a valid pointer to a sequence struct.
+        assert!(unsafe {
+            rosidl_runtime_c__int32__Sequence__init(&mut c_seq as *mut _ as *mut _, 3)
+        });
+        assert_eq!((c_seq.size, c_seq.capacity), (3, 3));
+        // SAFETY: The layouts are the same, and the sequence is now owned by Rust.
+        let mut seq: Sequence<i32> = unsafe { core::mem::transmute(c_seq) };
+        assert_eq!(seq.len(), 3);
+        seq.copy_from_slice(&[1, 2, 3]);
+
+        // A sequence initialized by Rust can be read from C
+        let data = seq.as_ptr();
+        // SAFETY: The layouts are the same. The struct is converted back below.
+        let c_seq: CSequence<i32> = unsafe { core::mem::transmute(seq) };
+        assert_eq!(c_seq.data as *const i32, data);
+        assert_eq!((c_seq.size, c_seq.capacity), (3, 3));
+        // SAFETY: data points to size initialized elements.
+        assert_eq!(
+            unsafe { core::slice::from_raw_parts(c_seq.data, c_seq.size) },
+            &[1, 2, 3]
+        );
+        // SAFETY: Hands ownership back to Rust, which frees the memory with the C fini function.
+        drop(unsafe { core::mem::transmute::<CSequence<i32>, Sequence<i32>>(c_seq) });
+
+        let mut c_seq = CSequence::<f64> {
+            data: core::ptr::null_mut(),
+            size: 0,
+            capacity: 0,
+        };
+        // SAFETY: The C function only requires a valid pointer to a sequence struct.
+        assert!(unsafe {
+            rosidl_runtime_c__double__Sequence__init(&mut c_seq as *mut _ as *mut _, 5)
+        });
+        // SAFETY: The layouts are the same, and the sequence is now owned by Rust.
+        let seq: Sequence<f64> = unsafe { core::mem::transmute(c_seq) };
+        assert_eq!(seq.len(), 5);
+        assert_eq!(
+            core::mem::size_of::<Sequence<f64>>(),
+            core::mem::size_of::<CSequence<f64>>()
+        );
+    }
+
+    #[test]
+    fn test_iterate_by_reference() {
+        let mut seq: Sequence<i32> = seq![1, 2, 3];
+        for x in &mut seq {
+            *x *= 10;
+        }
+        let mut sum = 0;
+        for x in &seq {
+            sum += *x;
+        }
+        assert_eq!(sum, 60);
+        let mut bounded = BoundedSequence::<i32, 4>::try_from_iter(1..4).unwrap();
+        for x in &mut bounded {
+            *x += 1;
+        }
+        let collected: Vec<i32> = (&bounded).into_iter().copied().collect();
+        assert_eq!(collected, [2, 3, 4]);
+    }
+
+    #[test]
+    fn test_extend_from_slice() {
+        let mut seq: Sequence<i32> = seq![1, 2, 3];
+        let tail: Vec<i32> = (4..1004).collect();
+        seq.extend_from_slice(&tail);
+        assert_eq!(seq.len(), 1003);
+        assert!(seq.iter().copied().eq(1..1004));
+        // Grown exactly once, to the required size
+        assert_eq!(seq.capacity, 1003);
+
+        // No reallocation when there is enough capacity
+        let mut seq = Sequence::<u8>::try_with_capacity(16).unwrap();
+        let data = seq.as_ptr();
+        seq.extend_from_slice(&[1; 10]);
+        seq.extend_from_slice(&[2; 6]);
+        assert_eq!(seq.as_ptr(), data);
+        assert_eq!(&seq[8..12], &[1, 1, 2, 2]);
+        seq.extend_from_slice(&[]);
+        assert_eq!(seq.len(), 16);
+    }
+
+    #[test]
+    fn test_resize() {
+        let mut seq: Sequence<i32> = seq![1, 2, 3];
+        seq.resize(5, -1);
+        assert_eq!(&seq[..], &[1, 2, 3, -1, -1]);
+        seq.resize(2, -1);
+        assert_eq!(&seq[..], &[1, 2]);
+        // Growing again does not bring back old elements
+        let mut next = 10;
+        seq.resize_with(4, || {
+            next += 1;
+            next
+        });
+        assert_eq!(&seq[..], &[1, 2, 11, 12]);
+    }
+
+    #[test]
+    fn test_bounded_try_resize() {
+        let mut seq = BoundedSequence::<i32, 4>::try_from_iter(1..3).unwrap();
+        // Within bounds
+        seq.try_resize(4, 9).unwrap();
+        assert_eq!(&seq[..], &[1, 2, 9, 9]);
+        // Shrinking
+        seq.try_resize_with(1, || unreachable!()).unwrap();
+        assert_eq!(&seq[..], &[1]);
+        // Exceeding the bound
+        let err = seq.try_resize(5, 0).unwrap_err();
+        assert_eq!((err.len(), err.upper_bound()), (5, 4));
+        assert_eq!(&seq[..], &[1]);
+        let err = seq.try_resize_with(6, || 0).unwrap_err();
+        assert_eq!(err.len(), 6);
+        seq.resize(2, 3);
+        assert_eq!(&seq[..], &[1, 3]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_bounded_resize_panics_beyond_bound() {
+        let mut seq = BoundedSequence::<i32, 2>::new(0);
+        seq.resize(3, 0);
+    }
+
+    #[test]
+    fn test_get_as() {
+        let seq: Sequence<i32> = seq![i32::MIN, 0, i32::MAX];
+        let widened: Vec<i64> = (0..seq.len()).filter_map(|i| seq.get_as(i)).collect();
+        assert_eq!(widened, [-2147483648, 0, 2147483647]);
+        assert_eq!(seq.get_as::<i64>(3), None);
+        let bounded = BoundedSequence::<u8, 2>::try_from_iter([200, 100]).unwrap();
+        assert_eq!(bounded.get_as::<u32>(0), Some(200));
+        assert_eq!(bounded.get_as::<u32>(2), None);
+    }
+
+    #[test]
+    fn test_collect_results() {
+        let ok: Result<Sequence<crate::String>, ()> = ["a", "b", "c"]
+            .iter()
+            .map(|s| Ok(crate::String::from(*s)))
+            .collect();
+        assert_eq!(ok.unwrap().len(), 3);
+        let mut consumed = 0;
+        let err: Result<Sequence<crate::String>, usize> = (0..10)
+            .map(|i| {
+                consumed += 1;
+                if i == 4 {
+                    Err(i)
+                } else {
+                    Ok(crate::String::from("element"))
+                }
+            })
+            .collect();
+        // The strings collected before the error are finalized when the partial sequence is
+        // dropped, and the rest of the iterator is never consumed.
+        assert_eq!(err, Err(4));
+        assert_eq!(consumed, 5);
+    }
+
+    #[test]
+    fn test_take() {
+        let mut seq: Sequence<i32> = seq![1, 2, 3];
+        let data = seq.as_ptr();
+        let taken = seq.take();
+        assert!(seq.is_empty());
+        assert_eq!(seq.capacity, 0);
+        assert_eq!(taken, seq![1, 2, 3]);
+        assert_eq!(taken.as_ptr(), data);
+    }
+
+    #[test]
+    fn test_into_chunks_exact() {
+        let seq = Sequence::from_fn(10, |i| i as f32);
+        let (chunks, remainder) = seq.into_chunks_exact(4);
+        assert_eq!(chunks.len(), 2);
+        let chunks: Vec<_> = chunks.collect();
+        assert_eq!(chunks, [seq![0.0, 1.0, 2.0, 3.0], seq![4.0, 5.0, 6.0, 7.0]]);
+        assert_eq!(remainder, seq![8.0, 9.0]);
+
+        // Chunks can also be taken from the back, and strings are moved
+        let seq: Sequence<crate::String> = seq!["a".into(), "b".into(), "c".into(), "d".into()];
+        let (mut chunks, remainder) = seq.into_chunks_exact(2);
+        assert_eq!(chunks.next_back().unwrap(), seq!["c".into(), "d".into()]);
+        assert_eq!(chunks.next().unwrap(), seq!["a".into(), "b".into()]);
+        assert!(chunks.next().is_none());
+        assert!(remainder.is_empty());
+
+        let (mut chunks, remainder) = Sequence::<i32>::from(vec![1, 2]).into_chunks_exact(3);
+        assert!(chunks.next().is_none());
+        assert_eq!(remainder, seq![1, 2]);
+    }
+
+    #[test]
+    fn test_try_into_array() {
+        let seq: Sequence<crate::String> = seq!["a".into(), "b".into()];
+        let array: [crate::String; 2] = seq.try_into_array().unwrap();
+        assert_eq!(array, ["a", "b"].map(crate::String::from));
+
+        let seq: Sequence<i32> = seq![1, 2, 3];
+        let seq = seq.try_into_array::<4>().unwrap_err();
+        assert_eq!(seq, seq![1, 2, 3]);
+        let seq = seq.try_into_array::<0>().unwrap_err();
+        assert_eq!(seq, seq![1, 2, 3]);
+        let empty: [i32; 0] = Sequence::new(0).try_into_array().unwrap();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_group_runs_by() {
+        let seq: Sequence<i32> = seq![1, 1, 2, 2, 2, 3];
+        assert_eq!(seq.group_runs_by(|a, b| a == b), [0..2, 2..5, 5..6]);
+        // Groups of values that differ by at most one from their neighbor
+        assert_eq!(seq.group_runs_by(|a, b| (a - b).abs() <= 1), vec![0..6]);
+        assert!(Sequence::<i32>::new(0)
+            .group_runs_by(|_, _| true)
+            .is_empty());
+        let bounded = BoundedSequence::<i32, 4>::try_from_iter([5, 6, 6]).unwrap();
+        assert_eq!(bounded.group_runs_by(|a, b| a == b), [0..1, 1..3]);
+    }
+
+    #[test]
+    fn test_windows_map() {
+        let seq: Sequence<i32> = seq![1, 2, 3, 4];
+        assert_eq!(seq.windows_map(2, |w| w[0] + w[1]), [3, 5, 7]);
+        assert_eq!(seq.windows_map(4, |w| w.len()), [4]);
+        assert!(seq.windows_map(5, |w| w.len()).is_empty());
+        let bounded = BoundedSequence::<i32, 4>::try_from_iter([5, 6, 8]).unwrap();
+        assert_eq!(bounded.windows_map(2, |w| w[1] - w[0]), [1, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "window size must be non-zero")]
+    fn test_windows_map_with_zero_size() {
+        let seq: Sequence<i32> = seq![1, 2, 3, 4];
+        seq.windows_map(0, |w| w.len());
+    }
+
+    #[test]
+    fn test_sub_sequence() {
+        let seq: Sequence<i32> = seq![0, 1, 2, 3, 4, 5];
+        let middle = seq.sub_sequence(2..=4);
+        assert_eq!(&middle[..], &[2, 3, 4]);
+        assert!(!middle.ptr_eq(&seq));
+        assert_eq!(seq.sub_sequence(..), seq);
+        assert!(seq.sub_sequence(6..).is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_sub_sequence_panics_out_of_bounds() {
+        let seq: Sequence<i32> = seq![0, 1, 2];
+        seq.sub_sequence(1..4);
+    }
+
+    #[test]
+    fn test_rotate() {
+        let mut seq: Sequence<i32> = seq![0, 1, 2, 3, 4];
+        let data = seq.as_ptr();
+        seq.rotate_left(2);
+        assert_eq!(&seq[..], &[2, 3, 4, 0, 1]);
+        seq.rotate_right(3);
+        assert_eq!(&seq[..], &[4, 0, 1, 2, 3]);
+        assert_eq!(seq.as_ptr(), data);
+        let mut bounded = BoundedSequence::<i32, 5>::try_from_iter(0..3).unwrap();
+        bounded.rotate_left(1);
+        assert_eq!(&bounded[..], &[1, 2, 0]);
+        bounded.rotate_right(1);
+        assert_eq!(&bounded[..], &[0, 1, 2]);
+    }
+
+    quickcheck! {
+        fn test_map(xs: Sequence<i32>) -> bool {
+            let mapped: Sequence<f64> = xs.clone().map(|x| f64::from(x) * 0.5);
+            mapped.len() == xs.len()
+                && xs.iter().zip(mapped.iter()).all(|(&x, &y)| f64::from(x) * 0.5 == y)
+        }
+    }
 }
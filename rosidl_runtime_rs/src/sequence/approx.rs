@@ -0,0 +1,136 @@
+use super::{BoundedSequence, Sequence};
+
+/// How NaN values are treated by the `approx_eq` methods of float sequences.
+///
+/// See e.g. [`Sequence::approx_eq_with()`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NanPolicy {
+    /// A NaN is never equal to anything, including another NaN, like with `==`.
+    NanNotEqual,
+    /// A NaN is equal to another NaN, but to nothing else.
+    NanEqual,
+}
+
+impl Default for NanPolicy {
+    fn default() -> Self {
+        Self::NanNotEqual
+    }
+}
+
+macro_rules! approx_eq_slices {
+    ($fn_name:ident, $float:ty) => {
+        fn $fn_name(
+            lhs: &[$float],
+            rhs: &[$float],
+            epsilon: $float,
+            nan_policy: NanPolicy,
+        ) -> bool {
+            lhs.len() == rhs.len()
+                && lhs.iter().zip(rhs).all(|(&a, &b)| {
+                    if a.is_nan() || b.is_nan() {
+                        nan_policy == NanPolicy::NanEqual && a.is_nan() && b.is_nan()
+                    } else {
+                        // The first check is needed for infinities of the same sign
+                        a == b || (a - b <= epsilon && b - a <= epsilon)
+                    }
+                })
+        }
+    };
+}
+
+approx_eq_slices!(approx_eq_f32, f32);
+approx_eq_slices!(approx_eq_f64, f64);
+
+macro_rules! impl_approx_eq {
+    ($float:ty, $slice_fn:ident) => {
+        impl Sequence<$float> {
+            /// Checks whether the sequence has the same length as `other`, and all elements differ
+            /// by at most `epsilon`.
+            ///
+            /// NaN values are never equal, see [`NanPolicy::NanNotEqual`].
+            ///
+            /// # Example
+            ///
+            /// ```
+            /// # use rosidl_runtime_rs::seq;
+            #[doc = concat!("let seq = seq![0.1 + 0.2, 1.0 as ", stringify!($float), "];")]
+            /// assert!(seq.approx_eq(&[0.3, 1.0], 1e-6));
+            /// assert!(!seq.approx_eq(&[0.3, 1.1], 1e-6));
+            /// assert!(!seq.approx_eq(&[0.3], 1e-6));
+            /// ```
+            pub fn approx_eq(&self, other: &[$float], epsilon: $float) -> bool {
+                self.approx_eq_with(other, epsilon, NanPolicy::NanNotEqual)
+            }
+
+            /// Like [`approx_eq()`](Self::approx_eq), but with a configurable treatment of NaN
+            /// values.
+            pub fn approx_eq_with(
+                &self,
+                other: &[$float],
+                epsilon: $float,
+                nan_policy: NanPolicy,
+            ) -> bool {
+                $slice_fn(self, other, epsilon, nan_policy)
+            }
+        }
+
+        impl<const N: usize> BoundedSequence<$float, N> {
+            /// Checks whether the sequence has the same length as `other`, and all elements differ
+            /// by at most `epsilon`.
+            ///
+            /// NaN values are never equal, see [`NanPolicy::NanNotEqual`].
+            pub fn approx_eq(&self, other: &[$float], epsilon: $float) -> bool {
+                self.approx_eq_with(other, epsilon, NanPolicy::NanNotEqual)
+            }
+
+            /// Like [`approx_eq()`](Self::approx_eq), but with a configurable treatment of NaN
+            /// values.
+            pub fn approx_eq_with(
+                &self,
+                other: &[$float],
+                epsilon: $float,
+                nan_policy: NanPolicy,
+            ) -> bool {
+                $slice_fn(self, other, epsilon, nan_policy)
+            }
+        }
+    };
+}
+
+impl_approx_eq!(f32, approx_eq_f32);
+impl_approx_eq!(f64, approx_eq_f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_approx_eq_tolerance() {
+        let seq = Sequence::<f64>::from(vec![1.0, 2.0, 3.0]);
+        assert!(seq.approx_eq(&[1.0, 2.0, 3.0], 0.0));
+        assert!(seq.approx_eq(&[1.05, 1.95, 3.0], 0.1));
+        assert!(!seq.approx_eq(&[1.2, 2.0, 3.0], 0.1));
+        let seq = Sequence::<f32>::from(vec![f32::INFINITY, -1.0]);
+        assert!(seq.approx_eq(&[f32::INFINITY, -1.001], 0.01));
+        assert!(!seq.approx_eq(&[f32::NEG_INFINITY, -1.0], 0.01));
+        let seq = BoundedSequence::<f32, 2>::try_from(&[0.5, 0.25][..]).unwrap();
+        assert!(seq.approx_eq(&[0.5, 0.26], 0.02));
+    }
+
+    #[test]
+    fn test_approx_eq_length_mismatch() {
+        let seq = Sequence::<f64>::from(vec![1.0, 2.0]);
+        assert!(!seq.approx_eq(&[1.0], 1.0));
+        assert!(!seq.approx_eq(&[1.0, 2.0, 3.0], 1.0));
+        assert!(Sequence::<f64>::default().approx_eq(&[], 0.0));
+    }
+
+    #[test]
+    fn test_approx_eq_nan_policy() {
+        let seq = Sequence::<f64>::from(vec![1.0, f64::NAN]);
+        assert!(!seq.approx_eq(&[1.0, f64::NAN], 0.1));
+        assert!(!seq.approx_eq_with(&[1.0, f64::NAN], 0.1, NanPolicy::NanNotEqual));
+        assert!(seq.approx_eq_with(&[1.0, f64::NAN], 0.1, NanPolicy::NanEqual));
+        assert!(!seq.approx_eq_with(&[1.0, 2.0], f64::INFINITY, NanPolicy::NanEqual));
+    }
+}
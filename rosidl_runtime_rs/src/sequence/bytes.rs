@@ -0,0 +1,55 @@
+use bytes::Bytes;
+
+use super::Sequence;
+
+/// Copies the bytes of the sequence.
+///
+/// A copy is necessary because the memory of the sequence is managed by C.
+impl From<Sequence<u8>> for Bytes {
+    fn from(seq: Sequence<u8>) -> Self {
+        Bytes::copy_from_slice(&seq)
+    }
+}
+
+/// Copies the bytes of the sequence.
+impl From<&Sequence<u8>> for Bytes {
+    fn from(seq: &Sequence<u8>) -> Self {
+        Bytes::copy_from_slice(seq)
+    }
+}
+
+/// Copies the bytes into a new sequence.
+///
+/// For building a sequence from a `&[u8]`, there is `From<&[T]>`.
+///
+/// # Example
+///
+/// ```
+/// # use rosidl_runtime_rs::Sequence;
+/// use bytes::Bytes;
+///
+/// let payload = Bytes::from_static(b"ros");
+/// let seq = Sequence::from(payload.clone());
+/// assert_eq!(Bytes::from(seq), payload);
+/// ```
+impl From<Bytes> for Sequence<u8> {
+    fn from(bytes: Bytes) -> Self {
+        Self::from(&bytes[..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{seq, Sequence};
+    use bytes::Bytes;
+
+    #[test]
+    fn test_round_trip() {
+        let seq: Sequence<u8> = seq![0, 1, 2, 255];
+        let bytes = Bytes::from(&seq);
+        assert_eq!(&bytes[..], &[0, 1, 2, 255]);
+        assert_eq!(Sequence::from(bytes.clone()), seq);
+        assert_eq!(Bytes::from(seq), bytes);
+        assert!(Sequence::from(Bytes::new()).is_empty());
+    }
+}
@@ -0,0 +1,276 @@
+use alloc::vec::Vec;
+use core::ops::Range;
+
+/// A span of elements in a [`SequenceDiff`].
+///
+/// The ranges are indices into the old sequence, i.e. the one that [`Sequence::diff()`][1] is
+/// called on, and the new sequence, i.e. its argument.
+///
+/// [1]: crate::Sequence::diff
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DiffSpan {
+    /// Elements that are in both sequences, i.e. `old[old] == new[new]`.
+    Common {
+        /// The range of the elements in the old sequence.
+        old: Range<usize>,
+        /// The range of the elements in the new sequence.
+        new: Range<usize>,
+    },
+    /// Elements of the old sequence that are not in the new sequence.
+    Removed(Range<usize>),
+    /// Elements of the new sequence that are not in the old sequence.
+    Added(Range<usize>),
+}
+
+/// The difference between two sequences, as returned by [`Sequence::diff()`][1].
+///
+/// The spans are in order, and cover both sequences completely. Removed elements come before the
+/// added elements that replace them.
+///
+/// [1]: crate::Sequence::diff
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SequenceDiff {
+    spans: Vec<DiffSpan>,
+}
+
+impl SequenceDiff {
+    /// Returns the spans of the diff.
+    pub fn spans(&self) -> &[DiffSpan] {
+        &self.spans
+    }
+
+    /// Returns `true` if both sequences are equal.
+    pub fn is_unchanged(&self) -> bool {
+        self.spans
+            .iter()
+            .all(|span| matches!(span, DiffSpan::Common { .. }))
+    }
+
+    /// Returns the number of removed and added elements.
+    ///
+    /// This is the minimal number of single-element removals and insertions that turn the old
+    /// sequence into the new sequence.
+    pub fn edit_distance(&self) -> usize {
+        self.spans
+            .iter()
+            .map(|span| match span {
+                DiffSpan::Common { .. } => 0,
+                DiffSpan::Removed(range) | DiffSpan::Added(range) => range.len(),
+            })
+            .sum()
+    }
+
+    // Appends a span of a single element, merging it into the last span if possible.
+    fn push(&mut self, span: DiffSpan) {
+        match (self.spans.last_mut(), span) {
+            (
+                Some(DiffSpan::Common { old, new }),
+                DiffSpan::Common {
+                    old: next_old,
+                    new: next_new,
+                },
+            ) if old.end == next_old.start && new.end == next_new.start => {
+                old.end = next_old.end;
+                new.end = next_new.end;
+            }
+            (Some(DiffSpan::Removed(range)), DiffSpan::Removed(next))
+            | (Some(DiffSpan::Added(range)), DiffSpan::Added(next))
+                if range.end == next.start =>
+            {
+                range.end = next.end;
+            }
+            (_, span) => self.spans.push(span),
+        }
+    }
+}
+
+// A single step of the edit script.
+#[derive(Clone, Copy)]
+enum Edit {
+    // Keep old[i], which equals new[j].
+    Keep(usize, usize),
+    // Remove old[i].
+    Remove(usize),
+    // Insert new[j].
+    Insert(usize),
+}
+
+// Computes the diff of two slices with Myers' algorithm, see "An O(ND) Difference Algorithm and
+// Its Variations". This takes O((N + M) * D) time and O(D²) memory, where D is the edit distance.
+pub(super) fn diff<T: PartialEq>(old: &[T], new: &[T]) -> SequenceDiff {
+    // The common prefix and suffix are handled without the main algorithm.
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+
+    let mut diff = SequenceDiff::default();
+    let push_common = |diff: &mut SequenceDiff, i: usize, j: usize, len: usize| {
+        if len > 0 {
+            diff.push(DiffSpan::Common {
+                old: i..i + len,
+                new: j..j + len,
+            });
+        }
+    };
+    push_common(&mut diff, 0, 0, prefix);
+    for edit in shortest_edit_script(old_middle, new_middle) {
+        match edit {
+            Edit::Keep(i, j) => push_common(&mut diff, prefix + i, prefix + j, 1),
+            Edit::Remove(i) => diff.push(DiffSpan::Removed(prefix + i..prefix + i + 1)),
+            Edit::Insert(j) => diff.push(DiffSpan::Added(prefix + j..prefix + j + 1)),
+        }
+    }
+    push_common(&mut diff, old.len() - suffix, new.len() - suffix, suffix);
+    diff
+}
+
+// Returns the edits that turn old into new, in order.
+fn shortest_edit_script<T: PartialEq>(old: &[T], new: &[T]) -> Vec<Edit> {
+    let (n, m) = (old.len() as isize, new.len() as isize);
+    let max = n + m;
+    // v[k] is the furthest x on diagonal k = x - y, at index k + max + 1. The extra element
+    // allows reading v[k + 1] for k = max.
+    let mut v = alloc::vec![0isize; 2 * max as usize + 3];
+    let index = |k: isize| (k + max + 1) as usize;
+    // The state of v before each step d, restricted to the diagonals -d - 1..=d + 1
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+    'search: for d in 0..=max {
+        trace.push(v[index(-d - 1)..=index(d + 1)].to_vec());
+        for k in (-d..=d).step_by(2) {
+            let mut x = if k == -d || (k != d && v[index(k - 1)] < v[index(k + 1)]) {
+                // Insertion, i.e. a step down from diagonal k + 1
+                v[index(k + 1)]
+            } else {
+                // Removal, i.e. a step right from diagonal k - 1
+                v[index(k - 1)] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[index(k)] = x;
+            if x >= n && y >= m {
+                break 'search;
+            }
+        }
+    }
+
+    // Walk back from the end through the recorded states
+    let mut edits = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let at = |k: isize| v[(k + d + 1) as usize];
+        let k = x - y;
+        let prev_k = if k == -d || (k != d && at(k - 1) < at(k + 1)) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let prev_x = at(prev_k);
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            x -= 1;
+            y -= 1;
+            edits.push(Edit::Keep(x as usize, y as usize));
+        }
+        if d > 0 {
+            if x == prev_x {
+                edits.push(Edit::Insert(prev_y as usize));
+            } else {
+                edits.push(Edit::Remove(prev_x as usize));
+            }
+        }
+        x = prev_x;
+        y = prev_y;
+    }
+    edits.reverse();
+    edits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Sequence;
+    use alloc::vec;
+
+    fn common(old: Range<usize>, new: Range<usize>) -> DiffSpan {
+        DiffSpan::Common { old, new }
+    }
+
+    #[test]
+    fn equal_sequences() {
+        let seq = Sequence::from(vec![1, 2, 3]);
+        let diff = seq.diff(&[1, 2, 3]);
+        assert_eq!(diff.spans(), [common(0..3, 0..3)]);
+        assert!(diff.is_unchanged());
+        assert_eq!(diff.edit_distance(), 0);
+        assert!(Sequence::<i32>::new(0).diff(&[]).spans().is_empty());
+    }
+
+    #[test]
+    fn single_changes() {
+        let seq = Sequence::from(vec![1, 2, 3, 4]);
+        assert_eq!(
+            seq.diff(&[1, 3, 4, 5]).spans(),
+            [
+                common(0..1, 0..1),
+                DiffSpan::Removed(1..2),
+                common(2..4, 1..3),
+                DiffSpan::Added(3..4),
+            ]
+        );
+        assert_eq!(
+            seq.diff(&[1, 2, 9, 4]).spans(),
+            [
+                common(0..2, 0..2),
+                DiffSpan::Removed(2..3),
+                DiffSpan::Added(2..3),
+                common(3..4, 3..4),
+            ]
+        );
+    }
+
+    #[test]
+    fn disjoint_sequences() {
+        let seq = Sequence::from(vec![1, 2, 3]);
+        let diff = seq.diff(&[4, 5]);
+        assert_eq!(
+            diff.spans(),
+            [DiffSpan::Removed(0..3), DiffSpan::Added(0..2)]
+        );
+        assert_eq!(diff.edit_distance(), 5);
+        assert_eq!(
+            Sequence::<i32>::new(0).diff(&[1, 2]).spans(),
+            [DiffSpan::Added(0..2)]
+        );
+    }
+
+    #[test]
+    fn edit_script_is_minimal() {
+        // The classic example from Myers' paper
+        let seq = Sequence::from(b"ABCABBA".to_vec());
+        let diff = seq.diff(b"CBABAC");
+        assert_eq!(diff.edit_distance(), 5);
+        // Replaying the diff on the old sequence gives the new sequence
+        let mut replayed = vec![];
+        for span in diff.spans() {
+            match span {
+                DiffSpan::Common { old, new } => {
+                    assert_eq!(seq[old.clone()], b"CBABAC"[new.clone()]);
+                    replayed.extend_from_slice(&seq[old.clone()]);
+                }
+                DiffSpan::Removed(_) => {}
+                DiffSpan::Added(new) => replayed.extend_from_slice(&b"CBABAC"[new.clone()]),
+            }
+        }
+        assert_eq!(replayed, b"CBABAC");
+    }
+}
@@ -0,0 +1,225 @@
+use std::io::{self, BufRead, Read, Write};
+
+use super::Sequence;
+
+/// Appends the written bytes to the sequence.
+///
+/// Writing always succeeds and writes the whole buffer, growing the sequence as needed. Like for
+/// [`Sequence::extend_from_slice()`], the capacity is at least doubled when the sequence grows,
+/// so that many small writes are cheap.
+///
+/// # Example
+///
+/// ```
+/// # use rosidl_runtime_rs::Sequence;
+/// use std::io::Write;
+///
+/// let mut bytes = Sequence::<u8>::default();
+/// write!(bytes, "{}, {}", 1, 2).unwrap();
+/// assert_eq!(&bytes[..], b"1, 2");
+/// ```
+impl Write for Sequence<u8> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A reader over the bytes of a sequence, created by [`Sequence::into_reader()`].
+///
+/// This is similar to a [`Cursor`](std::io::Cursor), and reads directly from the sequence without
+/// copying it first.
+pub struct SequenceReader {
+    seq: Sequence<u8>,
+    pos: usize,
+}
+
+impl Sequence<u8> {
+    /// Converts the sequence into a reader over its bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rosidl_runtime_rs::{Sequence, seq};
+    /// use std::io::Read;
+    ///
+    /// let bytes: Sequence<u8> = seq![104, 105];
+    /// let mut text = String::new();
+    /// bytes.into_reader().read_to_string(&mut text).unwrap();
+    /// assert_eq!(text, "hi");
+    /// ```
+    pub fn into_reader(self) -> SequenceReader {
+        SequenceReader { seq: self, pos: 0 }
+    }
+}
+
+impl SequenceReader {
+    /// Returns the number of bytes that have been read so far.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns the underlying sequence.
+    pub fn into_inner(self) -> Sequence<u8> {
+        self.seq
+    }
+
+    // The bytes that have not been read yet
+    fn remaining(&self) -> &[u8] {
+        &self.seq[self.pos..]
+    }
+}
+
+impl Read for SequenceReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.remaining().read(buf)?;
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+impl BufRead for SequenceReader {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        Ok(self.remaining())
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos = (self.pos + amt).min(self.seq.len());
+    }
+}
+
+macro_rules! impl_read_primitive {
+    ($($le_fn:ident, $be_fn:ident, $type:ty);* $(;)?) => {
+        impl Sequence<u8> {
+            $(
+                #[doc = concat!("Decodes a little-endian `", stringify!($type), "` at the given byte offset.")]
+                ///
+                /// Returns `None` when the value would extend past the end of the sequence.
+                pub fn $le_fn(&self, offset: usize) -> Option<$type> {
+                    self.read_array(offset).map(<$type>::from_le_bytes)
+                }
+
+                #[doc = concat!("Decodes a big-endian `", stringify!($type), "` at the given byte offset.")]
+                ///
+                /// Returns `None` when the value would extend past the end of the sequence.
+                pub fn $be_fn(&self, offset: usize) -> Option<$type> {
+                    self.read_array(offset).map(<$type>::from_be_bytes)
+                }
+            )*
+        }
+    };
+}
+
+impl_read_primitive!(
+    read_u16_le, read_u16_be, u16;
+    read_u32_le, read_u32_be, u32;
+    read_u64_le, read_u64_be, u64;
+    read_i16_le, read_i16_be, i16;
+    read_i32_le, read_i32_be, i32;
+    read_i64_le, read_i64_be, i64;
+    read_f32_le, read_f32_be, f32;
+    read_f64_le, read_f64_be, f64;
+);
+
+impl Sequence<u8> {
+    // Helper for the read_*_le() and read_*_be() functions
+    fn read_array<const N: usize>(&self, offset: usize) -> Option<[u8; N]> {
+        let end = offset.checked_add(N)?;
+        self.get(offset..end)?.try_into().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{seq, Sequence};
+    use quickcheck::quickcheck;
+    use std::io::{BufRead, Read, Write};
+
+    #[test]
+    fn test_write_formatted() {
+        let mut seq = Sequence::<u8>::new(0);
+        write!(seq, "x = {}", 42).unwrap();
+        writeln!(seq, ", y = {:.1}", 0.5).unwrap();
+        assert_eq!(&seq[..], b"x = 42, y = 0.5\n");
+    }
+
+    #[test]
+    fn test_small_writes_grow_geometrically() {
+        let mut seq = Sequence::<u8>::new(0);
+        let mut reallocations = 0;
+        for i in 0..1000 {
+            let capacity = seq.capacity;
+            seq.write_all(&[i as u8]).unwrap();
+            if seq.capacity != capacity {
+                reallocations += 1;
+            }
+        }
+        assert_eq!(seq.len(), 1000);
+        // The capacity goes from 1 to 1024 by doubling
+        assert!(reallocations <= 11, "{} reallocations", reallocations);
+    }
+
+    quickcheck! {
+        fn test_write_appends(xs: Vec<u8>, ys: Vec<u8>) -> bool {
+            let mut seq = Sequence::from(xs.clone());
+            seq.write_all(&ys).unwrap();
+            seq[..xs.len()] == xs[..] && seq[xs.len()..] == ys[..]
+        }
+    }
+
+    #[test]
+    fn test_read_to_end() {
+        let seq: Sequence<u8> = seq![0xde, 0xad, 0xbe, 0xef];
+        let mut reader = seq.into_reader();
+        let mut first = [0u8; 1];
+        reader.read_exact(&mut first).unwrap();
+        assert_eq!(first, [0xde]);
+        assert_eq!(reader.position(), 1);
+        let mut rest = Vec::new();
+        assert_eq!(reader.read_to_end(&mut rest).unwrap(), 3);
+        assert_eq!(rest, [0xad, 0xbe, 0xef]);
+        assert_eq!(reader.read(&mut first).unwrap(), 0);
+        assert_eq!(reader.into_inner().len(), 4);
+    }
+
+    #[test]
+    fn test_read_lines() {
+        let seq = Sequence::from(&b"first\nsecond\n"[..]);
+        let lines: Vec<_> = seq.into_reader().lines().map(Result::unwrap).collect();
+        assert_eq!(lines, ["first", "second"]);
+    }
+
+    #[test]
+    fn test_read_little_endian() {
+        let seq: Sequence<u8> = seq![0xff, 0x01, 0x02, 0x03, 0x04];
+        assert_eq!(seq.read_u16_le(1), Some(0x0201));
+        assert_eq!(seq.read_u32_le(1), Some(0x04030201));
+        assert_eq!(seq.read_i16_le(0), Some(0x01ff));
+        let float_seq = Sequence::from(&1.5f64.to_le_bytes()[..]);
+        assert_eq!(float_seq.read_f64_le(0), Some(1.5));
+    }
+
+    #[test]
+    fn test_read_big_endian() {
+        let seq: Sequence<u8> = seq![0xff, 0x01, 0x02, 0x03, 0x04];
+        assert_eq!(seq.read_u16_be(1), Some(0x0102));
+        assert_eq!(seq.read_u32_be(1), Some(0x01020304));
+        assert_eq!(seq.read_i16_be(0), Some(-255));
+        let float_seq = Sequence::from(&(-0.25f32).to_be_bytes()[..]);
+        assert_eq!(float_seq.read_f32_be(0), Some(-0.25));
+    }
+
+    #[test]
+    fn test_read_out_of_bounds() {
+        let seq: Sequence<u8> = seq![1, 2, 3, 4];
+        assert_eq!(seq.read_u32_le(0), Some(0x04030201));
+        assert_eq!(seq.read_u32_le(1), None);
+        assert_eq!(seq.read_u64_be(0), None);
+        assert_eq!(seq.read_u16_le(usize::MAX), None);
+        assert_eq!(Sequence::<u8>::default().read_u16_le(0), None);
+    }
+}
@@ -0,0 +1,67 @@
+use ndarray::{ArrayView1, ArrayViewMut1};
+
+use super::Sequence;
+use crate::traits::SequenceAlloc;
+
+impl<T: SequenceAlloc> Sequence<T> {
+    /// Returns a one-dimensional array view of the sequence.
+    ///
+    /// The view borrows the data of the sequence, no copy is made.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rosidl_runtime_rs::{Sequence, seq};
+    /// let seq: Sequence<f64> = seq![1.0, 2.0, 3.0];
+    /// assert_eq!(seq.as_array().sum(), 6.0);
+    /// ```
+    pub fn as_array(&self) -> ArrayView1<'_, T> {
+        ArrayView1::from(self.as_slice())
+    }
+
+    /// Returns a mutable one-dimensional array view of the sequence.
+    ///
+    /// The view borrows the data of the sequence, no copy is made.
+    pub fn as_array_mut(&mut self) -> ArrayViewMut1<'_, T> {
+        ArrayViewMut1::from(self.as_mut_slice())
+    }
+}
+
+impl<T: SequenceAlloc + Clone> From<ArrayView1<'_, T>> for Sequence<T> {
+    fn from(view: ArrayView1<'_, T>) -> Self {
+        match view.as_slice() {
+            Some(slice) => Self::from(slice),
+            // The view is not contiguous, e.g. because it has a stride
+            None => view.iter().cloned().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{seq, Sequence};
+    use ndarray::{s, Array1};
+
+    #[test]
+    fn test_array_view_borrows_data() {
+        let mut xs: Sequence<f64> = seq![1.0, 2.0, 3.0];
+        assert_eq!(xs.as_array().as_ptr(), xs.as_ptr());
+        xs.as_array_mut().mapv_inplace(|x| x * 2.0);
+        assert_eq!(&xs[..], &[2.0, 4.0, 6.0]);
+    }
+
+    #[test]
+    fn test_scalar_multiplication() {
+        let xs: Sequence<f64> = seq![1.0, -2.0, 0.5];
+        let scaled = &xs.as_array() * 3.0;
+        assert_eq!(scaled, Array1::from(vec![3.0, -6.0, 1.5]));
+        assert_eq!(Sequence::from(scaled.view()), seq![3.0, -6.0, 1.5]);
+    }
+
+    #[test]
+    fn test_from_strided_view() {
+        let array = Array1::from(vec![1.0, 2.0, 3.0, 4.0]);
+        let xs = Sequence::<f64>::from(array.slice(s![..;2]));
+        assert_eq!(&xs[..], &[1.0, 3.0]);
+    }
+}
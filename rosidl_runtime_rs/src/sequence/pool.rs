@@ -0,0 +1,163 @@
+use std::ops::{Deref, DerefMut};
+use std::sync::Mutex;
+
+use super::Sequence;
+use crate::traits::SequenceAlloc;
+
+/// A pool of pre-allocated sequences, for avoiding allocations in steady state.
+///
+/// Creating a [`Sequence`] allocates memory, which can cause jitter in tight loops. A pool
+/// allocates a fixed number of sequences up front and hands them out again and again. As long as
+/// the requested lengths do not exceed the capacity of the pooled sequences, and no more
+/// sequences are in use at the same time than the pool holds, no memory is allocated.
+///
+/// Sequences can be obtained either as a [`PooledSequence`] guard, which is returned to the pool
+/// when it is dropped, or as a plain `Sequence` via [`PooledSequence::into_inner()`] – e.g. for
+/// putting it into a message. In the latter case, give it back with [`SequencePool::release()`].
+///
+/// # Example
+///
+/// ```
+/// # use rosidl_runtime_rs::SequencePool;
+/// let pool = SequencePool::<f64>::new(2, 100);
+/// {
+///     let mut samples = pool.acquire(50);
+///     assert_eq!(samples.len(), 50);
+///     samples.fill(1.0);
+/// } // The sequence is returned to the pool here
+///
+/// // Taking a sequence out of the pool, and giving it back
+/// let samples = pool.acquire(100).into_inner();
+/// pool.release(samples);
+/// ```
+pub struct SequencePool<T: SequenceAlloc> {
+    free: Mutex<Vec<Sequence<T>>>,
+    max_free: usize,
+}
+
+/// A sequence borrowed from a [`SequencePool`], which is returned to the pool when dropped.
+///
+/// Dereferences to the [`Sequence`] itself.
+pub struct PooledSequence<'a, T: SequenceAlloc> {
+    // Only None after into_inner() has taken the sequence out.
+    seq: Option<Sequence<T>>,
+    pool: &'a SequencePool<T>,
+}
+
+impl<T: Default + SequenceAlloc> SequencePool<T> {
+    /// Creates a pool of `count` sequences, each of which can hold `capacity` elements without
+    /// reallocating.
+    pub fn new(count: usize, capacity: usize) -> Self {
+        let free = (0..count).map(|_| Sequence::new(capacity)).collect();
+        Self {
+            free: Mutex::new(free),
+            max_free: count,
+        }
+    }
+
+    /// Hands out a sequence of `len` elements.
+    ///
+    /// The values of the elements are left over from previous uses of the sequence, so they
+    /// should be overwritten before use.
+    ///
+    /// When the pool is exhausted, a new sequence is allocated. When `len` exceeds the capacity
+    /// of the pooled sequence, it is grown, and keeps its new capacity when it is returned.
+    pub fn acquire(&self, len: usize) -> PooledSequence<'_, T> {
+        let pooled = self.free.lock().unwrap().pop();
+        let seq = match pooled {
+            Some(mut seq) => {
+                seq.resize_to_at_least(len);
+                seq
+            }
+            None => Sequence::new(len),
+        };
+        PooledSequence {
+            seq: Some(seq),
+            pool: self,
+        }
+    }
+}
+
+impl<T: SequenceAlloc> SequencePool<T> {
+    /// Returns a sequence to the pool, so that its memory can be reused.
+    ///
+    /// The sequence does not need to have originated from this pool. If the pool is already
+    /// full, the sequence is dropped instead.
+    pub fn release(&self, seq: Sequence<T>) {
+        let mut free = self.free.lock().unwrap();
+        // Never grow the list, so that releasing does not allocate either
+        if free.len() < self.max_free {
+            free.push(seq);
+        }
+    }
+
+    /// Returns the number of sequences that are currently available in the pool.
+    pub fn available(&self) -> usize {
+        self.free.lock().unwrap().len()
+    }
+}
+
+impl<'a, T: SequenceAlloc> PooledSequence<'a, T> {
+    /// Takes the sequence out of the guard, so that it is not returned to the pool on drop.
+    pub fn into_inner(mut self) -> Sequence<T> {
+        self.seq.take().unwrap()
+    }
+}
+
+impl<'a, T: SequenceAlloc> Deref for PooledSequence<'a, T> {
+    type Target = Sequence<T>;
+    fn deref(&self) -> &Self::Target {
+        self.seq.as_ref().unwrap()
+    }
+}
+
+impl<'a, T: SequenceAlloc> DerefMut for PooledSequence<'a, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.seq.as_mut().unwrap()
+    }
+}
+
+impl<'a, T: SequenceAlloc> Drop for PooledSequence<'a, T> {
+    fn drop(&mut self) {
+        if let Some(seq) = self.seq.take() {
+            self.pool.release(seq);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buffers_are_reused_after_warm_up() {
+        let pool = SequencePool::<u8>::new(2, 16);
+        let mut buffers = Vec::with_capacity(2);
+        {
+            let first = pool.acquire(16);
+            let second = pool.acquire(8);
+            buffers.push(first.as_ptr());
+            buffers.push(second.as_ptr());
+        }
+        assert_eq!(pool.available(), 2);
+        for len in [0, 4, 16, 10] {
+            let seq = pool.acquire(len);
+            assert_eq!(seq.len(), len);
+            if len > 0 {
+                assert!(buffers.contains(&seq.as_ptr()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_release_does_not_exceed_pool_size() {
+        let pool = SequencePool::<i32>::new(1, 4);
+        let seq = pool.acquire(4).into_inner();
+        assert_eq!(pool.available(), 0);
+        let extra = pool.acquire(4);
+        pool.release(seq);
+        assert_eq!(pool.available(), 1);
+        drop(extra);
+        assert_eq!(pool.available(), 1);
+    }
+}
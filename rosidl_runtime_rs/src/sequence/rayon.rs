@@ -0,0 +1,105 @@
+use rayon::iter::IntoParallelIterator;
+use rayon::slice::{Iter, IterMut};
+
+use super::{BoundedSequence, Sequence};
+use crate::traits::SequenceAlloc;
+
+impl<T: SequenceAlloc + Sync> Sequence<T> {
+    /// Returns a parallel iterator over the elements of the sequence.
+    ///
+    /// This is the same as calling `par_iter()` on the slice of elements.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rosidl_runtime_rs::{Sequence, seq};
+    /// use rayon::prelude::*;
+    ///
+    /// let ranges: Sequence<f32> = seq![1.0, 2.5, 0.5];
+    /// let max_range = ranges.par_iter().cloned().reduce(|| 0.0, f32::max);
+    /// assert_eq!(max_range, 2.5);
+    /// ```
+    pub fn par_iter(&self) -> Iter<'_, T> {
+        self.as_slice().into_par_iter()
+    }
+}
+
+impl<T: SequenceAlloc + Send> Sequence<T> {
+    /// Returns a parallel iterator that allows modifying each element of the sequence.
+    ///
+    /// This is the same as calling `par_iter_mut()` on the slice of elements.
+    pub fn par_iter_mut(&mut self) -> IterMut<'_, T> {
+        self.as_mut_slice().into_par_iter()
+    }
+}
+
+impl<'data, T: SequenceAlloc + Sync + 'data> IntoParallelIterator for &'data Sequence<T> {
+    type Item = &'data T;
+    type Iter = Iter<'data, T>;
+
+    fn into_par_iter(self) -> Self::Iter {
+        self.par_iter()
+    }
+}
+
+impl<'data, T: SequenceAlloc + Send + 'data> IntoParallelIterator for &'data mut Sequence<T> {
+    type Item = &'data mut T;
+    type Iter = IterMut<'data, T>;
+
+    fn into_par_iter(self) -> Self::Iter {
+        self.par_iter_mut()
+    }
+}
+
+impl<'data, T: SequenceAlloc + Sync + 'data, const N: usize> IntoParallelIterator
+    for &'data BoundedSequence<T, N>
+{
+    type Item = &'data T;
+    type Iter = Iter<'data, T>;
+
+    fn into_par_iter(self) -> Self::Iter {
+        self.inner.par_iter()
+    }
+}
+
+impl<'data, T: SequenceAlloc + Send + 'data, const N: usize> IntoParallelIterator
+    for &'data mut BoundedSequence<T, N>
+{
+    type Item = &'data mut T;
+    type Iter = IterMut<'data, T>;
+
+    fn into_par_iter(self) -> Self::Iter {
+        self.inner.par_iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BoundedSequence, Sequence};
+    use quickcheck::quickcheck;
+    use rayon::prelude::*;
+
+    quickcheck! {
+        fn test_par_iter_sum(xs: Sequence<i32>) -> bool {
+            let expected: i64 = xs.iter().map(|&x| i64::from(x)).sum();
+            let actual: i64 = xs.par_iter().map(|&x| i64::from(x)).sum();
+            expected == actual
+        }
+    }
+
+    quickcheck! {
+        fn test_par_iter_mut(xs: Sequence<i32>) -> bool {
+            let mut ys = xs.clone();
+            ys.par_iter_mut().for_each(|y| *y = y.wrapping_mul(2));
+            xs.iter().zip(ys.iter()).all(|(x, y)| x.wrapping_mul(2) == *y)
+        }
+    }
+
+    quickcheck! {
+        fn test_bounded_par_iter(xs: BoundedSequence<i32, 256>) -> bool {
+            // Goes through IntoParallelRefIterator, since there is no inherent method
+            let count = xs.par_iter().filter(|&&x| x > 0).count();
+            count == xs.iter().filter(|&&x| x > 0).count()
+        }
+    }
+}
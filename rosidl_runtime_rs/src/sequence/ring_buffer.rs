@@ -0,0 +1,218 @@
+use core::fmt::{self, Debug};
+
+use super::Sequence;
+use crate::traits::SequenceAlloc;
+
+/// A fixed-capacity ring buffer that stores its elements in a [`Sequence`].
+///
+/// All memory is allocated when the buffer is created. Pushing an element into a full buffer
+/// overwrites the oldest element, so that a sliding window over a stream of samples can be kept
+/// without allocating per sample. The elements are iterated in insertion order, from oldest to
+/// newest.
+///
+/// # Example
+///
+/// ```
+/// # use rosidl_runtime_rs::{seq, SequenceRingBuffer};
+/// let mut window = SequenceRingBuffer::<f64>::new(3);
+/// for sample in [1.0, 2.0, 3.0, 4.0] {
+///     window.push_overwrite(sample);
+/// }
+/// let mean = window.iter().sum::<f64>() / window.len() as f64;
+/// assert_eq!(mean, 3.0);
+/// // The window can be turned into a sequence for a message without allocating
+/// assert_eq!(window.into_sequence(), seq![2.0, 3.0, 4.0]);
+/// ```
+pub struct SequenceRingBuffer<T: SequenceAlloc> {
+    // Always has `capacity` elements, of which `len` starting at `head` (wrapping around) are in
+    // use. The others hold default or left-over values.
+    seq: Sequence<T>,
+    head: usize,
+    len: usize,
+}
+
+impl<T: Default + SequenceAlloc> SequenceRingBuffer<T> {
+    /// Creates an empty ring buffer that can hold `capacity` elements.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            seq: Sequence::new(capacity),
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// Removes the oldest element and returns it, or `None` if the buffer is empty.
+    pub fn pop_oldest(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        let oldest = core::mem::take(&mut self.seq[self.head]);
+        self.head = (self.head + 1) % self.capacity();
+        self.len -= 1;
+        Some(oldest)
+    }
+}
+
+impl<T: SequenceAlloc> SequenceRingBuffer<T> {
+    /// Returns the maximum number of elements in the buffer.
+    pub fn capacity(&self) -> usize {
+        self.seq.len()
+    }
+
+    /// Returns the number of elements in the buffer.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the buffer contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if the next push will overwrite the oldest element.
+    pub fn is_full(&self) -> bool {
+        self.len == self.capacity()
+    }
+
+    /// Appends an element as the newest element of the buffer.
+    ///
+    /// If the buffer is full, the oldest element is removed and returned. A buffer with a
+    /// capacity of zero returns the element itself.
+    pub fn push_overwrite(&mut self, value: T) -> Option<T> {
+        let capacity = self.capacity();
+        if capacity == 0 {
+            return Some(value);
+        }
+        if self.is_full() {
+            let oldest = core::mem::replace(&mut self.seq[self.head], value);
+            self.head = (self.head + 1) % capacity;
+            return Some(oldest);
+        }
+        self.seq[(self.head + self.len) % capacity] = value;
+        self.len += 1;
+        None
+    }
+
+    /// Removes all elements.
+    ///
+    /// The elements are not dropped until they are overwritten, or the buffer is dropped.
+    pub fn clear(&mut self) {
+        self.head = 0;
+        self.len = 0;
+    }
+
+    /// Returns the elements as two slices, which contain the elements in order when concatenated.
+    ///
+    /// This is analogous to [`VecDeque::as_slices()`][1].
+    ///
+    /// [1]: alloc::collections::VecDeque::as_slices
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let capacity = self.capacity();
+        if self.head + self.len <= capacity {
+            (&self.seq[self.head..self.head + self.len], &[])
+        } else {
+            let wrapped = self.head + self.len - capacity;
+            (&self.seq[self.head..], &self.seq[..wrapped])
+        }
+    }
+
+    /// Returns an iterator over the elements, from oldest to newest.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> {
+        let (front, back) = self.as_slices();
+        front.iter().chain(back.iter())
+    }
+
+    /// Converts the buffer into a sequence with the elements in order, without allocating.
+    ///
+    /// The elements are rotated in place, and the unused capacity stays allocated.
+    pub fn into_sequence(mut self) -> Sequence<T> {
+        self.seq.rotate_left(self.head);
+        // The elements beyond the new length stay allocated, since the C fini functions finalize
+        // all elements up to the capacity.
+        self.seq.size = self.len;
+        self.seq
+    }
+}
+
+impl<T: Debug + SequenceAlloc> Debug for SequenceRingBuffer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    fn contents(buffer: &SequenceRingBuffer<i32>) -> Vec<i32> {
+        buffer.iter().copied().collect()
+    }
+
+    #[test]
+    fn fills_up_to_capacity() {
+        let mut buffer = SequenceRingBuffer::<i32>::new(3);
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.push_overwrite(1), None);
+        assert_eq!(buffer.push_overwrite(2), None);
+        assert!(!buffer.is_full());
+        assert_eq!(buffer.push_overwrite(3), None);
+        assert!(buffer.is_full());
+        assert_eq!(contents(&buffer), [1, 2, 3]);
+    }
+
+    #[test]
+    fn overwrites_oldest_when_full() {
+        let mut buffer = SequenceRingBuffer::<i32>::new(3);
+        let evicted: Vec<_> = (1..=8).filter_map(|i| buffer.push_overwrite(i)).collect();
+        assert_eq!(evicted, [1, 2, 3, 4, 5]);
+        assert_eq!(buffer.len(), 3);
+        assert_eq!(contents(&buffer), [6, 7, 8]);
+        // The elements wrap around the end of the sequence
+        assert_eq!(buffer.as_slices(), (&[6][..], &[7, 8][..]));
+        assert_eq!(buffer.iter().rev().copied().collect::<Vec<_>>(), [8, 7, 6]);
+    }
+
+    #[test]
+    fn pop_and_push_wrap_around() {
+        let mut buffer = SequenceRingBuffer::<i32>::new(3);
+        for i in 1..=3 {
+            buffer.push_overwrite(i);
+        }
+        assert_eq!(buffer.pop_oldest(), Some(1));
+        assert_eq!(buffer.pop_oldest(), Some(2));
+        buffer.push_overwrite(4);
+        buffer.push_overwrite(5);
+        assert_eq!(contents(&buffer), [3, 4, 5]);
+        buffer.clear();
+        assert_eq!(buffer.pop_oldest(), None);
+        buffer.push_overwrite(6);
+        assert_eq!(contents(&buffer), [6]);
+    }
+
+    #[test]
+    fn converts_into_ordered_sequence() {
+        let mut buffer = SequenceRingBuffer::<i32>::new(4);
+        for i in 1..=6 {
+            buffer.push_overwrite(i);
+        }
+        assert_eq!(buffer.into_sequence(), Sequence::from(vec![3, 4, 5, 6]));
+
+        let mut buffer = SequenceRingBuffer::<i32>::new(4);
+        buffer.push_overwrite(1);
+        buffer.push_overwrite(2);
+        buffer.pop_oldest();
+        buffer.push_overwrite(3);
+        assert_eq!(buffer.into_sequence(), Sequence::from(vec![2, 3]));
+    }
+
+    #[test]
+    fn zero_capacity() {
+        let mut buffer = SequenceRingBuffer::<i32>::new(0);
+        assert!(buffer.is_full());
+        assert_eq!(buffer.push_overwrite(1), Some(1));
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.pop_oldest(), None);
+    }
+}
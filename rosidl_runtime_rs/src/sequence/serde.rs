@@ -1,23 +1,39 @@
-use serde::{de::Error, ser::SerializeSeq, Deserialize, Deserializer, Serialize, Serializer};
+use std::any::TypeId;
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem::ManuallyDrop;
 
-use super::{BoundedSequence, Sequence};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::de::{Error, IgnoredAny, SeqAccess, Visitor};
+use serde::ser::{SerializeSeq, SerializeStruct};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{BoundedSequence, Sequence, SequenceExceedsBoundsError};
 use crate::traits::SequenceAlloc;
 
-impl<'de, T: Deserialize<'de> + SequenceAlloc> Deserialize<'de> for Sequence<T> {
+impl<'de, T: Deserialize<'de> + SequenceAlloc + 'static> Deserialize<'de> for Sequence<T> {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
+        if is_byte_type::<T>() {
+            let bytes = deserialize_bytes(deserializer, None)?;
+            return Ok(Self::from(vec_from_bytes::<T>(bytes)));
+        }
         let v: Vec<_> = Deserialize::deserialize(deserializer)?;
         Ok(Self::from(v))
     }
 }
 
-impl<T: Serialize + SequenceAlloc> Serialize for Sequence<T> {
+impl<T: Serialize + SequenceAlloc + 'static> Serialize for Sequence<T> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
+        if let Some(bytes) = as_byte_slice(self) {
+            return serialize_bytes(bytes, serializer);
+        }
         let mut seq = serializer.serialize_seq(Some(self.len()))?;
         for element in self.iter() {
             seq.serialize_element(element)?;
@@ -26,19 +42,173 @@ impl<T: Serialize + SequenceAlloc> Serialize for Sequence<T> {
     }
 }
 
-impl<'de, T: Deserialize<'de> + SequenceAlloc, const N: usize> Deserialize<'de>
+impl<'de, T: Deserialize<'de> + SequenceAlloc + 'static, const N: usize> Deserialize<'de>
     for BoundedSequence<T, N>
 {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let v: Vec<_> = Deserialize::deserialize(deserializer)?;
-        Self::try_from(v).map_err(D::Error::custom)
+        if is_byte_type::<T>() {
+            let bytes = deserialize_bytes(deserializer, Some(N))?;
+            let v = vec_from_bytes::<T>(bytes);
+            // The length has already been checked
+            return Ok(BoundedSequence::try_from(v).unwrap());
+        }
+        deserializer.deserialize_seq(BoundedSequenceVisitor(PhantomData))
     }
 }
 
-impl<T: Serialize + SequenceAlloc, const N: usize> Serialize for BoundedSequence<T, N> {
+// Deserializes at most N elements, so that an overly long input is rejected without allocating
+// memory for all of its elements.
+struct BoundedSequenceVisitor<T, const N: usize>(PhantomData<T>);
+
+impl<'de, T: Deserialize<'de> + SequenceAlloc, const N: usize> Visitor<'de>
+    for BoundedSequenceVisitor<T, N>
+{
+    type Value = BoundedSequence<T, N>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a sequence of at most {} elements", N)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut v = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(N));
+        while v.len() < N {
+            match seq.next_element()? {
+                Some(element) => v.push(element),
+                None => break,
+            }
+        }
+        // Any remaining elements are only counted, for the error message
+        let mut len = v.len();
+        while seq.next_element::<IgnoredAny>()?.is_some() {
+            len += 1;
+        }
+        if len > N {
+            return Err(A::Error::custom(SequenceExceedsBoundsError {
+                len,
+                upper_bound: N,
+            }));
+        }
+        Ok(BoundedSequence::try_from(v).unwrap())
+    }
+}
+
+// Byte sequences are serialized as base64 strings in human-readable formats and as raw bytes in
+// binary formats, which is much more compact than a list of numbers.
+fn is_byte_type<T: 'static>() -> bool {
+    TypeId::of::<T>() == TypeId::of::<u8>()
+}
+
+fn as_byte_slice<T: 'static>(elements: &[T]) -> Option<&[u8]> {
+    if !is_byte_type::<T>() {
+        return None;
+    }
+    // SAFETY: T is u8.
+    Some(unsafe { std::slice::from_raw_parts(elements.as_ptr() as *const u8, elements.len()) })
+}
+
+// Must only be called if is_byte_type::<T>() is true.
+fn vec_from_bytes<T: 'static>(bytes: Vec<u8>) -> Vec<T> {
+    assert!(is_byte_type::<T>());
+    let mut bytes = ManuallyDrop::new(bytes);
+    // SAFETY: T is u8, and the vector is not dropped, so its buffer is only owned by the result.
+    unsafe { Vec::from_raw_parts(bytes.as_mut_ptr() as *mut T, bytes.len(), bytes.capacity()) }
+}
+
+fn serialize_bytes<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&BASE64.encode(bytes))
+    } else {
+        serializer.serialize_bytes(bytes)
+    }
+}
+
+fn deserialize_bytes<'de, D>(deserializer: D, max_len: Option<usize>) -> Result<Vec<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let visitor = BytesVisitor { max_len };
+    if deserializer.is_human_readable() {
+        // Human-readable formats are self-describing, and this also accepts lists of numbers.
+        deserializer.deserialize_any(visitor)
+    } else {
+        deserializer.deserialize_byte_buf(visitor)
+    }
+}
+
+// Accepts base64 strings, bytes and lists of numbers, with an optional upper bound on the length.
+struct BytesVisitor {
+    max_len: Option<usize>,
+}
+
+impl BytesVisitor {
+    fn check_len<E: Error>(&self, len: usize) -> Result<(), E> {
+        match self.max_len {
+            Some(upper_bound) if len > upper_bound => {
+                Err(E::custom(SequenceExceedsBoundsError { len, upper_bound }))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a base64 string or bytes")?;
+        if let Some(max_len) = self.max_len {
+            write!(f, " of at most {} bytes", max_len)?;
+        }
+        Ok(())
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        let bytes = BASE64
+            .decode(v)
+            .map_err(|e| E::custom(format_args!("invalid base64 string: {}", e)))?;
+        self.check_len(bytes.len())?;
+        Ok(bytes)
+    }
+
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        self.check_len(v.len())?;
+        Ok(v.to_vec())
+    }
+
+    fn visit_byte_buf<E: Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        self.check_len(v.len())?;
+        Ok(v)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let max_len = self.max_len.unwrap_or(usize::MAX);
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(max_len));
+        // Bytes beyond the upper bound are only counted, for the error message
+        let mut len = 0;
+        while let Some(byte) = seq.next_element()? {
+            if len < max_len {
+                bytes.push(byte);
+            }
+            len += 1;
+        }
+        self.check_len(len)?;
+        Ok(bytes)
+    }
+}
+
+impl<T: Serialize + SequenceAlloc + 'static, const N: usize> Serialize for BoundedSequence<T, N> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
@@ -47,6 +217,44 @@ impl<T: Serialize + SequenceAlloc, const N: usize> Serialize for BoundedSequence
     }
 }
 
+/// Serializes a [`BoundedSequence`] together with its upper bound.
+///
+/// A `BoundedSequence` on its own is serialized just like a `Sequence`, i.e. as a plain list of
+/// elements. This wrapper instead serializes it as a struct with an `upper_bound` and a `data`
+/// field, which retains the bound in self-describing formats such as JSON. It is created with
+/// [`BoundedSequence::with_bound()`].
+///
+/// This is only meant for output, e.g. for tooling. There is no corresponding `Deserialize` impl.
+pub struct WithBound<'a, T: SequenceAlloc, const N: usize>(&'a BoundedSequence<T, N>);
+
+impl<T: SequenceAlloc, const N: usize> BoundedSequence<T, N> {
+    /// Returns a wrapper that serializes the sequence together with its upper bound.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rosidl_runtime_rs::BoundedSequence;
+    /// let seq = BoundedSequence::<i32, 4>::try_from_iter(1..3).unwrap();
+    /// let json = serde_json::to_string(&seq.with_bound()).unwrap();
+    /// assert_eq!(json, r#"{"upper_bound":4,"data":[1,2]}"#);
+    /// ```
+    pub fn with_bound(&self) -> WithBound<'_, T, N> {
+        WithBound(self)
+    }
+}
+
+impl<'a, T: Serialize + SequenceAlloc + 'static, const N: usize> Serialize for WithBound<'a, T, N> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut s = serializer.serialize_struct("BoundedSequence", 2)?;
+        s.serialize_field("upper_bound", &N)?;
+        s.serialize_field("data", &self.0.inner)?;
+        s.end()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{BoundedSequence, Sequence};
@@ -60,6 +268,93 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_bounded_sequence_length_is_enforced() {
+        let seq: BoundedSequence<i32, 3> = serde_json::from_str("[1, 2, 3]").unwrap();
+        assert_eq!(&seq[..], &[1, 2, 3]);
+        let empty: BoundedSequence<i32, 3> = serde_json::from_str("[]").unwrap();
+        assert!(empty.is_empty());
+        let err = serde_json::from_str::<BoundedSequence<i32, 3>>("[1, 2, 3, 4, 5]").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("BoundedSequence with upper bound 3 initialized with len 5"));
+        // Unbounded sequences accept the same input
+        let seq: Sequence<i32> = serde_json::from_str("[1, 2, 3, 4, 5]").unwrap();
+        assert_eq!(seq.len(), 5);
+    }
+
+    #[test]
+    fn test_bounded_sequence_with_bound() {
+        let seq = BoundedSequence::<u8, 8>::try_from_iter(0..2).unwrap();
+        let value = serde_json::to_value(seq.with_bound()).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({ "upper_bound": 8, "data": "AAE=" })
+        );
+        // The plain serialization does not change
+        assert_eq!(
+            serde_json::to_value(&seq).unwrap(),
+            serde_json::json!("AAE=")
+        );
+    }
+
+    #[test]
+    fn test_byte_sequence_is_base64_in_json() {
+        let seq = Sequence::from(&b"ros"[..]);
+        let json = serde_json::to_string(&seq).unwrap();
+        assert_eq!(json, r#""cm9z""#);
+        let recovered: Sequence<u8> = serde_json::from_str(&json).unwrap();
+        assert_eq!(recovered, seq);
+        // Lists of numbers are still accepted
+        let recovered: Sequence<u8> = serde_json::from_str("[114, 111, 115]").unwrap();
+        assert_eq!(recovered, seq);
+        assert!(serde_json::from_str::<Sequence<u8>>(r#""not base64!""#).is_err());
+        // Other element types are not affected
+        let seq: Sequence<i8> = Sequence::from(vec![1, 2]);
+        assert_eq!(serde_json::to_string(&seq).unwrap(), "[1,2]");
+    }
+
+    #[test]
+    fn test_byte_sequence_is_bytes_in_bincode() {
+        let seq = Sequence::from(&b"ros"[..]);
+        let encoded = bincode::serialize(&seq).unwrap();
+        // A u64 length prefix followed by the raw bytes
+        assert_eq!(encoded, [3, 0, 0, 0, 0, 0, 0, 0, b'r', b'o', b's']);
+        let recovered: Sequence<u8> = bincode::deserialize(&encoded).unwrap();
+        assert_eq!(recovered, seq);
+    }
+
+    #[test]
+    fn test_bounded_byte_sequence_length_is_enforced() {
+        let seq = BoundedSequence::<u8, 3>::try_from(&b"ros"[..]).unwrap();
+        let json = serde_json::to_string(&seq).unwrap();
+        assert_eq!(
+            serde_json::from_str::<BoundedSequence<u8, 3>>(&json).unwrap(),
+            seq
+        );
+        let encoded = bincode::serialize(&seq).unwrap();
+        assert_eq!(
+            bincode::deserialize::<BoundedSequence<u8, 3>>(&encoded).unwrap(),
+            seq
+        );
+
+        let err = serde_json::from_str::<BoundedSequence<u8, 2>>(&json).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("BoundedSequence with upper bound 2 initialized with len 3"));
+        assert!(serde_json::from_str::<BoundedSequence<u8, 2>>("[1, 2, 3]").is_err());
+        assert!(bincode::deserialize::<BoundedSequence<u8, 2>>(&encoded).is_err());
+    }
+
+    quickcheck! {
+        fn test_roundtrip_byte_sequence(xs: Sequence<u8>) -> bool {
+            let json = serde_json::to_string(&xs).unwrap();
+            let encoded = bincode::serialize(&xs).unwrap();
+            xs == serde_json::from_str::<Sequence<u8>>(&json).unwrap()
+                && xs == bincode::deserialize::<Sequence<u8>>(&encoded).unwrap()
+        }
+    }
+
     quickcheck! {
         fn test_json_roundtrip_bounded_sequence(xs: BoundedSequence<i32, 256>) -> bool {
             let value = serde_json::to_value(xs.clone()).unwrap();
@@ -1,8 +1,13 @@
-use std::cmp::Ordering;
+use alloc::borrow::Cow;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt::{self, Debug, Display};
+use core::hash::{Hash, Hasher};
+use core::ops::{Deref, DerefMut};
+// core::ffi::CStr requires Rust 1.64
+#[cfg(feature = "std")]
 use std::ffi::CStr;
-use std::fmt::{self, Debug, Display};
-use std::hash::{Hash, Hasher};
-use std::ops::{Deref, DerefMut};
 
 #[cfg(feature = "serde")]
 mod serde;
@@ -29,7 +34,8 @@ use crate::traits::SequenceAlloc;
 pub struct String {
     /// Dynamic memory in this type is allocated and deallocated by C, but this is a detail that is managed by
     /// the relevant functions and trait impls.
-    data: *mut libc::c_char,
+    // A `char` in C, but libc::c_char is an alias for core::ffi::c_char, which requires Rust 1.64
+    data: *mut u8,
     size: libc::size_t,
     capacity: libc::size_t,
 }
@@ -50,7 +56,8 @@ pub struct String {
 /// ```
 #[repr(C)]
 pub struct WString {
-    data: *mut libc::c_ushort,
+    // A `uint_least16_t` in C
+    data: *mut u16,
     size: libc::size_t,
     capacity: libc::size_t,
 }
@@ -123,7 +130,7 @@ macro_rules! string_impl {
         impl Default for $string {
             fn default() -> Self {
                 let mut msg = Self {
-                    data: std::ptr::null_mut(),
+                    data: core::ptr::null_mut(),
                     size: 0,
                     capacity: 0,
                 };
@@ -159,7 +166,7 @@ macro_rules! string_impl {
             fn deref(&self) -> &Self::Target {
                 // SAFETY: self.data points to self.size consecutive, initialized elements and
                 // isn't modified externally.
-                unsafe { std::slice::from_raw_parts(self.data as *const $char_type, self.size) }
+                unsafe { core::slice::from_raw_parts(self.data as *const $char_type, self.size) }
             }
         }
 
@@ -167,13 +174,13 @@ macro_rules! string_impl {
             fn deref_mut(&mut self) -> &mut Self::Target {
                 // SAFETY: self.data points to self.size consecutive, initialized elements and
                 // isn't modified externally.
-                unsafe { std::slice::from_raw_parts_mut(self.data as *mut $char_type, self.size) }
+                unsafe { core::slice::from_raw_parts_mut(self.data as *mut $char_type, self.size) }
             }
         }
 
         impl Display for $string {
             fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
-                let converted = std::string::String::$string_conversion_func(self.deref());
+                let converted = alloc::string::String::$string_conversion_func(self.deref());
                 Display::fmt(&converted, f)
             }
         }
@@ -249,7 +256,7 @@ string_impl!(
 );
 string_impl!(
     WString,
-    libc::c_ushort,
+    u16,
     from_utf16_lossy,
     rosidl_runtime_c__U16String__init,
     rosidl_runtime_c__U16String__fini,
@@ -262,7 +269,7 @@ string_impl!(
 impl From<&str> for String {
     fn from(s: &str) -> Self {
         let mut msg = Self {
-            data: std::ptr::null_mut(),
+            data: core::ptr::null_mut(),
             size: 0,
             capacity: 0,
         };
@@ -277,11 +284,42 @@ impl From<&str> for String {
     }
 }
 
+impl From<alloc::string::String> for String {
+    fn from(s: alloc::string::String) -> Self {
+        Self::from(s.as_str())
+    }
+}
+
 impl String {
+    /// Returns the contents as a `&str`, if they are valid UTF-8.
+    ///
+    /// ROS strings are nominally UTF-8, but this is not enforced. Interior null bytes are
+    /// allowed and included in the result.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rosidl_runtime_rs::String;
+    /// let s = String::from("Grüß Gott!");
+    /// assert_eq!(s.as_str(), Ok("Grüß Gott!"));
+    /// ```
+    pub fn as_str(&self) -> Result<&str, core::str::Utf8Error> {
+        core::str::from_utf8(self)
+    }
+
+    /// Returns the contents as a `&str` if possible, and otherwise converts invalid UTF-8
+    /// sequences to `U+FFFD REPLACEMENT CHARACTER`.
+    ///
+    /// See also [`std::string::String::from_utf8_lossy()`].
+    pub fn to_string_lossy(&self) -> Cow<'_, str> {
+        alloc::string::String::from_utf8_lossy(self)
+    }
+
     /// Creates a CStr from this String.
     ///
     /// This scales with the length of the string but does not create copy of the string.
     /// See also [`CStr::from_ptr()`].
+    #[cfg(feature = "std")]
     pub fn to_cstr(&self) -> &CStr {
         // SAFETY: self.data is a valid pointer and won't change.
         // Also, the lifetime of the CStr is the same as self, which is correct.
@@ -292,7 +330,7 @@ impl String {
 impl From<&str> for WString {
     fn from(s: &str) -> Self {
         let mut msg = Self {
-            data: std::ptr::null_mut(),
+            data: core::ptr::null_mut(),
             size: 0,
             capacity: 0,
         };
@@ -354,8 +392,8 @@ impl<const N: usize> SequenceAlloc for BoundedString<N> {
         // SAFETY: Transmute of a transparent type to the inner type is fine
         unsafe {
             <String as SequenceAlloc>::sequence_copy(
-                std::mem::transmute::<&Sequence<Self>, &Sequence<String>>(in_seq),
-                std::mem::transmute::<&mut Sequence<Self>, &mut Sequence<String>>(out_seq),
+                core::mem::transmute::<&Sequence<Self>, &Sequence<String>>(in_seq),
+                core::mem::transmute::<&mut Sequence<Self>, &mut Sequence<String>>(out_seq),
             )
         }
     }
@@ -420,8 +458,8 @@ impl<const N: usize> SequenceAlloc for BoundedWString<N> {
         // SAFETY: Transmute of a transparent type to the inner type is fine
         unsafe {
             <WString as SequenceAlloc>::sequence_copy(
-                std::mem::transmute::<&Sequence<Self>, &Sequence<WString>>(in_seq),
-                std::mem::transmute::<&mut Sequence<Self>, &mut Sequence<WString>>(out_seq),
+                core::mem::transmute::<&Sequence<Self>, &Sequence<WString>>(in_seq),
+                core::mem::transmute::<&mut Sequence<Self>, &mut Sequence<WString>>(out_seq),
             )
         }
     }
@@ -456,6 +494,7 @@ impl Display for StringExceedsBoundsError {
     }
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for StringExceedsBoundsError {}
 
 #[cfg(test)]
@@ -490,4 +529,63 @@ mod tests {
             s.as_str().try_into().unwrap()
         }
     }
+
+    #[test]
+    fn string_sequence_clone_and_drop() {
+        let seq: Sequence<String> = ["a", "bb", "ccc"].into_iter().map(String::from).collect();
+        let cloned = seq.clone();
+        drop(seq);
+        assert_eq!(cloned.len(), 3);
+        assert_eq!(cloned[2].to_string(), "ccc");
+    }
+
+    #[test]
+    fn string_sequence_clone_from() {
+        let long: Sequence<String> = ["a", "b", "c", "d"].into_iter().map(String::from).collect();
+        let short: Sequence<String> = ["x", "y"].into_iter().map(String::from).collect();
+        let mut target = long.clone();
+        // Shrinking keeps the allocation, and the excess elements are finalized on drop
+        target.clone_from(&short);
+        assert_eq!(target, short);
+        // Growing reallocates and initializes the new elements
+        target.clone_from(&long);
+        assert_eq!(target, long);
+        let mut bounded = Sequence::<BoundedString<4>>::new(1);
+        bounded.clone_from(&Sequence::new(3));
+        assert_eq!(bounded.len(), 3);
+    }
+
+    #[test]
+    fn bounded_string_enforces_bound() {
+        assert_eq!(BoundedString::<5>::try_from("").unwrap().to_string(), "");
+        assert_eq!(
+            BoundedString::<5>::try_from("hello").unwrap().to_string(),
+            "hello"
+        );
+        let err = BoundedString::<5>::try_from("hello!").unwrap_err();
+        assert_eq!((err.len, err.upper_bound), (6, 5));
+        // The bound counts characters, not bytes
+        assert!(BoundedString::<5>::try_from("Grüße").is_ok());
+        assert!(BoundedWString::<5>::try_from("Grüße").is_ok());
+        assert!(BoundedWString::<4>::try_from("Grüße").is_err());
+    }
+
+    #[test]
+    fn string_str_roundtrip() {
+        for text in ["", "ascii text", "Grüß Gott! 今日は 🦀", "with\0null byte"] {
+            let s = String::from(text);
+            assert_eq!(s.len(), text.len());
+            assert_eq!(s.as_str(), Ok(text));
+            assert_eq!(s.to_string_lossy(), text);
+            assert_eq!(String::from(text.to_owned()), s);
+        }
+    }
+
+    #[test]
+    fn string_invalid_utf8() {
+        let mut s = String::from("abc");
+        s[1] = 0xff;
+        assert!(s.as_str().is_err());
+        assert_eq!(s.to_string_lossy(), "a\u{FFFD}c");
+    }
 }
@@ -15,8 +15,8 @@
 // DISTRIBUTION A. Approved for public release; distribution unlimited.
 // OPSEC #4584.
 //
-use std::borrow::Cow;
-use std::fmt::Debug;
+use alloc::borrow::Cow;
+use core::fmt::Debug;
 
 /// Internal trait that connects a particular `Sequence<T>` instance to generated C functions
 /// that allocate and deallocate memory.